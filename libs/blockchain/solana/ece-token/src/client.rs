@@ -5,9 +5,61 @@ use solana_sdk::{
     sysvar,
 };
 use spl_token;
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+};
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address,
+    instruction::TransferHookInstruction,
+};
 use borsh::BorshSerialize;
 
-use crate::instruction::EceTokenInstruction;
+use crate::{
+    instruction::EceTokenInstruction,
+    merkle_distributor::DistributionTree,
+    proof_of_reserves::HolderSnapshot,
+    state::{
+        TreasuryState, AuthorityKind, FeeRecipient, LockupTier, Role, RoyaltyCreator, TreasuryParameterChange,
+        STAKING_POOL_SEED, STAKE_VAULT_SEED, REWARD_VAULT_SEED, STAKE_ACCOUNT_SEED,
+        GOVERNANCE_SEED, PROPOSAL_SEED, VOTE_RECORD_SEED, FEE_SPLIT_SEED, ROYALTY_SEED,
+        CARD_COLLECTION_SEED, CARD_MINT_SEED, CARD_RECORD_SEED,
+        SUBSCRIPTION_PLAN_SEED, SUBSCRIPTION_SEED,
+        REDEMPTION_QUEUE_SEED, REDEMPTION_ESCROW_SEED, REDEMPTION_TICKET_SEED,
+        PENDING_WITHDRAWAL_SEED, ROLE_REGISTRY_SEED, DENYLIST_SEED,
+        COLLATERAL_REGISTRY_SEED, COLLATERAL_RESERVE_SEED, KYC_RECORD_SEED,
+        RESERVE_ATTESTATION_SEED, DISTRIBUTOR_SEED, DISTRIBUTOR_VAULT_SEED,
+        VESTING_SEED, VESTING_VAULT_SEED, VestingSchedule,
+        REFERRAL_CONFIG_SEED, REFERRAL_VAULT_SEED, REFERRAL_SEED, REFERRER_SEED,
+        TradeOffer, TRADE_OFFER_SEED, TRADE_CARD_VAULT_SEED, TRADE_ECE_VAULT_SEED,
+        MINT_AUTHORITY_SEED, TREASURY_AUTHORITY_SEED,
+    },
+};
+
+/// Net amounts for a mint or redemption after fees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeQuote {
+    /// Amount the user puts in
+    pub gross_amount: u64,
+
+    /// Fee collected into the treasury fee account (in USDC)
+    pub fee: u64,
+
+    /// Amount the user receives, converted to the output mint's decimals
+    pub net_amount: u64,
+}
+
+/// Split of a vesting schedule's ECE at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VestingBalance {
+    /// ECE vested so far, released or not
+    pub vested: u64,
+
+    /// ECE still to vest
+    pub unvested: u64,
+
+    /// Vested ECE a `Release` would pay out now
+    pub releasable: u64,
+}
 
 /// Client helper functions for interacting with the ECE Token program
 pub struct EceTokenClient;
@@ -63,36 +115,47 @@ impl EceTokenClient {
         })
     }
 
-    /// Create instruction to mint ECE tokens
-    pub fn mint_tokens(
+    /// Create instruction for a KYC-verified user to mint ECE into their
+    /// associated token account against their own USDC
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint_for_usdc(
         program_id: &Pubkey,
-        authority: &Pubkey,
+        user: &Pubkey,
         ece_mint: &Pubkey,
-        destination_ece_account: &Pubkey,
         source_usdc_account: &Pubkey,
         treasury_usdc_account: &Pubkey,
         treasury_state_account: &Pubkey,
+        ece_token_program_id: &Pubkey,
         amount: u64,
     ) -> Result<Instruction, Box<dyn std::error::Error>> {
-        let instruction_data = EceTokenInstruction::MintTokens { amount };
+        let destination_ece_account =
+            get_associated_token_address_with_program_id(user, ece_mint, ece_token_program_id);
+        let (kyc_record, _) = Self::find_kyc_record_address(program_id, treasury_state_account, user);
+        let (mint_authority, _) = Self::find_mint_authority_address(program_id);
+        let instruction_data = EceTokenInstruction::MintForUsdc { amount };
 
-        Ok(Instruction {
+        let instruction = Instruction {
             program_id: *program_id,
             accounts: vec![
-                AccountMeta::new(*authority, true),
+                AccountMeta::new(*user, true),
                 AccountMeta::new(*ece_mint, false),
-                AccountMeta::new(*destination_ece_account, false),
+                AccountMeta::new(destination_ece_account, false),
                 AccountMeta::new(*source_usdc_account, false),
                 AccountMeta::new(*treasury_usdc_account, false),
                 AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new(kyc_record, false),
+                AccountMeta::new_readonly(mint_authority, false),
                 AccountMeta::new_readonly(spl_token::id(), false),
                 AccountMeta::new_readonly(sysvar::clock::id(), false),
             ],
             data: instruction_data.try_to_vec()?,
-        })
+        };
+
+        Ok(Self::with_ece_token_program(instruction, ece_token_program_id))
     }
 
     /// Create instruction to burn ECE tokens
+    #[allow(clippy::too_many_arguments)]
     pub fn burn_tokens(
         program_id: &Pubkey,
         authority: &Pubkey,
@@ -103,6 +166,7 @@ impl EceTokenClient {
         treasury_state_account: &Pubkey,
         amount: u64,
     ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (treasury_authority, _) = Self::find_treasury_authority_address(program_id);
         let instruction_data = EceTokenInstruction::BurnTokens { amount };
 
         Ok(Instruction {
@@ -116,12 +180,14 @@ impl EceTokenClient {
                 AccountMeta::new(*treasury_state_account, false),
                 AccountMeta::new_readonly(spl_token::id(), false),
                 AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(treasury_authority, false),
             ],
             data: instruction_data.try_to_vec()?,
         })
     }
 
     /// Create instruction for weekly payout
+    #[allow(clippy::too_many_arguments)]
     pub fn weekly_payout(
         program_id: &Pubkey,
         authority: &Pubkey,
@@ -133,6 +199,8 @@ impl EceTokenClient {
         revenue_amount: u64,
         payout_percentage: u8,
     ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (fee_split, _) = Self::find_fee_split_address(program_id, treasury_state_account);
+        let (treasury_authority, _) = Self::find_treasury_authority_address(program_id);
         let instruction_data = EceTokenInstruction::WeeklyPayout {
             revenue_amount,
             payout_percentage,
@@ -149,11 +217,48 @@ impl EceTokenClient {
                 AccountMeta::new(*ece_mint, false),
                 AccountMeta::new_readonly(spl_token::id(), false),
                 AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(fee_split, false),
+                AccountMeta::new_readonly(treasury_authority, false),
             ],
             data: instruction_data.try_to_vec()?,
         })
     }
 
+    /// Create instruction for weekly payout routed through the fee split table
+    #[allow(clippy::too_many_arguments)]
+    pub fn weekly_payout_with_fee_split(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        company_ece_account: &Pubkey,
+        company_usdc_account: &Pubkey,
+        treasury_usdc_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        recipient_usdc_accounts: &[Pubkey],
+        revenue_amount: u64,
+        payout_percentage: u8,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let mut instruction = Self::weekly_payout(
+            program_id,
+            authority,
+            company_ece_account,
+            company_usdc_account,
+            treasury_usdc_account,
+            treasury_state_account,
+            ece_mint,
+            revenue_amount,
+            payout_percentage,
+        )?;
+
+        instruction.accounts.extend(
+            recipient_usdc_accounts
+                .iter()
+                .map(|account| AccountMeta::new(*account, false)),
+        );
+
+        Ok(instruction)
+    }
+
     /// Create instruction to deposit USDC
     pub fn deposit_usdc(
         program_id: &Pubkey,
@@ -188,6 +293,7 @@ impl EceTokenClient {
         treasury_state_account: &Pubkey,
         amount: u64,
     ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (treasury_authority, _) = Self::find_treasury_authority_address(program_id);
         let instruction_data = EceTokenInstruction::WithdrawUsdc { amount };
 
         Ok(Instruction {
@@ -199,11 +305,49 @@ impl EceTokenClient {
                 AccountMeta::new(*treasury_state_account, false),
                 AccountMeta::new_readonly(spl_token::id(), false),
                 AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(treasury_authority, false),
             ],
             data: instruction_data.try_to_vec()?,
         })
     }
 
+    /// Create instruction to request a withdrawal above the timelock threshold;
+    /// `withdrawal_id` is the treasury's current withdrawal count and `approvers`
+    /// are the additional treasury signers meeting the threshold
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdraw_usdc_timelocked(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_usdc_account: &Pubkey,
+        destination_usdc_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
+        withdrawal_id: u64,
+        amount: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let mut instruction = Self::withdraw_usdc(
+            program_id,
+            authority,
+            treasury_usdc_account,
+            destination_usdc_account,
+            treasury_state_account,
+            amount,
+        )?;
+
+        let (pending_withdrawal, _) =
+            Self::find_pending_withdrawal_address(program_id, treasury_state_account, withdrawal_id);
+        instruction.accounts.extend([
+            AccountMeta::new(pending_withdrawal, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ]);
+        instruction
+            .accounts
+            .extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(instruction)
+    }
+
     /// Create instruction for emergency pause
     pub fn emergency_pause(
         program_id: &Pubkey,
@@ -245,6 +389,7 @@ impl EceTokenClient {
         program_id: &Pubkey,
         authority: &Pubkey,
         treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
         new_signers: Vec<Pubkey>,
         new_threshold: u8,
     ) -> Result<Instruction, Box<dyn std::error::Error>> {
@@ -253,13 +398,2418 @@ impl EceTokenClient {
             new_threshold,
         };
 
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*treasury_state_account, false),
+        ];
+        accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the staking pool address for a treasury
+    pub fn find_staking_pool_address(program_id: &Pubkey, treasury_state_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[STAKING_POOL_SEED, treasury_state_account.as_ref()], program_id)
+    }
+
+    /// Derive the staked ECE vault address for a staking pool
+    pub fn find_stake_vault_address(program_id: &Pubkey, staking_pool: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[STAKE_VAULT_SEED, staking_pool.as_ref()], program_id)
+    }
+
+    /// Derive the reward vault address for a staking pool
+    pub fn find_reward_vault_address(program_id: &Pubkey, staking_pool: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REWARD_VAULT_SEED, staking_pool.as_ref()], program_id)
+    }
+
+    /// Derive a user's stake account address
+    pub fn find_stake_account_address(
+        program_id: &Pubkey,
+        staking_pool: &Pubkey,
+        owner: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[STAKE_ACCOUNT_SEED, staking_pool.as_ref(), owner.as_ref()],
+            program_id,
+        )
+    }
+
+    /// Create instruction to initialize the staking pool
+    pub fn initialize_staking_pool(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        ece_mint: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_token_program_id: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (staking_pool, _) = Self::find_staking_pool_address(program_id, treasury_state_account);
+        let (stake_vault, _) = Self::find_stake_vault_address(program_id, &staking_pool);
+        let (reward_vault, _) = Self::find_reward_vault_address(program_id, &staking_pool);
+        let instruction_data = EceTokenInstruction::InitializeStakingPool;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(staking_pool, false),
+                AccountMeta::new(stake_vault, false),
+                AccountMeta::new(reward_vault, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to fund staking rewards
+    #[allow(clippy::too_many_arguments)]
+    pub fn fund_staking_rewards(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        source_ece_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        amount: u64,
+        duration: i64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (staking_pool, _) = Self::find_staking_pool_address(program_id, treasury_state_account);
+        let (reward_vault, _) = Self::find_reward_vault_address(program_id, &staking_pool);
+        let instruction_data = EceTokenInstruction::FundStakingRewards { amount, duration };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(*source_ece_account, false),
+                AccountMeta::new(staking_pool, false),
+                AccountMeta::new(reward_vault, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to stake ECE
+    #[allow(clippy::too_many_arguments)]
+    pub fn stake(
+        program_id: &Pubkey,
+        staker: &Pubkey,
+        source_ece_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        amount: u64,
+        lockup_tier: LockupTier,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (staking_pool, _) = Self::find_staking_pool_address(program_id, treasury_state_account);
+        let (stake_account, _) = Self::find_stake_account_address(program_id, &staking_pool, staker);
+        let (stake_vault, _) = Self::find_stake_vault_address(program_id, &staking_pool);
+        let instruction_data = EceTokenInstruction::Stake {
+            amount,
+            lockup_tier,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*staker, true),
+                AccountMeta::new(*source_ece_account, false),
+                AccountMeta::new(staking_pool, false),
+                AccountMeta::new(stake_account, false),
+                AccountMeta::new(stake_vault, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to unstake ECE
+    pub fn unstake(
+        program_id: &Pubkey,
+        staker: &Pubkey,
+        destination_ece_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (staking_pool, _) = Self::find_staking_pool_address(program_id, treasury_state_account);
+        let (stake_account, _) = Self::find_stake_account_address(program_id, &staking_pool, staker);
+        let (stake_vault, _) = Self::find_stake_vault_address(program_id, &staking_pool);
+        let instruction_data = EceTokenInstruction::Unstake { amount };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*staker, true),
+                AccountMeta::new(*destination_ece_account, false),
+                AccountMeta::new(staking_pool, false),
+                AccountMeta::new(stake_account, false),
+                AccountMeta::new(stake_vault, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to claim staking rewards
+    pub fn claim_staking_rewards(
+        program_id: &Pubkey,
+        staker: &Pubkey,
+        destination_ece_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (staking_pool, _) = Self::find_staking_pool_address(program_id, treasury_state_account);
+        let (stake_account, _) = Self::find_stake_account_address(program_id, &staking_pool, staker);
+        let (reward_vault, _) = Self::find_reward_vault_address(program_id, &staking_pool);
+        let instruction_data = EceTokenInstruction::ClaimStakingRewards;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*staker, true),
+                AccountMeta::new(*destination_ece_account, false),
+                AccountMeta::new(staking_pool, false),
+                AccountMeta::new(stake_account, false),
+                AccountMeta::new(reward_vault, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the governance config address for a treasury
+    pub fn find_governance_address(program_id: &Pubkey, treasury_state_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[GOVERNANCE_SEED, treasury_state_account.as_ref()], program_id)
+    }
+
+    /// Derive a proposal address from its sequential id
+    pub fn find_proposal_address(program_id: &Pubkey, governance: &Pubkey, proposal_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[PROPOSAL_SEED, governance.as_ref(), &proposal_id.to_le_bytes()],
+            program_id,
+        )
+    }
+
+    /// Derive a voter's vote record address for a proposal
+    pub fn find_vote_record_address(program_id: &Pubkey, proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[VOTE_RECORD_SEED, proposal.as_ref(), voter.as_ref()],
+            program_id,
+        )
+    }
+
+    /// Create instruction to initialize governance
+    pub fn initialize_governance(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        quorum_bps: u16,
+        voting_period: i64,
+        proposal_threshold: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (governance, _) = Self::find_governance_address(program_id, treasury_state_account);
+        let (staking_pool, _) = Self::find_staking_pool_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::InitializeGovernance {
+            quorum_bps,
+            voting_period,
+            proposal_threshold,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(governance, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(staking_pool, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to open a governance proposal
+    pub fn create_proposal(
+        program_id: &Pubkey,
+        proposer: &Pubkey,
+        treasury_state_account: &Pubkey,
+        proposal_id: u64,
+        change: TreasuryParameterChange,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (governance, _) = Self::find_governance_address(program_id, treasury_state_account);
+        let (proposal, _) = Self::find_proposal_address(program_id, &governance, proposal_id);
+        let (staking_pool, _) = Self::find_staking_pool_address(program_id, treasury_state_account);
+        let (stake_account, _) = Self::find_stake_account_address(program_id, &staking_pool, proposer);
+        let instruction_data = EceTokenInstruction::CreateProposal { change };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*proposer, true),
+                AccountMeta::new(governance, false),
+                AccountMeta::new(proposal, false),
+                AccountMeta::new_readonly(stake_account, false),
+                AccountMeta::new_readonly(staking_pool, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to vote on a proposal
+    pub fn cast_vote(
+        program_id: &Pubkey,
+        voter: &Pubkey,
+        treasury_state_account: &Pubkey,
+        proposal: &Pubkey,
+        support: bool,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (governance, _) = Self::find_governance_address(program_id, treasury_state_account);
+        let (vote_record, _) = Self::find_vote_record_address(program_id, proposal, voter);
+        let (staking_pool, _) = Self::find_staking_pool_address(program_id, treasury_state_account);
+        let (stake_account, _) = Self::find_stake_account_address(program_id, &staking_pool, voter);
+        let instruction_data = EceTokenInstruction::CastVote { support };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*voter, true),
+                AccountMeta::new_readonly(governance, false),
+                AccountMeta::new(*proposal, false),
+                AccountMeta::new(vote_record, false),
+                AccountMeta::new_readonly(stake_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to execute a passed proposal
+    pub fn execute_proposal(
+        program_id: &Pubkey,
+        executor: &Pubkey,
+        treasury_state_account: &Pubkey,
+        proposal: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (governance, _) = Self::find_governance_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::ExecuteProposal;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*executor, true),
+                AccountMeta::new_readonly(governance, false),
+                AccountMeta::new(*proposal, false),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the fee split address for a treasury
+    pub fn find_fee_split_address(program_id: &Pubkey, treasury_state_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[FEE_SPLIT_SEED, treasury_state_account.as_ref()], program_id)
+    }
+
+    /// Create instruction to set the payout fee split
+    pub fn set_fee_split(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        recipients: Vec<FeeRecipient>,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (fee_split, _) = Self::find_fee_split_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::SetFeeSplit { recipients };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(fee_split, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to set the company USDC account for weekly payouts
+    pub fn set_company_usdc_account(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        company_usdc_account: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::SetCompanyUsdcAccount;
+
         Ok(Instruction {
             program_id: *program_id,
             accounts: vec![
                 AccountMeta::new(*authority, true),
                 AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(*company_usdc_account, false),
             ],
             data: instruction_data.try_to_vec()?,
         })
     }
+
+    /// Derive the royalty registry address for a card collection
+    pub fn find_royalty_registry_address(program_id: &Pubkey, collection: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ROYALTY_SEED, collection.as_ref()], program_id)
+    }
+
+    /// Create instruction to set a royalty registry
+    pub fn set_royalty_registry(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        collection: &Pubkey,
+        royalty_bps: u16,
+        creators: Vec<RoyaltyCreator>,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (royalty_registry, _) = Self::find_royalty_registry_address(program_id, collection);
+        let instruction_data = EceTokenInstruction::SetRoyaltyRegistry {
+            collection: *collection,
+            royalty_bps,
+            creators,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(royalty_registry, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to settle a card sale with royalties
+    #[allow(clippy::too_many_arguments)]
+    pub fn settle_sale_with_royalties(
+        program_id: &Pubkey,
+        buyer: &Pubkey,
+        seller: &Pubkey,
+        buyer_ece_account: &Pubkey,
+        seller_ece_account: &Pubkey,
+        fee_ece_account: &Pubkey,
+        card_mint: &Pubkey,
+        seller_card_account: &Pubkey,
+        buyer_card_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        card_token_program_id: &Pubkey,
+        creator_ece_accounts: &[Pubkey],
+        price: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (collection, _) = Self::find_card_collection_address(program_id, treasury_state_account);
+        let (card_record, _) = Self::find_card_record_address(program_id, card_mint);
+        let (royalty_registry, _) = Self::find_royalty_registry_address(program_id, &collection);
+        let instruction_data = EceTokenInstruction::SettleSaleWithRoyalties { price };
+
+        let mut accounts = vec![
+            AccountMeta::new(*buyer, true),
+            AccountMeta::new_readonly(*seller, true),
+            AccountMeta::new(*buyer_ece_account, false),
+            AccountMeta::new(*seller_ece_account, false),
+            AccountMeta::new(*fee_ece_account, false),
+            AccountMeta::new_readonly(card_record, false),
+            AccountMeta::new(*seller_card_account, false),
+            AccountMeta::new(*buyer_card_account, false),
+            AccountMeta::new_readonly(royalty_registry, false),
+            AccountMeta::new_readonly(*treasury_state_account, false),
+            AccountMeta::new_readonly(*ece_token_program_id, false),
+            AccountMeta::new_readonly(*card_token_program_id, false),
+            AccountMeta::new_readonly(*ece_mint, false),
+            AccountMeta::new_readonly(*card_mint, false),
+        ];
+        accounts.extend(
+            creator_ece_accounts
+                .iter()
+                .map(|account| AccountMeta::new(*account, false)),
+        );
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the card collection address for a treasury
+    pub fn find_card_collection_address(program_id: &Pubkey, treasury_state_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CARD_COLLECTION_SEED, treasury_state_account.as_ref()], program_id)
+    }
+
+    /// Derive the card NFT mint address for an off-chain card id
+    pub fn find_card_mint_address(program_id: &Pubkey, collection: &Pubkey, card_id: &[u8; 32]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CARD_MINT_SEED, collection.as_ref(), card_id], program_id)
+    }
+
+    /// Derive the card record address for a card mint
+    pub fn find_card_record_address(program_id: &Pubkey, card_mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CARD_RECORD_SEED, card_mint.as_ref()], program_id)
+    }
+
+    /// Create instruction to initialize the card collection
+    pub fn initialize_card_collection(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        mint_fee: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (collection, _) = Self::find_card_collection_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::InitializeCardCollection { mint_fee };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(collection, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to update the card mint fee
+    pub fn set_card_mint_fee(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        mint_fee: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (collection, _) = Self::find_card_collection_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::SetCardMintFee { mint_fee };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(collection, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to mint a card NFT
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint_card(
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        minter: &Pubkey,
+        payer_ece_account: &Pubkey,
+        fee_ece_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        card_id: [u8; 32],
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (collection, _) = Self::find_card_collection_address(program_id, treasury_state_account);
+        let (card_mint, _) = Self::find_card_mint_address(program_id, &collection, &card_id);
+        let (card_record, _) = Self::find_card_record_address(program_id, &card_mint);
+        let recipient_card_account =
+            get_associated_token_address_with_program_id(payer, &card_mint, ece_token_program_id);
+        let instruction_data = EceTokenInstruction::MintCard { card_id };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new(collection, false),
+                AccountMeta::new(card_mint, false),
+                AccountMeta::new(card_record, false),
+                AccountMeta::new(recipient_card_account, false),
+                AccountMeta::new(*payer_ece_account, false),
+                AccountMeta::new(*fee_ece_account, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(*minter, true),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive a subscription plan address
+    pub fn find_subscription_plan_address(program_id: &Pubkey, plan_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[SUBSCRIPTION_PLAN_SEED, &plan_id.to_le_bytes()], program_id)
+    }
+
+    /// Derive a subscriber's subscription address for a plan
+    pub fn find_subscription_address(program_id: &Pubkey, plan: &Pubkey, subscriber: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[SUBSCRIPTION_SEED, plan.as_ref(), subscriber.as_ref()],
+            program_id,
+        )
+    }
+
+    /// Create instruction to create a subscription plan
+    pub fn create_subscription_plan(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        plan_id: u64,
+        price: u64,
+        period: i64,
+        grace_period: i64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (plan, _) = Self::find_subscription_plan_address(program_id, plan_id);
+        let instruction_data = EceTokenInstruction::CreateSubscriptionPlan {
+            plan_id,
+            price,
+            period,
+            grace_period,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(plan, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to subscribe to a plan
+    #[allow(clippy::too_many_arguments)]
+    pub fn subscribe(
+        program_id: &Pubkey,
+        subscriber: &Pubkey,
+        subscriber_ece_account: &Pubkey,
+        fee_ece_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        plan_id: u64,
+        max_periods: u32,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (plan, _) = Self::find_subscription_plan_address(program_id, plan_id);
+        let (subscription, _) = Self::find_subscription_address(program_id, &plan, subscriber);
+        let instruction_data = EceTokenInstruction::Subscribe { max_periods };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*subscriber, true),
+                AccountMeta::new_readonly(plan, false),
+                AccountMeta::new(subscription, false),
+                AccountMeta::new(*subscriber_ece_account, false),
+                AccountMeta::new(*fee_ece_account, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to collect a due subscription period
+    pub fn collect_subscription(
+        program_id: &Pubkey,
+        subscription: &Pubkey,
+        subscriber_ece_account: &Pubkey,
+        fee_ece_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::CollectSubscription;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*subscription, false),
+                AccountMeta::new(*subscriber_ece_account, false),
+                AccountMeta::new(*fee_ece_account, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to cancel a subscription
+    pub fn cancel_subscription(
+        program_id: &Pubkey,
+        subscriber: &Pubkey,
+        subscription: &Pubkey,
+        subscriber_ece_account: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::CancelSubscription;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*subscriber, true),
+                AccountMeta::new(*subscription, false),
+                AccountMeta::new(*subscriber_ece_account, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the redemption queue address for a treasury
+    pub fn find_redemption_queue_address(program_id: &Pubkey, treasury_state_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REDEMPTION_QUEUE_SEED, treasury_state_account.as_ref()], program_id)
+    }
+
+    /// Derive the redemption escrow vault address for a queue
+    pub fn find_redemption_escrow_address(program_id: &Pubkey, queue: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REDEMPTION_ESCROW_SEED, queue.as_ref()], program_id)
+    }
+
+    /// Derive a redemption ticket address from its queue position
+    pub fn find_redemption_ticket_address(program_id: &Pubkey, queue: &Pubkey, ticket_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[REDEMPTION_TICKET_SEED, queue.as_ref(), &ticket_id.to_le_bytes()],
+            program_id,
+        )
+    }
+
+    /// Create instruction to initialize the redemption queue
+    pub fn initialize_redemption_queue(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        ece_mint: &Pubkey,
+        treasury_state_account: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (queue, _) = Self::find_redemption_queue_address(program_id, treasury_state_account);
+        let (escrow_vault, _) = Self::find_redemption_escrow_address(program_id, &queue);
+        let instruction_data = EceTokenInstruction::InitializeRedemptionQueue;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(queue, false),
+                AccountMeta::new(escrow_vault, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to queue a redemption; `ticket_id` is the queue's current tail
+    #[allow(clippy::too_many_arguments)]
+    pub fn queue_redemption(
+        program_id: &Pubkey,
+        owner: &Pubkey,
+        source_ece_account: &Pubkey,
+        destination_usdc_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        ticket_id: u64,
+        amount: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (queue, _) = Self::find_redemption_queue_address(program_id, treasury_state_account);
+        let (ticket, _) = Self::find_redemption_ticket_address(program_id, &queue, ticket_id);
+        let (escrow_vault, _) = Self::find_redemption_escrow_address(program_id, &queue);
+        let instruction_data = EceTokenInstruction::QueueRedemption { amount };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*owner, true),
+                AccountMeta::new(*source_ece_account, false),
+                AccountMeta::new_readonly(*destination_usdc_account, false),
+                AccountMeta::new(queue, false),
+                AccountMeta::new(ticket, false),
+                AccountMeta::new(escrow_vault, false),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to settle the redemption ticket at the queue head
+    pub fn settle_redemption(
+        program_id: &Pubkey,
+        ece_mint: &Pubkey,
+        destination_usdc_account: &Pubkey,
+        treasury_usdc_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ticket_id: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (queue, _) = Self::find_redemption_queue_address(program_id, treasury_state_account);
+        let (ticket, _) = Self::find_redemption_ticket_address(program_id, &queue, ticket_id);
+        let (escrow_vault, _) = Self::find_redemption_escrow_address(program_id, &queue);
+        let (treasury_authority, _) = Self::find_treasury_authority_address(program_id);
+        let instruction_data = EceTokenInstruction::SettleRedemption;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(queue, false),
+                AccountMeta::new(ticket, false),
+                AccountMeta::new(escrow_vault, false),
+                AccountMeta::new(*ece_mint, false),
+                AccountMeta::new(*destination_usdc_account, false),
+                AccountMeta::new(*treasury_usdc_account, false),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(treasury_authority, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to cancel a queued redemption
+    pub fn cancel_redemption(
+        program_id: &Pubkey,
+        owner: &Pubkey,
+        destination_ece_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        ticket_id: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (queue, _) = Self::find_redemption_queue_address(program_id, treasury_state_account);
+        let (ticket, _) = Self::find_redemption_ticket_address(program_id, &queue, ticket_id);
+        let (escrow_vault, _) = Self::find_redemption_escrow_address(program_id, &queue);
+        let instruction_data = EceTokenInstruction::CancelRedemption;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(*destination_ece_account, false),
+                AccountMeta::new(queue, false),
+                AccountMeta::new(ticket, false),
+                AccountMeta::new(escrow_vault, false),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive a timelocked withdrawal address
+    pub fn find_pending_withdrawal_address(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+        withdrawal_id: u64,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                PENDING_WITHDRAWAL_SEED,
+                treasury_state_account.as_ref(),
+                &withdrawal_id.to_le_bytes(),
+            ],
+            program_id,
+        )
+    }
+
+    /// Create instruction to execute a timelocked withdrawal; `approvers` are the
+    /// additional treasury signers meeting the threshold
+    pub fn execute_withdrawal(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        pending_withdrawal: &Pubkey,
+        treasury_usdc_account: &Pubkey,
+        destination_usdc_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (treasury_authority, _) = Self::find_treasury_authority_address(program_id);
+        let instruction_data = EceTokenInstruction::ExecuteWithdrawal;
+
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*pending_withdrawal, false),
+            AccountMeta::new(*treasury_usdc_account, false),
+            AccountMeta::new(*destination_usdc_account, false),
+            AccountMeta::new(*treasury_state_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(treasury_authority, false),
+        ];
+        accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to cancel a timelocked withdrawal
+    pub fn cancel_withdrawal(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        pending_withdrawal: &Pubkey,
+        treasury_state_account: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::CancelWithdrawal;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(*pending_withdrawal, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to configure the withdrawal timelock
+    pub fn set_withdrawal_timelock(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
+        threshold: u64,
+        delay: i64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::SetWithdrawalTimelock { threshold, delay };
+
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*treasury_state_account, false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ];
+        accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to configure the circuit breaker
+    pub fn set_circuit_breaker(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        outflow_window: i64,
+        max_window_outflow_bps: u16,
+        reserve_floor_bps: u16,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::SetCircuitBreaker {
+            outflow_window,
+            max_window_outflow_bps,
+            reserve_floor_bps,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(*treasury_state_account, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to pause the treasury once it is past a circuit breaker limit
+    pub fn trip_circuit_breaker(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::TripCircuitBreaker;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to set per-operation pause flags
+    pub fn set_pause_flags(
+        program_id: &Pubkey,
+        emergency_authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        pause_flags: u16,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::SetPauseFlags { pause_flags };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*emergency_authority, true),
+                AccountMeta::new(*treasury_state_account, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the role registry address for a treasury
+    pub fn find_role_registry_address(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[ROLE_REGISTRY_SEED, treasury_state_account.as_ref()],
+            program_id,
+        )
+    }
+
+    /// Append the role registry to an instruction so a granted role is recognised
+    pub fn with_role_registry(
+        mut instruction: Instruction,
+        treasury_state_account: &Pubkey,
+    ) -> Instruction {
+        let (role_registry, _) =
+            Self::find_role_registry_address(&instruction.program_id, treasury_state_account);
+        instruction.accounts.push(AccountMeta::new_readonly(role_registry, false));
+        instruction
+    }
+
+    /// Create instruction to grant a role; `approvers` are the additional treasury
+    /// signers needed to meet the multisig threshold
+    pub fn grant_role(
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
+        holder: &Pubkey,
+        role: Role,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (role_registry, _) = Self::find_role_registry_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::GrantRole { holder: *holder, role };
+
+        let mut accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(role_registry, false),
+            AccountMeta::new_readonly(*treasury_state_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ];
+        accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to revoke a role; `approvers` are the additional treasury
+    /// signers needed to meet the multisig threshold
+    pub fn revoke_role(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
+        holder: &Pubkey,
+        role: Role,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (role_registry, _) = Self::find_role_registry_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::RevokeRole { holder: *holder, role };
+
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(role_registry, false),
+            AccountMeta::new_readonly(*treasury_state_account, false),
+        ];
+        accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to propose a new emergency or compliance authority; `approvers`
+    /// are only needed when the proposer is not the current holder
+    pub fn propose_authority(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
+        kind: AuthorityKind,
+        new_authority: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::ProposeAuthority {
+            kind,
+            new_authority: *new_authority,
+        };
+
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*treasury_state_account, false),
+        ];
+        accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to accept a proposed authority handover
+    pub fn accept_authority(
+        program_id: &Pubkey,
+        new_authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        kind: AuthorityKind,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::AcceptAuthority { kind };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*new_authority, true),
+                AccountMeta::new(*treasury_state_account, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to cancel a pending authority handover; `approvers` are only
+    /// needed when the canceller is not the current holder
+    pub fn cancel_authority_transfer(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
+        kind: AuthorityKind,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::CancelAuthorityTransfer { kind };
+
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*treasury_state_account, false),
+        ];
+        accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to set the minimum signer threshold
+    pub fn set_min_signer_threshold(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
+        min_signer_threshold: u8,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::SetMinSignerThreshold { min_signer_threshold };
+
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*treasury_state_account, false),
+        ];
+        accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to configure the USDC oracle (pass the default key to disable it)
+    pub fn set_usdc_oracle(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        oracle: &Pubkey,
+        max_deviation_bps: u16,
+        max_staleness: i64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::SetUsdcOracle {
+            oracle: *oracle,
+            max_deviation_bps,
+            max_staleness,
+        };
+
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*treasury_state_account, false),
+        ];
+        if *oracle != Pubkey::default() {
+            accounts.push(AccountMeta::new_readonly(*oracle, false));
+        }
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Append the USDC oracle to a mint or burn instruction
+    pub fn with_usdc_oracle(mut instruction: Instruction, oracle: &Pubkey) -> Instruction {
+        instruction.accounts.push(AccountMeta::new_readonly(*oracle, false));
+        instruction
+    }
+
+    /// Quote the ECE received for minting with `usdc_amount`
+    pub fn quote_mint(treasury_state: &TreasuryState, usdc_amount: u64) -> Option<FeeQuote> {
+        let fee = treasury_state.mint_fee(usdc_amount)?;
+        Some(FeeQuote {
+            gross_amount: usdc_amount,
+            fee,
+            net_amount: treasury_state.usdc_to_ece(usdc_amount.checked_sub(fee)?)?,
+        })
+    }
+
+    /// Quote the USDC received for redeeming `ece_amount`
+    pub fn quote_redeem(treasury_state: &TreasuryState, ece_amount: u64) -> Option<FeeQuote> {
+        let usdc_amount = treasury_state.ece_to_usdc(ece_amount)?;
+        let fee = treasury_state.redeem_fee(usdc_amount)?;
+        Some(FeeQuote {
+            gross_amount: ece_amount,
+            fee,
+            net_amount: usdc_amount.checked_sub(fee)?,
+        })
+    }
+
+    /// Append the treasury fee account to a mint or burn instruction
+    pub fn with_fee_account(mut instruction: Instruction, fee_usdc_account: &Pubkey) -> Instruction {
+        instruction.accounts.push(AccountMeta::new(*fee_usdc_account, false));
+        instruction
+    }
+
+    /// Create instruction to configure mint and redeem fees
+    pub fn set_fees(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        fee_usdc_account: &Pubkey,
+        fee_ece_account: &Pubkey,
+        mint_fee_bps: u16,
+        redeem_fee_bps: u16,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::SetFees {
+            mint_fee_bps,
+            redeem_fee_bps,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(*fee_usdc_account, false),
+                AccountMeta::new_readonly(*fee_ece_account, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the program's mint authority address
+    pub fn find_mint_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], program_id)
+    }
+
+    /// Derive the program's treasury authority address
+    pub fn find_treasury_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[TREASURY_AUTHORITY_SEED], program_id)
+    }
+
+    /// Point every classic SPL Token account of an ECE-only instruction at another token program
+    pub fn with_token_program(mut instruction: Instruction, token_program_id: &Pubkey) -> Instruction {
+        for account in instruction.accounts.iter_mut() {
+            if account.pubkey == spl_token::id() {
+                account.pubkey = *token_program_id;
+            }
+        }
+        instruction
+    }
+
+    /// Append the ECE token program to an instruction that also moves classic USDC
+    /// (mint, burn, weekly payout, redemption settlement)
+    pub fn with_ece_token_program(mut instruction: Instruction, ece_token_program_id: &Pubkey) -> Instruction {
+        if *ece_token_program_id != spl_token::id() {
+            instruction.accounts.push(AccountMeta::new_readonly(*ece_token_program_id, false));
+        }
+        instruction
+    }
+
+    /// Create instruction to create ECE as a Token-2022 mint with metadata;
+    /// a `transfer_fee_bps` of zero leaves out the transfer fee extension and
+    /// `transfer_hook` routes every transfer through this program
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_token_2022(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        mint: &Pubkey,
+        treasury_state_account: &Pubkey,
+        decimals: u8,
+        freeze_authority: Option<Pubkey>,
+        name: String,
+        symbol: String,
+        uri: String,
+        transfer_fee_bps: u16,
+        maximum_fee: u64,
+        transfer_hook: bool,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (mint_authority, _) = Self::find_mint_authority_address(program_id);
+        let instruction_data = EceTokenInstruction::InitializeToken2022 {
+            decimals,
+            freeze_authority,
+            name,
+            symbol,
+            uri,
+            transfer_fee_bps,
+            maximum_fee,
+            transfer_hook,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(*mint, true),
+                AccountMeta::new_readonly(mint_authority, false),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(spl_token_2022::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to sweep withheld ECE transfer fees into the treasury
+    pub fn withdraw_transfer_fees(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        source_accounts: &[Pubkey],
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (treasury_authority, _) = Self::find_treasury_authority_address(program_id);
+        let treasury_ece_account = get_associated_token_address_with_program_id(
+            &treasury_authority,
+            ece_mint,
+            &spl_token_2022::id(),
+        );
+        let instruction_data = EceTokenInstruction::WithdrawTransferFees;
+
+        let mut accounts = vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(*treasury_state_account, false),
+            AccountMeta::new(*ece_mint, false),
+            AccountMeta::new(treasury_ece_account, false),
+            AccountMeta::new_readonly(treasury_authority, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+        ];
+        accounts.extend(source_accounts.iter().map(|source| AccountMeta::new(*source, false)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the treasury's transfer denylist address
+    pub fn find_denylist_address(program_id: &Pubkey, treasury_state_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[DENYLIST_SEED, treasury_state_account.as_ref()], program_id)
+    }
+
+    /// Create instruction to add a wallet to the transfer denylist
+    pub fn add_to_denylist(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        wallet: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (denylist, _) = Self::find_denylist_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::AddToDenylist { wallet: *wallet };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(denylist, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to remove a wallet from the transfer denylist
+    pub fn remove_from_denylist(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        wallet: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (denylist, _) = Self::find_denylist_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::RemoveFromDenylist { wallet: *wallet };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(denylist, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to set up the transfer hook's extra account metas for the ECE mint
+    pub fn initialize_transfer_hook_accounts(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        ece_mint: &Pubkey,
+        treasury_state_account: &Pubkey,
+    ) -> Instruction {
+        let validation_account = get_extra_account_metas_address(ece_mint, program_id);
+        let instruction_data = TransferHookInstruction::InitializeExtraAccountMetaList {
+            extra_account_metas: vec![],
+        };
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(validation_account, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+                AccountMeta::new(*authority, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+            ],
+            data: instruction_data.pack(),
+        }
+    }
+
+    /// Accounts to append to a Token-2022 `transfer_checked` of hooked ECE
+    pub fn transfer_hook_accounts(
+        program_id: &Pubkey,
+        ece_mint: &Pubkey,
+        treasury_state_account: &Pubkey,
+    ) -> Vec<AccountMeta> {
+        let (denylist, _) = Self::find_denylist_address(program_id, treasury_state_account);
+
+        vec![
+            AccountMeta::new_readonly(*treasury_state_account, false),
+            AccountMeta::new_readonly(denylist, false),
+            AccountMeta::new_readonly(*program_id, false),
+            AccountMeta::new_readonly(get_extra_account_metas_address(ece_mint, program_id), false),
+        ]
+    }
+
+    /// Derive the treasury's collateral registry address
+    pub fn find_collateral_registry_address(program_id: &Pubkey, treasury_state_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[COLLATERAL_REGISTRY_SEED, treasury_state_account.as_ref()], program_id)
+    }
+
+    /// Derive the reserve token account address for a collateral mint
+    pub fn find_collateral_reserve_address(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+        collateral_mint: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[COLLATERAL_RESERVE_SEED, treasury_state_account.as_ref(), collateral_mint.as_ref()],
+            program_id,
+        )
+    }
+
+    /// Create instruction to approve a collateral asset; `collateral_token_program_id`
+    /// is the program owning its mint
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_collateral(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        collateral_mint: &Pubkey,
+        ece_mint: &Pubkey,
+        collateral_token_program_id: &Pubkey,
+        cap: u64,
+        haircut_bps: u16,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (collateral_registry, _) = Self::find_collateral_registry_address(program_id, treasury_state_account);
+        let (collateral_reserve, _) =
+            Self::find_collateral_reserve_address(program_id, treasury_state_account, collateral_mint);
+        let (treasury_authority, _) = Self::find_treasury_authority_address(program_id);
+        let instruction_data = EceTokenInstruction::AddCollateral { cap, haircut_bps };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(collateral_registry, false),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(*collateral_mint, false),
+                AccountMeta::new(collateral_reserve, false),
+                AccountMeta::new_readonly(treasury_authority, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(*collateral_token_program_id, false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to update a collateral asset
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_collateral(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        collateral_mint: &Pubkey,
+        cap: u64,
+        haircut_bps: u16,
+        is_active: bool,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (collateral_registry, _) = Self::find_collateral_registry_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::UpdateCollateral {
+            mint: *collateral_mint,
+            cap,
+            haircut_bps,
+            is_active,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(collateral_registry, false),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to mint ECE against an approved collateral asset
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint_with_collateral(
+        program_id: &Pubkey,
+        user: &Pubkey,
+        treasury_state_account: &Pubkey,
+        collateral_mint: &Pubkey,
+        ece_mint: &Pubkey,
+        collateral_token_program_id: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (collateral_registry, _) = Self::find_collateral_registry_address(program_id, treasury_state_account);
+        let (collateral_reserve, _) =
+            Self::find_collateral_reserve_address(program_id, treasury_state_account, collateral_mint);
+        let (mint_authority, _) = Self::find_mint_authority_address(program_id);
+        let (kyc_record, _) = Self::find_kyc_record_address(program_id, treasury_state_account, user);
+        let source_collateral_account =
+            get_associated_token_address_with_program_id(user, collateral_mint, collateral_token_program_id);
+        let destination_ece_account =
+            get_associated_token_address_with_program_id(user, ece_mint, ece_token_program_id);
+        let instruction_data = EceTokenInstruction::MintWithCollateral { amount };
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(source_collateral_account, false),
+            AccountMeta::new_readonly(*collateral_mint, false),
+            AccountMeta::new(collateral_reserve, false),
+            AccountMeta::new(collateral_registry, false),
+            AccountMeta::new(*ece_mint, false),
+            AccountMeta::new(destination_ece_account, false),
+            AccountMeta::new_readonly(mint_authority, false),
+            AccountMeta::new(*treasury_state_account, false),
+            AccountMeta::new(kyc_record, false),
+            AccountMeta::new_readonly(*collateral_token_program_id, false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ];
+        if ece_token_program_id != collateral_token_program_id {
+            accounts.push(AccountMeta::new_readonly(*ece_token_program_id, false));
+        }
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to configure the treasury's ECE and USDC mints
+    pub fn configure_mints(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        usdc_mint: &Pubkey,
+        usdc_reserve: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::ConfigureMints;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+                AccountMeta::new_readonly(*usdc_mint, false),
+                AccountMeta::new_readonly(*usdc_reserve, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to mint ECE against surplus reserves (minter role)
+    pub fn mint_from_reserves(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        ece_mint: &Pubkey,
+        destination_ece_account: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (mint_authority, _) = Self::find_mint_authority_address(program_id);
+        let instruction_data = EceTokenInstruction::MintFromReserves { amount };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(*ece_mint, false),
+                AccountMeta::new(*destination_ece_account, false),
+                AccountMeta::new_readonly(mint_authority, false),
+                AccountMeta::new(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive a wallet's KYC record address
+    pub fn find_kyc_record_address(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+        wallet: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[KYC_RECORD_SEED, treasury_state_account.as_ref(), wallet.as_ref()],
+            program_id,
+        )
+    }
+
+    /// Create instruction to set a wallet's KYC status
+    pub fn set_kyc_status(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        wallet: &Pubkey,
+        verified: bool,
+        expires_at: i64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (kyc_record, _) = Self::find_kyc_record_address(program_id, treasury_state_account, wallet);
+        let instruction_data = EceTokenInstruction::SetKycStatus {
+            wallet: *wallet,
+            verified,
+            expires_at,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(kyc_record, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to set the self-service and admin mint limits
+    pub fn set_mint_limits(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        user_mint_limit: u64,
+        user_daily_mint_limit: u64,
+        reserve_mint_limit: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let instruction_data = EceTokenInstruction::SetMintLimits {
+            user_mint_limit,
+            user_daily_mint_limit,
+            reserve_mint_limit,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(*treasury_state_account, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the treasury's proof-of-reserves attestation address
+    pub fn find_reserve_attestation_address(program_id: &Pubkey, treasury_state_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[RESERVE_ATTESTATION_SEED, treasury_state_account.as_ref()], program_id)
+    }
+
+    /// Create instruction to publish a holder balance snapshot built with `HolderSnapshot`
+    pub fn publish_reserve_snapshot(
+        program_id: &Pubkey,
+        auditor: &Pubkey,
+        treasury_state_account: &Pubkey,
+        snapshot: &HolderSnapshot,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (attestation, _) = Self::find_reserve_attestation_address(program_id, treasury_state_account);
+        let instruction_data = EceTokenInstruction::PublishReserveSnapshot {
+            merkle_root: snapshot.root(),
+            total_balances: snapshot.total(),
+            holder_count: snapshot.holder_count(),
+            snapshot_slot: snapshot.slot,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*auditor, true),
+                AccountMeta::new(attestation, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive a merkle distributor's address
+    pub fn find_distributor_address(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+        distributor_id: u64,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[DISTRIBUTOR_SEED, treasury_state_account.as_ref(), &distributor_id.to_le_bytes()],
+            program_id,
+        )
+    }
+
+    /// Derive a merkle distributor's vault address
+    pub fn find_distributor_vault_address(program_id: &Pubkey, distributor: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[DISTRIBUTOR_VAULT_SEED, distributor.as_ref()], program_id)
+    }
+
+    /// Create instruction to create and fund a merkle distributor from a `DistributionTree`
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_distributor(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        funding_ece_account: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        distributor_id: u64,
+        tree: &DistributionTree,
+        expires_at: i64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (distributor, _) = Self::find_distributor_address(program_id, treasury_state_account, distributor_id);
+        let (vault, _) = Self::find_distributor_vault_address(program_id, &distributor);
+        let instruction_data = EceTokenInstruction::CreateDistributor {
+            distributor_id,
+            merkle_root: tree.root(),
+            total_amount: tree.total_amount().ok_or("distribution total overflows")?,
+            num_claims: tree.num_claims(),
+            expires_at,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(distributor, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+                AccountMeta::new(*funding_ece_account, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to claim from a merkle distributor into the claimant's ATA
+    #[allow(clippy::too_many_arguments)]
+    pub fn claim(
+        program_id: &Pubkey,
+        claimant: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        distributor_id: u64,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (distributor, _) = Self::find_distributor_address(program_id, treasury_state_account, distributor_id);
+        let (vault, _) = Self::find_distributor_vault_address(program_id, &distributor);
+        let claimant_ece_account =
+            get_associated_token_address_with_program_id(claimant, ece_mint, ece_token_program_id);
+        let instruction_data = EceTokenInstruction::Claim { index, amount, proof };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*claimant, true),
+                AccountMeta::new(distributor, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(claimant_ece_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to claw back an expired distributor's unclaimed ECE
+    pub fn clawback_distributor(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        destination_ece_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        distributor_id: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (distributor, _) = Self::find_distributor_address(program_id, treasury_state_account, distributor_id);
+        let (vault, _) = Self::find_distributor_vault_address(program_id, &distributor);
+        let instruction_data = EceTokenInstruction::ClawbackDistributor;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(distributor, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(*destination_ece_account, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive a vesting schedule's address
+    pub fn find_vesting_address(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+        beneficiary: &Pubkey,
+        vesting_id: u64,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                VESTING_SEED,
+                treasury_state_account.as_ref(),
+                beneficiary.as_ref(),
+                &vesting_id.to_le_bytes(),
+            ],
+            program_id,
+        )
+    }
+
+    /// Derive a vesting schedule's vault address
+    pub fn find_vesting_vault_address(program_id: &Pubkey, vesting: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[VESTING_VAULT_SEED, vesting.as_ref()], program_id)
+    }
+
+    /// Vested, unvested and releasable ECE of a schedule at `timestamp`
+    pub fn vesting_balance(vesting: &VestingSchedule, timestamp: i64) -> VestingBalance {
+        VestingBalance {
+            vested: vesting.vested_amount(timestamp),
+            unvested: vesting.unvested_amount(timestamp),
+            releasable: vesting.releasable_amount(timestamp),
+        }
+    }
+
+    /// Create instruction to create and fund a vesting schedule
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_vesting(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        funding_ece_account: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        vesting_id: u64,
+        beneficiary: &Pubkey,
+        total_amount: u64,
+        start_timestamp: i64,
+        cliff_timestamp: i64,
+        end_timestamp: i64,
+        revocable: bool,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (vesting, _) = Self::find_vesting_address(program_id, treasury_state_account, beneficiary, vesting_id);
+        let (vault, _) = Self::find_vesting_vault_address(program_id, &vesting);
+        let instruction_data = EceTokenInstruction::CreateVesting {
+            vesting_id,
+            beneficiary: *beneficiary,
+            total_amount,
+            start_timestamp,
+            cliff_timestamp,
+            end_timestamp,
+            revocable,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(vesting, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+                AccountMeta::new(*funding_ece_account, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to release vested ECE to the beneficiary's ATA
+    pub fn release(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+        beneficiary: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        vesting_id: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (vesting, _) = Self::find_vesting_address(program_id, treasury_state_account, beneficiary, vesting_id);
+        let (vault, _) = Self::find_vesting_vault_address(program_id, &vesting);
+        let beneficiary_ece_account =
+            get_associated_token_address_with_program_id(beneficiary, ece_mint, ece_token_program_id);
+        let instruction_data = EceTokenInstruction::Release;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(vesting, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(beneficiary_ece_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to revoke a vesting schedule; `approvers` are the additional
+    /// treasury signers needed to meet the multisig threshold
+    #[allow(clippy::too_many_arguments)]
+    pub fn revoke_vesting(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        approvers: &[Pubkey],
+        beneficiary: &Pubkey,
+        destination_ece_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        vesting_id: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (vesting, _) = Self::find_vesting_address(program_id, treasury_state_account, beneficiary, vesting_id);
+        let (vault, _) = Self::find_vesting_vault_address(program_id, &vesting);
+        let instruction_data = EceTokenInstruction::RevokeVesting;
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(vesting, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(*destination_ece_account, false),
+            AccountMeta::new_readonly(*treasury_state_account, false),
+            AccountMeta::new_readonly(*ece_token_program_id, false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(*ece_mint, false),
+        ];
+        accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive the treasury's referral config address
+    pub fn find_referral_config_address(program_id: &Pubkey, treasury_state_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REFERRAL_CONFIG_SEED, treasury_state_account.as_ref()], program_id)
+    }
+
+    /// Derive the referral reward vault address
+    pub fn find_referral_vault_address(program_id: &Pubkey, referral_config: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REFERRAL_VAULT_SEED, referral_config.as_ref()], program_id)
+    }
+
+    /// Derive a user's referral record address
+    pub fn find_referral_address(program_id: &Pubkey, treasury_state_account: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REFERRAL_SEED, treasury_state_account.as_ref(), user.as_ref()], program_id)
+    }
+
+    /// Derive a referrer's reward account address
+    pub fn find_referrer_address(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+        referrer: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REFERRER_SEED, treasury_state_account.as_ref(), referrer.as_ref()], program_id)
+    }
+
+    /// Append the referral accounts to a user mint (MintForUsdc or MintWithCollateral)
+    /// so the user's referrer accrues their reward
+    pub fn with_referral_accounts(
+        mut instruction: Instruction,
+        treasury_state_account: &Pubkey,
+        user: &Pubkey,
+        referrer: &Pubkey,
+    ) -> Instruction {
+        let program_id = instruction.program_id;
+        let (referral, _) = Self::find_referral_address(&program_id, treasury_state_account, user);
+        let (referrer_account, _) = Self::find_referrer_address(&program_id, treasury_state_account, referrer);
+        let (referral_config, _) = Self::find_referral_config_address(&program_id, treasury_state_account);
+        let (vault, _) = Self::find_referral_vault_address(&program_id, &referral_config);
+
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(referral, false),
+            AccountMeta::new(referrer_account, false),
+            AccountMeta::new(referral_config, false),
+            AccountMeta::new_readonly(vault, false),
+        ]);
+        instruction
+    }
+
+    /// Create instruction to configure referral rewards
+    pub fn configure_referrals(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        reward_bps: u16,
+        max_reward_per_referrer: u64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (referral_config, _) = Self::find_referral_config_address(program_id, treasury_state_account);
+        let (vault, _) = Self::find_referral_vault_address(program_id, &referral_config);
+        let instruction_data = EceTokenInstruction::ConfigureReferrals {
+            reward_bps,
+            max_reward_per_referrer,
+        };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(referral_config, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction registering `user` as referred by `referrer`
+    pub fn register_referral(
+        program_id: &Pubkey,
+        user: &Pubkey,
+        treasury_state_account: &Pubkey,
+        referrer: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (referral, _) = Self::find_referral_address(program_id, treasury_state_account, user);
+        let (referrer_account, _) = Self::find_referrer_address(program_id, treasury_state_account, referrer);
+        let instruction_data = EceTokenInstruction::RegisterReferral { referrer: *referrer };
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new(referral, false),
+                AccountMeta::new(referrer_account, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to claim a referrer's rewards into their ATA
+    pub fn claim_referral_rewards(
+        program_id: &Pubkey,
+        referrer: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        ece_token_program_id: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (referrer_account, _) = Self::find_referrer_address(program_id, treasury_state_account, referrer);
+        let (referral_config, _) = Self::find_referral_config_address(program_id, treasury_state_account);
+        let (vault, _) = Self::find_referral_vault_address(program_id, &referral_config);
+        let referrer_ece_account =
+            get_associated_token_address_with_program_id(referrer, ece_mint, ece_token_program_id);
+        let instruction_data = EceTokenInstruction::ClaimReferralRewards;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*referrer, true),
+                AccountMeta::new(referrer_account, false),
+                AccountMeta::new(referral_config, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(referrer_ece_account, false),
+                AccountMeta::new_readonly(*treasury_state_account, false),
+                AccountMeta::new_readonly(*ece_token_program_id, false),
+                AccountMeta::new_readonly(*ece_mint, false),
+            ],
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Derive a card trade offer's address
+    pub fn find_trade_offer_address(
+        program_id: &Pubkey,
+        treasury_state_account: &Pubkey,
+        maker: &Pubkey,
+        offer_id: u64,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[TRADE_OFFER_SEED, treasury_state_account.as_ref(), maker.as_ref(), &offer_id.to_le_bytes()],
+            program_id,
+        )
+    }
+
+    /// Derive a trade offer's escrowed card vault address
+    pub fn find_trade_card_vault_address(program_id: &Pubkey, offer: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[TRADE_CARD_VAULT_SEED, offer.as_ref()], program_id)
+    }
+
+    /// Derive a trade offer's escrowed ECE vault address
+    pub fn find_trade_ece_vault_address(program_id: &Pubkey, offer: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[TRADE_ECE_VAULT_SEED, offer.as_ref()], program_id)
+    }
+
+    /// Create instruction to open a card trade offer, escrowing from the maker's ATAs
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_trade_offer(
+        program_id: &Pubkey,
+        maker: &Pubkey,
+        treasury_state_account: &Pubkey,
+        ece_mint: &Pubkey,
+        card_token_program_id: &Pubkey,
+        ece_token_program_id: &Pubkey,
+        offer_id: u64,
+        offered_card_mint: &Pubkey,
+        offered_ece_amount: u64,
+        requested_card_mint: &Pubkey,
+        requested_ece_amount: u64,
+        taker: &Pubkey,
+        expires_at: i64,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (offer, _) = Self::find_trade_offer_address(program_id, treasury_state_account, maker, offer_id);
+        let instruction_data = EceTokenInstruction::CreateTradeOffer {
+            offer_id,
+            offered_card_mint: *offered_card_mint,
+            offered_ece_amount,
+            requested_card_mint: *requested_card_mint,
+            requested_ece_amount,
+            taker: *taker,
+            expires_at,
+        };
+
+        let mut accounts = vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new(offer, false),
+            AccountMeta::new_readonly(*treasury_state_account, false),
+            AccountMeta::new_readonly(*card_token_program_id, false),
+            AccountMeta::new_readonly(*ece_token_program_id, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ];
+        if *offered_card_mint != Pubkey::default() {
+            let (card_vault, _) = Self::find_trade_card_vault_address(program_id, &offer);
+            accounts.extend([
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        maker,
+                        offered_card_mint,
+                        card_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new_readonly(*offered_card_mint, false),
+                AccountMeta::new(card_vault, false),
+            ]);
+        }
+        if offered_ece_amount > 0 {
+            let (ece_vault, _) = Self::find_trade_ece_vault_address(program_id, &offer);
+            accounts.extend([
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        maker,
+                        ece_mint,
+                        ece_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new_readonly(*ece_mint, false),
+                AccountMeta::new(ece_vault, false),
+            ]);
+        }
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to fill a trade offer from the taker's ATAs
+    pub fn fill_trade_offer(
+        program_id: &Pubkey,
+        taker: &Pubkey,
+        offer: &TradeOffer,
+        fee_ece_account: &Pubkey,
+        ece_token_program_id: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (offer_address, _) =
+            Self::find_trade_offer_address(program_id, &offer.treasury, &offer.maker, offer.offer_id);
+        let card_token_program_id = &offer.card_token_program;
+        let instruction_data = EceTokenInstruction::FillTradeOffer;
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*taker, true),
+            AccountMeta::new(offer.maker, false),
+            AccountMeta::new(offer_address, false),
+            AccountMeta::new_readonly(offer.treasury, false),
+            AccountMeta::new_readonly(*card_token_program_id, false),
+            AccountMeta::new_readonly(*ece_token_program_id, false),
+            AccountMeta::new(*fee_ece_account, false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ];
+        if offer.offers_card() {
+            let (card_vault, _) = Self::find_trade_card_vault_address(program_id, &offer_address);
+            accounts.extend([
+                AccountMeta::new(card_vault, false),
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        taker,
+                        &offer.offered_card_mint,
+                        card_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new_readonly(offer.offered_card_mint, false),
+            ]);
+        }
+        if offer.offered_ece_amount > 0 {
+            let (ece_vault, _) = Self::find_trade_ece_vault_address(program_id, &offer_address);
+            accounts.extend([
+                AccountMeta::new(ece_vault, false),
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        taker,
+                        &offer.ece_mint,
+                        ece_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new_readonly(offer.ece_mint, false),
+            ]);
+        }
+        if offer.requests_card() {
+            accounts.extend([
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        taker,
+                        &offer.requested_card_mint,
+                        card_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        &offer.maker,
+                        &offer.requested_card_mint,
+                        card_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new_readonly(offer.requested_card_mint, false),
+            ]);
+        }
+        if offer.requested_ece_amount > 0 {
+            accounts.extend([
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        taker,
+                        &offer.ece_mint,
+                        ece_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        &offer.maker,
+                        &offer.ece_mint,
+                        ece_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new_readonly(offer.ece_mint, false),
+            ]);
+        }
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
+
+    /// Create instruction to cancel a trade offer back to the maker's ATAs;
+    /// `authority` is the maker or a resolver
+    pub fn cancel_trade_offer(
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        offer: &TradeOffer,
+        ece_token_program_id: &Pubkey,
+    ) -> Result<Instruction, Box<dyn std::error::Error>> {
+        let (offer_address, _) =
+            Self::find_trade_offer_address(program_id, &offer.treasury, &offer.maker, offer.offer_id);
+        let card_token_program_id = &offer.card_token_program;
+        let instruction_data = EceTokenInstruction::CancelTradeOffer;
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(offer.maker, false),
+            AccountMeta::new(offer_address, false),
+            AccountMeta::new_readonly(offer.treasury, false),
+            AccountMeta::new_readonly(*card_token_program_id, false),
+            AccountMeta::new_readonly(*ece_token_program_id, false),
+        ];
+        if offer.offers_card() {
+            let (card_vault, _) = Self::find_trade_card_vault_address(program_id, &offer_address);
+            accounts.extend([
+                AccountMeta::new(card_vault, false),
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        &offer.maker,
+                        &offer.offered_card_mint,
+                        card_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new_readonly(offer.offered_card_mint, false),
+            ]);
+        }
+        if offer.offered_ece_amount > 0 {
+            let (ece_vault, _) = Self::find_trade_ece_vault_address(program_id, &offer_address);
+            accounts.extend([
+                AccountMeta::new(ece_vault, false),
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        &offer.maker,
+                        &offer.ece_mint,
+                        ece_token_program_id,
+                    ),
+                    false,
+                ),
+                AccountMeta::new_readonly(offer.ece_mint, false),
+            ]);
+        }
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data: instruction_data.try_to_vec()?,
+        })
+    }
 }
@@ -77,6 +77,168 @@ pub enum EceTokenError {
     
     #[error("Numerical overflow")]
     NumericalOverflow,
+    
+    #[error("Invalid program derived address")]
+    InvalidProgramAddress,
+    
+    #[error("Stake is still locked")]
+    StakeLocked,
+    
+    #[error("Insufficient staked balance")]
+    InsufficientStakedBalance,
+    
+    #[error("Invalid reward duration")]
+    InvalidRewardDuration,
+    
+    #[error("Invalid governance parameter")]
+    InvalidGovernanceParameter,
+    
+    #[error("Insufficient voting power")]
+    InsufficientVotingPower,
+    
+    #[error("Voting period has ended")]
+    VotingPeriodEnded,
+    
+    #[error("Voting period still active")]
+    VotingPeriodActive,
+    
+    #[error("Proposal did not pass")]
+    ProposalNotPassed,
+    
+    #[error("Proposal already executed")]
+    ProposalAlreadyExecuted,
+    
+    #[error("Invalid fee split")]
+    InvalidFeeSplit,
+    
+    #[error("Invalid royalty configuration")]
+    InvalidRoyaltyConfig,
+    
+    #[error("Invalid subscription plan")]
+    InvalidSubscriptionPlan,
+    
+    #[error("Subscription not active")]
+    SubscriptionNotActive,
+    
+    #[error("Subscription period not elapsed")]
+    SubscriptionPeriodNotElapsed,
+    
+    #[error("Redemption ticket is not at the head of the queue")]
+    RedemptionTicketOutOfOrder,
+    
+    #[error("Withdrawal timelock not elapsed")]
+    WithdrawalTimelockActive,
+    
+    #[error("Withdrawal not pending")]
+    WithdrawalNotPending,
+    
+    #[error("Invalid circuit breaker configuration")]
+    InvalidCircuitBreakerConfig,
+    
+    #[error("Invalid pause flags")]
+    InvalidPauseFlags,
+    
+    #[error("Role registry full")]
+    RoleRegistryFull,
+    
+    #[error("Missing required role")]
+    MissingRole,
+    
+    #[error("No pending authority transfer")]
+    NoPendingAuthority,
+    
+    #[error("Signer set changed since this was proposed")]
+    StaleSignerEpoch,
+    
+    #[error("Invalid oracle account")]
+    InvalidOracleAccount,
+    
+    #[error("Oracle price stale")]
+    OraclePriceStale,
+    
+    #[error("Invalid fee configuration")]
+    InvalidFeeConfig,
+    
+    #[error("Invalid token metadata")]
+    InvalidTokenMetadata,
+    
+    #[error("Denylist full")]
+    DenylistFull,
+    
+    #[error("Wallet is denylisted")]
+    WalletDenylisted,
+    
+    #[error("Not called from a token transfer")]
+    NotTransferring,
+    
+    #[error("Collateral registry full")]
+    CollateralRegistryFull,
+    
+    #[error("Collateral not approved")]
+    CollateralNotApproved,
+    
+    #[error("Collateral cap exceeded")]
+    CollateralCapExceeded,
+    
+    #[error("Invalid collateral configuration")]
+    InvalidCollateralConfig,
+    
+    #[error("ECE and USDC decimals mismatch")]
+    DecimalsMismatch,
+    
+    #[error("Mint configuration locked while ECE is outstanding")]
+    MintConfigurationLocked,
+    
+    #[error("Wallet is not KYC verified")]
+    KycNotVerified,
+    
+    #[error("Reserve snapshot is not newer than the published one")]
+    StaleReserveSnapshot,
+    
+    #[error("Invalid distributor configuration")]
+    InvalidDistributorConfig,
+    
+    #[error("Invalid merkle proof")]
+    InvalidMerkleProof,
+    
+    #[error("Already claimed")]
+    AlreadyClaimed,
+    
+    #[error("Distributor expired")]
+    DistributorExpired,
+    
+    #[error("Distributor not expired")]
+    DistributorNotExpired,
+    
+    #[error("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+    
+    #[error("Nothing to release")]
+    NothingToRelease,
+    
+    #[error("Vesting schedule not revocable")]
+    VestingNotRevocable,
+    
+    #[error("Invalid referral")]
+    InvalidReferral,
+    
+    #[error("No referral rewards to claim")]
+    NoReferralRewards,
+    
+    #[error("Invalid trade offer")]
+    InvalidTradeOffer,
+    
+    #[error("Trade offer not fillable")]
+    TradeOfferNotFillable,
+    
+    #[error("Redemption ticket already settled or cancelled")]
+    RedemptionTicketClosed,
+    
+    #[error("Outflow exceeds circuit breaker limits")]
+    CircuitBreakerLimitExceeded,
+    
+    #[error("Treasury is within circuit breaker limits")]
+    CircuitBreakerWithinLimits,
 }
 
 impl From<EceTokenError> for ProgramError {
@@ -1,6 +1,8 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 
+use crate::state::{AuthorityKind, FeeRecipient, LockupTier, Role, RoyaltyCreator, TreasuryParameterChange};
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub enum EceTokenInstruction {
     /// Initialize the ECE token mint
@@ -25,19 +27,27 @@ pub enum EceTokenInstruction {
         threshold: u8,
     },
 
-    /// Mint ECE tokens backed by USDC deposits
+    /// Mint ECE to a KYC-verified user who deposits their own USDC
     /// Accounts expected:
-    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 0. `[signer]` User
     /// 1. `[writable]` ECE token mint
-    /// 2. `[writable]` Destination ECE token account
-    /// 3. `[writable]` Source USDC token account
+    /// 2. `[writable]` User's ECE associated token account
+    /// 3. `[writable]` User's source USDC token account
     /// 4. `[writable]` Treasury USDC reserve account
     /// 5. `[writable]` Treasury state account
-    /// 6. `[]` Token program
-    /// 7. `[]` Clock sysvar
-    MintTokens { amount: u64 },
+    /// 6. `[writable]` User's KYC record PDA
+    /// 7. `[]` Mint authority PDA
+    /// 8. `[]` Token program
+    /// 9. `[]` Clock sysvar
+    /// 10. ..10+N Any of, in any order:
+    ///     `[]` USDC oracle price account (required once an oracle is configured)
+    ///     `[writable]` Fee USDC account (required while a mint fee is set)
+    ///     `[]` ECE token program, when it differs from the token program
+    ///     Referral record PDA, referrer account PDA (writable), referral config
+    ///     PDA (writable) and referral vault, to accrue the referrer's reward
+    MintForUsdc { amount: u64 },
 
-    /// Burn ECE tokens and release USDC
+    /// Burn ECE tokens and release USDC, up to the USDC share of reserves
     /// Accounts expected:
     /// 0. `[signer]` Token holder
     /// 1. `[writable]` ECE token mint
@@ -47,6 +57,10 @@ pub enum EceTokenInstruction {
     /// 5. `[writable]` Treasury state account
     /// 6. `[]` Token program
     /// 7. `[]` Clock sysvar
+    /// 8. `[]` Treasury authority PDA, owner of the USDC reserve
+    /// 9. ..9+N Any of, in any order:
+    ///    `[]` USDC oracle price account (required once an oracle is configured)
+    ///    `[writable]` Fee USDC account (required while a redeem fee is set)
     BurnTokens { amount: u64 },
 
     /// Deposit USDC to treasury reserves
@@ -67,18 +81,31 @@ pub enum EceTokenInstruction {
     /// 3. `[writable]` Treasury state account
     /// 4. `[]` Token program
     /// 5. `[]` Clock sysvar
+    /// 6. `[]` Treasury authority PDA, owner of the USDC reserve
+    ///
+    /// Amounts that, with this outflow window's earlier withdrawals, exceed the
+    /// treasury's timelock threshold are not transferred; they become a pending
+    /// withdrawal, need approval from the threshold of signers and these extra accounts:
+    /// 7. `[writable]` Pending withdrawal account (PDA)
+    /// 8. `[]` System program
+    /// 9. `[]` Rent sysvar
+    /// 10. ..10+N `[signer]` Additional treasury signers meeting the threshold
     WithdrawUsdc { amount: u64 },
 
     /// Process weekly company payout
     /// Accounts expected:
     /// 0. `[signer]` Treasury authority (multi-sig)
     /// 1. `[writable]` Company ECE revenue account
-    /// 2. `[writable]` Company USDC account
+    /// 2. `[writable]` Company USDC account (unused once a fee split exists)
     /// 3. `[writable]` Treasury USDC reserve account
     /// 4. `[writable]` Treasury state account
     /// 5. `[writable]` ECE token mint
     /// 6. `[]` Token program
     /// 7. `[]` Clock sysvar
+    /// 8. `[]` Fee split PDA; once it exists the payout is split across its
+    ///    recipients instead of going to the company USDC account
+    /// 9. `[]` Treasury authority PDA, owner of the USDC reserve
+    /// 10. ..10+N `[writable]` Recipient USDC accounts, in fee split order
     WeeklyPayout {
         revenue_amount: u64,
         payout_percentage: u8, // 0-100
@@ -90,18 +117,837 @@ pub enum EceTokenInstruction {
     /// 1. `[writable]` Treasury state account
     EmergencyPause,
 
-    /// Emergency unpause operations
+    /// Emergency unpause operations (also resets a circuit breaker trip, keeping
+    /// the outflow window so the same limit still applies)
     /// Accounts expected:
     /// 0. `[signer]` Emergency authority
     /// 1. `[writable]` Treasury state account
     EmergencyUnpause,
 
-    /// Update treasury signers and threshold
+    /// Update treasury signers and threshold. Needs approval from the current
+    /// threshold of signers and bumps the signer epoch, invalidating pending
+    /// withdrawals requested under the old set.
     /// Accounts expected:
     /// 0. `[signer]` Current treasury authority (multi-sig)
     /// 1. `[writable]` Treasury state account
+    /// 2. ..2+N `[signer]` Additional current signers meeting the threshold
     UpdateTreasury {
         new_signers: Vec<Pubkey>,
         new_threshold: u8,
     },
+
+    /// Initialize the ECE staking pool and its vaults
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Staking pool account (PDA)
+    /// 2. `[writable]` Stake vault token account (PDA)
+    /// 3. `[writable]` Reward vault token account (PDA)
+    /// 4. `[]` ECE token mint
+    /// 5. `[]` Treasury state account
+    /// 6. `[]` ECE token program
+    /// 7. `[]` System program
+    /// 8. `[]` Rent sysvar
+    InitializeStakingPool,
+
+    /// Fund the staking reward vault and start a new reward period
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Source ECE token account
+    /// 2. `[writable]` Staking pool account
+    /// 3. `[writable]` Reward vault token account
+    /// 4. `[]` Treasury state account
+    /// 5. `[]` ECE token program
+    /// 6. `[]` Clock sysvar
+    /// 7. `[]` ECE token mint
+    /// 8. ..8+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    FundStakingRewards {
+        amount: u64,
+        duration: i64, // seconds
+    },
+
+    /// Lock ECE into the staking vault
+    /// Accounts expected:
+    /// 0. `[signer]` Staker
+    /// 1. `[writable]` Source ECE token account
+    /// 2. `[writable]` Staking pool account
+    /// 3. `[writable]` Stake account (PDA)
+    /// 4. `[writable]` Stake vault token account
+    /// 5. `[]` Treasury state account
+    /// 6. `[]` ECE token program
+    /// 7. `[]` System program
+    /// 8. `[]` Clock sysvar
+    /// 9. `[]` Rent sysvar
+    /// 10. `[]` ECE token mint
+    /// 11. ..11+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    Stake {
+        amount: u64,
+        lockup_tier: LockupTier,
+    },
+
+    /// Withdraw staked ECE once the lockup has expired
+    /// Accounts expected:
+    /// 0. `[signer]` Staker
+    /// 1. `[writable]` Destination ECE token account
+    /// 2. `[writable]` Staking pool account
+    /// 3. `[writable]` Stake account
+    /// 4. `[writable]` Stake vault token account
+    /// 5. `[]` Treasury state account
+    /// 6. `[]` ECE token program
+    /// 7. `[]` Clock sysvar
+    /// 8. `[]` ECE token mint
+    /// 9. ..9+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    Unstake { amount: u64 },
+
+    /// Claim accrued staking rewards
+    /// Accounts expected:
+    /// 0. `[signer]` Staker
+    /// 1. `[writable]` Destination ECE token account
+    /// 2. `[writable]` Staking pool account
+    /// 3. `[writable]` Stake account
+    /// 4. `[writable]` Reward vault token account
+    /// 5. `[]` Treasury state account
+    /// 6. `[]` ECE token program
+    /// 7. `[]` Clock sysvar
+    /// 8. `[]` ECE token mint
+    /// 9. ..9+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    ClaimStakingRewards,
+
+    /// Initialize staker governance over treasury parameters
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Governance config account (PDA)
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` Staking pool account
+    /// 4. `[]` System program
+    /// 5. `[]` Rent sysvar
+    InitializeGovernance {
+        quorum_bps: u16,
+        voting_period: i64, // seconds
+        proposal_threshold: u64,
+    },
+
+    /// Create a proposal to change a whitelisted treasury parameter
+    /// Accounts expected:
+    /// 0. `[signer]` Proposer
+    /// 1. `[writable]` Governance config account
+    /// 2. `[writable]` Proposal account (PDA)
+    /// 3. `[]` Proposer stake account
+    /// 4. `[]` Staking pool account
+    /// 5. `[]` System program
+    /// 6. `[]` Clock sysvar
+    /// 7. `[]` Rent sysvar
+    CreateProposal { change: TreasuryParameterChange },
+
+    /// Cast a stake-weighted vote on a proposal
+    /// Accounts expected:
+    /// 0. `[signer]` Voter
+    /// 1. `[]` Governance config account
+    /// 2. `[writable]` Proposal account
+    /// 3. `[writable]` Vote record account (PDA)
+    /// 4. `[]` Voter stake account
+    /// 5. `[]` System program
+    /// 6. `[]` Clock sysvar
+    /// 7. `[]` Rent sysvar
+    CastVote { support: bool },
+
+    /// Execute a passed proposal against the treasury state
+    /// Accounts expected:
+    /// 0. `[signer]` Executor (any account)
+    /// 1. `[]` Governance config account
+    /// 2. `[writable]` Proposal account
+    /// 3. `[writable]` Treasury state account
+    /// 4. `[]` Clock sysvar
+    ExecuteProposal,
+
+    /// Create or replace the weekly payout fee split table
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Fee split account (PDA)
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    SetFeeSplit { recipients: Vec<FeeRecipient> },
+
+    /// Create or replace the royalty registry for a card collection
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Royalty registry account (PDA)
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    SetRoyaltyRegistry {
+        collection: Pubkey,
+        royalty_bps: u16,
+        creators: Vec<RoyaltyCreator>,
+    },
+
+    /// Settle a card sale in ECE, routing royalties and the platform fee and
+    /// moving the card from seller to buyer
+    /// Accounts expected:
+    /// 0. `[signer]` Buyer
+    /// 1. `[signer]` Seller
+    /// 2. `[writable]` Buyer ECE token account
+    /// 3. `[writable]` Seller ECE token account
+    /// 4. `[writable]` Treasury fee ECE account (platform fee)
+    /// 5. `[]` Card record account
+    /// 6. `[writable]` Seller card token account
+    /// 7. `[writable]` Buyer card token account
+    /// 8. `[]` Royalty registry account of the card's collection
+    /// 9. `[]` Treasury state account
+    /// 10. `[]` ECE token program
+    /// 11. `[]` Card token program
+    /// 12. `[]` ECE token mint
+    /// 13. `[]` Card mint
+    /// 14. ..14+N `[writable]` Creator ECE token accounts, in registry order, followed by
+    ///     any transfer-hook extra accounts required by the ECE or card mint
+    SettleSaleWithRoyalties { price: u64 },
+
+    /// Initialize the trading card NFT collection
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Card collection account (PDA)
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    InitializeCardCollection { mint_fee: u64 },
+
+    /// Update the ECE fee charged per card mint
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Card collection account
+    /// 2. `[]` Treasury state account
+    SetCardMintFee { mint_fee: u64 },
+
+    /// Mint a trading card NFT paid for in ECE
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Payer and card recipient
+    /// 1. `[writable]` Card collection account
+    /// 2. `[writable]` Card mint account (PDA)
+    /// 3. `[writable]` Card record account (PDA)
+    /// 4. `[writable]` Recipient card associated token account
+    /// 5. `[writable]` Payer ECE token account
+    /// 6. `[writable]` Treasury fee ECE account
+    /// 7. `[]` Treasury state account
+    /// 8. `[]` ECE token program
+    /// 9. `[]` Associated token program
+    /// 10. `[]` System program
+    /// 11. `[]` Rent sysvar
+    /// 12. `[]` Clock sysvar
+    /// 13. `[signer]` Minter
+    /// 14. `[]` ECE token mint
+    /// 15. ..15+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    MintCard { card_id: [u8; 32] },
+
+    /// Create a recurring ECE subscription plan
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Subscription plan account (PDA)
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    CreateSubscriptionPlan {
+        plan_id: u64,
+        price: u64,
+        period: i64,       // seconds
+        grace_period: i64, // seconds
+    },
+
+    /// Subscribe to a plan, paying the first period and delegating a capped allowance.
+    /// Renewing a cancelled subscription inside its paid period charges nothing up front.
+    /// Accounts expected:
+    /// 0. `[signer]` Subscriber
+    /// 1. `[]` Subscription plan account
+    /// 2. `[writable]` Subscription account (PDA)
+    /// 3. `[writable]` Subscriber ECE token account
+    /// 4. `[writable]` Treasury fee ECE account
+    /// 5. `[]` Treasury state account
+    /// 6. `[]` ECE token program
+    /// 7. `[]` System program
+    /// 8. `[]` Clock sysvar
+    /// 9. `[]` Rent sysvar
+    /// 10. `[]` ECE token mint
+    /// 11. ..11+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    Subscribe { max_periods: u32 },
+
+    /// Collect a due subscription period (permissionless crank)
+    /// Accounts expected:
+    /// 0. `[writable]` Subscription account
+    /// 1. `[writable]` Subscriber ECE token account
+    /// 2. `[writable]` Treasury fee ECE account
+    /// 3. `[]` Treasury state account
+    /// 4. `[]` ECE token program
+    /// 5. `[]` Clock sysvar
+    /// 6. `[]` ECE token mint
+    /// 7. ..7+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    CollectSubscription,
+
+    /// Cancel a subscription and revoke its allowance
+    /// Accounts expected:
+    /// 0. `[signer]` Subscriber
+    /// 1. `[writable]` Subscription account
+    /// 2. `[writable]` Subscriber ECE token account
+    /// 3. `[]` Token program
+    CancelSubscription,
+
+    /// Initialize the FIFO redemption queue and its ECE escrow vault
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Redemption queue account (PDA)
+    /// 2. `[writable]` Redemption escrow vault token account (PDA)
+    /// 3. `[]` ECE token mint
+    /// 4. `[]` Treasury state account
+    /// 5. `[]` Token program
+    /// 6. `[]` System program
+    /// 7. `[]` Rent sysvar
+    InitializeRedemptionQueue,
+
+    /// Lock ECE into a queued redemption ticket
+    /// Accounts expected:
+    /// 0. `[signer]` Token holder
+    /// 1. `[writable]` Source ECE token account
+    /// 2. `[]` Destination USDC token account, owned by the token holder
+    /// 3. `[writable]` Redemption queue account
+    /// 4. `[writable]` Redemption ticket account (PDA)
+    /// 5. `[writable]` Redemption escrow vault token account
+    /// 6. `[writable]` Treasury state account
+    /// 7. `[]` ECE token program
+    /// 8. `[]` System program
+    /// 9. `[]` Clock sysvar
+    /// 10. `[]` Rent sysvar
+    /// 11. `[]` ECE token mint
+    /// 12. ..12+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    QueueRedemption { amount: u64 },
+
+    /// Settle the ticket at the head of the redemption queue, or move past it if
+    /// it was cancelled (permissionless crank)
+    /// Accounts expected:
+    /// 0. `[writable]` Redemption queue account
+    /// 1. `[writable]` Redemption ticket account at the queue head
+    /// 2. `[writable]` Redemption escrow vault token account
+    /// 3. `[writable]` ECE token mint
+    /// 4. `[writable]` Ticket destination USDC token account
+    /// 5. `[writable]` Treasury USDC reserve account
+    /// 6. `[writable]` Treasury state account
+    /// 7. `[]` Token program
+    /// 8. `[]` Treasury authority PDA, owner of the USDC reserve
+    /// 9. ..9+N Any of, in any order:
+    ///    `[]` USDC oracle price account (required once an oracle is configured)
+    ///    `[writable]` Fee USDC account (required while a redeem fee is set)
+    ///    `[]` ECE token program, when it differs from the token program
+    SettleRedemption,
+
+    /// Execute a timelocked withdrawal once its delay has elapsed. Needs approval
+    /// from the threshold of signers.
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Pending withdrawal account
+    /// 2. `[writable]` Treasury USDC reserve account
+    /// 3. `[writable]` Destination USDC account
+    /// 4. `[writable]` Treasury state account
+    /// 5. `[]` Token program
+    /// 6. `[]` Clock sysvar
+    /// 7. `[]` Treasury authority PDA, owner of the USDC reserve
+    /// 8. ..8+N `[signer]` Additional treasury signers meeting the threshold
+    ExecuteWithdrawal,
+
+    /// Cancel a timelocked withdrawal
+    /// Accounts expected:
+    /// 0. `[signer]` Emergency authority or treasury signer
+    /// 1. `[writable]` Pending withdrawal account
+    /// 2. `[]` Treasury state account
+    CancelWithdrawal,
+
+    /// Configure the withdrawal timelock. Tightening applies at once; a looser
+    /// threshold or shorter delay is staged behind the current delay and applied
+    /// by submitting the same change again once it has elapsed.
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    /// 2. `[]` Clock sysvar
+    /// 3. ..3+N `[signer]` Additional treasury signers meeting the threshold
+    SetWithdrawalTimelock {
+        threshold: u64,
+        delay: i64, // seconds
+    },
+
+    /// Configure the circuit breaker that pauses the treasury on outflows past its limits
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    SetCircuitBreaker {
+        outflow_window: i64, // seconds
+        max_window_outflow_bps: u16,
+        reserve_floor_bps: u16,
+    },
+
+    /// Pause or resume individual operation types (bitmask of `PAUSE_*` flags; bits
+    /// outside `PAUSE_ALL` are rejected)
+    /// Accounts expected:
+    /// 0. `[signer]` Emergency authority
+    /// 1. `[writable]` Treasury state account
+    SetPauseFlags { pause_flags: u16 },
+
+    /// Grant a role to a key, creating the role registry on first use.
+    /// The registry can be appended to any instruction so holders of a
+    /// granted role are recognised alongside the built-in authorities.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Payer
+    /// 1. `[writable]` Role registry account (PDA)
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    /// 5. ..5+N `[signer]` Treasury signers meeting the multisig threshold
+    GrantRole { holder: Pubkey, role: Role },
+
+    /// Revoke a role from a key
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury signer
+    /// 1. `[writable]` Role registry account
+    /// 2. `[]` Treasury state account
+    /// 3. ..3+N `[signer]` Additional treasury signers meeting the multisig threshold
+    RevokeRole { holder: Pubkey, role: Role },
+
+    /// Propose a new emergency or compliance authority
+    /// Accounts expected:
+    /// 0. `[signer]` Current authority of that kind, or treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    /// 2. ..2+N `[signer]` Additional treasury signers meeting the threshold (multi-sig path only)
+    ProposeAuthority {
+        kind: AuthorityKind,
+        new_authority: Pubkey,
+    },
+
+    /// Accept a proposed authority handover
+    /// Accounts expected:
+    /// 0. `[signer]` Proposed authority
+    /// 1. `[writable]` Treasury state account
+    AcceptAuthority { kind: AuthorityKind },
+
+    /// Cancel a pending authority handover
+    /// Accounts expected:
+    /// 0. `[signer]` Current authority of that kind, or treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    /// 2. ..2+N `[signer]` Additional treasury signers meeting the threshold (multi-sig path only)
+    CancelAuthorityTransfer { kind: AuthorityKind },
+
+    /// Set the lowest threshold a signer-set update may use
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    /// 2. ..2+N `[signer]` Additional treasury signers meeting the threshold
+    SetMinSignerThreshold { min_signer_threshold: u8 },
+
+    /// Configure the USDC/USD oracle guarding mint and burn. Once set, the
+    /// oracle account must be appended to MintForUsdc and BurnTokens.
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    /// 2. `[]` Oracle price account (omitted when disabling the oracle)
+    SetUsdcOracle {
+        oracle: Pubkey, // default key disables the check
+        max_deviation_bps: u16,
+        max_staleness: i64, // seconds
+    },
+
+    /// Configure mint and redeem fees
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    /// 2. `[]` Fee USDC account
+    /// 3. `[]` Fee ECE account (marketplace, card mint and subscription fees)
+    SetFees {
+        mint_fee_bps: u16,
+        redeem_fee_bps: u16,
+    },
+
+    /// Create ECE as a Token-2022 mint with a metadata pointer, on-chain
+    /// metadata, an optional transfer fee withdrawable by the treasury and an
+    /// optional transfer hook pointing back at this program.
+    /// Program-internal ECE movements use plain transfers, which Token-2022
+    /// rejects for transfer-fee and transfer-hook mints, so leave both off if
+    /// staking, subscriptions or the redemption queue are in use.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Treasury authority (multi-sig), pays for the mint
+    /// 1. `[signer, writable]` New ECE mint account
+    /// 2. `[]` Mint authority PDA
+    /// 3. `[writable]` Treasury state account
+    /// 4. `[]` System program
+    /// 5. `[]` Rent sysvar
+    /// 6. `[]` Token-2022 program
+    InitializeToken2022 {
+        decimals: u8,
+        freeze_authority: Option<Pubkey>,
+        name: String,
+        symbol: String,
+        uri: String,
+        transfer_fee_bps: u16, // 0 disables the transfer fee extension
+        maximum_fee: u64,
+        transfer_hook: bool, // enforce the denylist and transaction limit on every transfer
+    },
+
+    /// Withdraw withheld Token-2022 transfer fees to the treasury
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[]` Treasury state account
+    /// 2. `[writable]` ECE mint
+    /// 3. `[writable]` Treasury authority's ECE associated token account
+    /// 4. `[]` Treasury authority PDA
+    /// 5. `[]` Token-2022 program
+    /// 6. ..6+N `[writable]` ECE token accounts holding withheld fees (optional)
+    WithdrawTransferFees,
+
+    /// Add a wallet to the transfer denylist enforced by the transfer hook
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Compliance officer, pays for the denylist on first use
+    /// 1. `[writable]` Denylist PDA
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    AddToDenylist { wallet: Pubkey },
+
+    /// Remove a wallet from the transfer denylist
+    /// Accounts expected:
+    /// 0. `[signer]` Compliance officer
+    /// 1. `[writable]` Denylist PDA
+    /// 2. `[]` Treasury state account
+    RemoveFromDenylist { wallet: Pubkey },
+
+    /// Approve a non-USDC stablecoin as collateral and create its reserve
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Treasury authority (multi-sig), pays for the accounts
+    /// 1. `[writable]` Collateral registry PDA
+    /// 2. `[writable]` Treasury state account
+    /// 3. `[]` Collateral mint
+    /// 4. `[writable]` Collateral reserve token account PDA
+    /// 5. `[]` Treasury authority PDA
+    /// 6. `[]` ECE mint
+    /// 7. `[]` System program
+    /// 8. `[]` Collateral token program
+    /// 9. `[]` Rent sysvar
+    AddCollateral {
+        cap: u64,         // in collateral base units
+        haircut_bps: u16, // discount applied when valuing the collateral
+    },
+
+    /// Update a collateral asset's cap, haircut or status and revalue the basket
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Collateral registry PDA
+    /// 2. `[writable]` Treasury state account
+    /// 3. `[]` ECE mint
+    UpdateCollateral {
+        mint: Pubkey,
+        cap: u64,
+        haircut_bps: u16,
+        is_active: bool,
+    },
+
+    /// Mint ECE against an approved non-USDC stablecoin. The user receives the
+    /// haircut-adjusted value less the mint fee, which stays in reserve.
+    /// Accounts expected:
+    /// 0. `[signer]` User
+    /// 1. `[writable]` User collateral token account
+    /// 2. `[]` Collateral mint
+    /// 3. `[writable]` Collateral reserve token account PDA
+    /// 4. `[writable]` Collateral registry PDA
+    /// 5. `[writable]` ECE mint
+    /// 6. `[writable]` User ECE token account
+    /// 7. `[]` Mint authority PDA
+    /// 8. `[writable]` Treasury state account
+    /// 9. `[writable]` User's KYC record PDA
+    /// 10. `[]` Collateral token program
+    /// 11. `[]` Clock sysvar
+    /// 12. `[]` ECE token program, when it differs from the collateral token program
+    /// 13. ..13+N Referral accounts as for MintForUsdc, to accrue the referrer's reward
+    MintWithCollateral { amount: u64 },
+
+    /// Point the treasury at its ECE and USDC mints, recording both mints'
+    /// decimals. Only allowed while no ECE is outstanding.
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    /// 2. `[]` ECE mint
+    /// 3. `[]` USDC mint
+    /// 4. `[]` Treasury USDC reserve account, owned by the treasury authority PDA
+    ConfigureMints,
+
+    /// Mint ECE against reserves already held above the minimum reserve ratio
+    /// Accounts expected:
+    /// 0. `[signer]` Minter
+    /// 1. `[writable]` ECE token mint
+    /// 2. `[writable]` Destination ECE token account
+    /// 3. `[]` Mint authority PDA
+    /// 4. `[writable]` Treasury state account
+    /// 5. `[]` ECE token program
+    MintFromReserves { amount: u64 },
+
+    /// Record a wallet's KYC status, creating its KYC record on first use
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Compliance officer, pays for the record
+    /// 1. `[writable]` KYC record PDA
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    SetKycStatus {
+        wallet: Pubkey,
+        verified: bool,
+        expires_at: i64, // 0 never expires
+    },
+
+    /// Configure the self-service and admin mint limits (in USDC base units)
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    SetMintLimits {
+        user_mint_limit: u64,
+        user_daily_mint_limit: u64,
+        reserve_mint_limit: u64,
+    },
+
+    /// Publish the root and total of an off-chain Merkle sum tree of ECE holder
+    /// balances, recorded alongside the treasury's current reserves
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Auditor, pays for the attestation on first use
+    /// 1. `[writable]` Reserve attestation PDA
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    /// 5. `[]` Clock sysvar
+    PublishReserveSnapshot {
+        merkle_root: [u8; 32],
+        total_balances: u64,
+        holder_count: u32,
+        snapshot_slot: u64,
+    },
+
+    /// Create a merkle distributor and fund its vault with ECE. The funding transfer is
+    /// grossed up so the vault receives `total_amount` after any ECE transfer fee.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Treasury authority (multi-sig), pays for the accounts and funds the vault
+    /// 1. `[writable]` Distributor PDA
+    /// 2. `[writable]` Distributor vault token account PDA
+    /// 3. `[]` ECE mint
+    /// 4. `[writable]` Funding ECE token account
+    /// 5. `[]` Treasury state account
+    /// 6. `[]` ECE token program
+    /// 7. `[]` System program
+    /// 8. `[]` Rent sysvar
+    /// 9. `[]` Clock sysvar
+    /// 10. ..10+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    CreateDistributor {
+        distributor_id: u64,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_claims: u32,
+        expires_at: i64,
+    },
+
+    /// Claim ECE from a distributor into the claimant's associated token account
+    /// Accounts expected:
+    /// 0. `[signer]` Claimant
+    /// 1. `[writable]` Distributor PDA
+    /// 2. `[writable]` Distributor vault token account PDA
+    /// 3. `[writable]` Claimant's ECE associated token account
+    /// 4. `[]` ECE token program
+    /// 5. `[]` Clock sysvar
+    /// 6. `[]` Treasury state account
+    /// 7. `[]` ECE mint
+    /// 8. ..8+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    Claim {
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Return a distributor's unclaimed ECE once it has expired
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Distributor PDA
+    /// 2. `[writable]` Distributor vault token account PDA
+    /// 3. `[writable]` Destination ECE token account
+    /// 4. `[]` Treasury state account
+    /// 5. `[]` ECE token program
+    /// 6. `[]` Clock sysvar
+    /// 7. `[]` ECE mint
+    /// 8. ..8+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    ClawbackDistributor,
+
+    /// Create a vesting schedule and fund its vault with ECE. The schedule vests what
+    /// the vault receives, net of any ECE transfer fee.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Treasury authority (multi-sig), pays for the accounts and funds the vault
+    /// 1. `[writable]` Vesting schedule PDA
+    /// 2. `[writable]` Vesting vault token account PDA
+    /// 3. `[]` ECE mint
+    /// 4. `[writable]` Funding ECE token account
+    /// 5. `[]` Treasury state account
+    /// 6. `[]` ECE token program
+    /// 7. `[]` System program
+    /// 8. `[]` Rent sysvar
+    /// 9. ..9+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    CreateVesting {
+        vesting_id: u64,
+        beneficiary: Pubkey,
+        total_amount: u64,
+        start_timestamp: i64,
+        cliff_timestamp: i64,
+        end_timestamp: i64,
+        revocable: bool,
+    },
+
+    /// Release vested ECE to the beneficiary; callable by anyone
+    /// Accounts expected:
+    /// 0. `[writable]` Vesting schedule PDA
+    /// 1. `[writable]` Vesting vault token account PDA
+    /// 2. `[writable]` Beneficiary's ECE associated token account
+    /// 3. `[]` ECE token program
+    /// 4. `[]` Clock sysvar
+    /// 5. `[]` Treasury state account
+    /// 6. `[]` ECE mint
+    /// 7. ..7+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    Release,
+
+    /// Revoke a revocable vesting schedule, returning the unvested ECE. What
+    /// has vested stays releasable to the beneficiary.
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Vesting schedule PDA
+    /// 2. `[writable]` Vesting vault token account PDA
+    /// 3. `[writable]` Destination ECE token account
+    /// 4. `[]` Treasury state account
+    /// 5. `[]` ECE token program
+    /// 6. `[]` Clock sysvar
+    /// 7. `[]` ECE mint
+    /// 8. ..8+N `[signer]` Additional treasury signers meeting the multisig threshold, followed
+    ///    by any transfer-hook extra accounts required by the ECE mint
+    RevokeVesting,
+
+    /// Configure referral rewards, creating the config and its ECE vault on
+    /// first use. The vault is funded with plain ECE transfers.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Treasury authority (multi-sig), pays for the accounts
+    /// 1. `[writable]` Referral config PDA
+    /// 2. `[writable]` Referral vault token account PDA
+    /// 3. `[]` ECE mint
+    /// 4. `[]` Treasury state account
+    /// 5. `[]` ECE token program
+    /// 6. `[]` System program
+    /// 7. `[]` Rent sysvar
+    ConfigureReferrals {
+        reward_bps: u16,
+        max_reward_per_referrer: u64, // lifetime cap in ECE base units
+    },
+
+    /// Register the signing user as referred by `referrer`; can only be done once
+    /// Accounts expected:
+    /// 0. `[signer, writable]` User, pays for the accounts
+    /// 1. `[writable]` User's referral record PDA
+    /// 2. `[writable]` Referrer account PDA
+    /// 3. `[]` Treasury state account
+    /// 4. `[]` System program
+    /// 5. `[]` Rent sysvar
+    /// 6. `[]` Clock sysvar
+    RegisterReferral { referrer: Pubkey },
+
+    /// Pay a referrer's accrued rewards from the referral vault
+    /// Accounts expected:
+    /// 0. `[signer]` Referrer
+    /// 1. `[writable]` Referrer account PDA
+    /// 2. `[writable]` Referral config PDA
+    /// 3. `[writable]` Referral vault token account PDA
+    /// 4. `[writable]` Referrer's ECE associated token account
+    /// 5. `[]` Treasury state account
+    /// 6. `[]` ECE token program
+    /// 7. `[]` ECE mint
+    /// 8. ..8+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    ClaimReferralRewards,
+
+    /// Open a card trade offer, escrowing the maker's card and/or ECE
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Maker, pays for the accounts
+    /// 1. `[writable]` Trade offer PDA
+    /// 2. `[]` Treasury state account
+    /// 3. `[]` Card token program
+    /// 4. `[]` ECE token program
+    /// 5. `[]` System program
+    /// 6. `[]` Rent sysvar
+    /// 7. `[]` Clock sysvar
+    ///
+    /// Then, when offering a card:
+    ///     `[writable]` Maker card token account, `[]` card mint, `[writable]` card vault PDA
+    /// Then, when offering ECE:
+    ///     `[writable]` Maker ECE token account, `[]` ECE mint, `[writable]` ECE vault PDA
+    /// Then any transfer-hook extra accounts required by the card or ECE mint
+    CreateTradeOffer {
+        offer_id: u64,
+        offered_card_mint: Pubkey,   // default key for none
+        offered_ece_amount: u64,
+        requested_card_mint: Pubkey, // default key for none
+        requested_ece_amount: u64,
+        taker: Pubkey,               // default key lets anyone fill
+        expires_at: i64,             // 0 never expires
+    },
+
+    /// Fill a trade offer: both sides swap atomically and the marketplace fee
+    /// is withheld from each ECE leg into the treasury fee ECE account
+    /// Accounts expected:
+    /// 0. `[signer]` Taker
+    /// 1. `[writable]` Maker, receives the vaults' rent
+    /// 2. `[writable]` Trade offer PDA
+    /// 3. `[]` Treasury state account
+    /// 4. `[]` Card token program
+    /// 5. `[]` ECE token program
+    /// 6. `[writable]` Treasury fee ECE account
+    /// 7. `[]` Clock sysvar
+    ///
+    /// Then, for each leg of the offer in this order:
+    ///     offered card: `[writable]` card vault PDA, `[writable]` taker card account, `[]` card mint
+    ///     offered ECE: `[writable]` ECE vault PDA, `[writable]` taker ECE account, `[]` ECE mint
+    ///     requested card: `[writable]` taker card account, `[writable]` maker card account, `[]` card mint
+    ///     requested ECE: `[writable]` taker ECE account, `[writable]` maker ECE account, `[]` ECE mint
+    /// Then any transfer-hook extra accounts required by the card or ECE mints
+    FillTradeOffer,
+
+    /// Cancel an open trade offer, returning the escrow to the maker. The maker
+    /// or a resolver can cancel.
+    /// Accounts expected:
+    /// 0. `[signer]` Maker or resolver
+    /// 1. `[writable]` Maker, receives the vaults' rent
+    /// 2. `[writable]` Trade offer PDA
+    /// 3. `[]` Treasury state account
+    /// 4. `[]` Card token program
+    /// 5. `[]` ECE token program
+    ///
+    /// Then, when a card was offered:
+    ///     `[writable]` Card vault PDA, `[writable]` maker card account, `[]` card mint
+    /// Then, when ECE was offered:
+    ///     `[writable]` ECE vault PDA, `[writable]` maker ECE account, `[]` ECE mint
+    /// Then any transfer-hook extra accounts required by the card or ECE mint
+    CancelTradeOffer,
+
+    /// Set the company USDC account receiving weekly payouts while no fee split exists
+    /// Accounts expected:
+    /// 0. `[signer]` Treasury authority (multi-sig)
+    /// 1. `[writable]` Treasury state account
+    /// 2. `[]` Company USDC account
+    SetCompanyUsdcAccount,
+
+    /// Cancel a queued redemption, returning the unsettled ECE to the ticket owner
+    /// Accounts expected:
+    /// 0. `[signer]` Ticket owner
+    /// 1. `[writable]` Owner ECE token account
+    /// 2. `[writable]` Redemption queue account
+    /// 3. `[writable]` Redemption ticket account
+    /// 4. `[writable]` Redemption escrow vault token account
+    /// 5. `[writable]` Treasury state account
+    /// 6. `[]` ECE token program
+    /// 7. `[]` ECE token mint
+    /// 8. ..8+N `[]` Transfer-hook extra accounts required by the ECE mint, if any
+    CancelRedemption,
+
+    /// Pause the treasury when it is already past a circuit breaker limit (permissionless crank)
+    /// Accounts expected:
+    /// 0. `[writable]` Treasury state account
+    /// 1. `[]` Clock sysvar
+    TripCircuitBreaker,
 }
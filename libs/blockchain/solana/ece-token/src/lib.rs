@@ -1,27 +1,25 @@
 use solana_program::{
-    account_info::{next_account_info, AccountInfo},
+    account_info::AccountInfo,
     entrypoint,
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
     pubkey::Pubkey,
-    system_instruction,
-    sysvar::{clock::Clock, Sysvar},
 };
-use spl_token::{
-    instruction as token_instruction,
-    state::{Account as TokenAccount, Mint},
-};
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshDeserialize;
 
 pub mod error;
 pub mod instruction;
+pub mod merkle_distributor;
+pub mod oracle;
 pub mod processor;
+pub mod proof_of_reserves;
 pub mod state;
 pub mod client;
 
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
+
 use crate::{
-    error::EceTokenError,
     instruction::EceTokenInstruction,
     processor::Processor,
 };
@@ -34,6 +32,24 @@ pub fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
+    // Token-2022 transfer hook interface, identified by its 8-byte discriminators
+    if let Ok(instruction) = TransferHookInstruction::unpack(instruction_data) {
+        return match instruction {
+            TransferHookInstruction::Execute { amount } => {
+                msg!("Instruction: Transfer Hook Execute");
+                Processor::process_transfer_hook_execute(program_id, accounts, instruction_data, amount)
+            }
+            TransferHookInstruction::InitializeExtraAccountMetaList { extra_account_metas } => {
+                msg!("Instruction: Initialize Extra Account Meta List");
+                Processor::process_initialize_extra_account_meta_list(program_id, accounts, extra_account_metas)
+            }
+            // The extra accounts are fixed by the program, so there is nothing to update
+            TransferHookInstruction::UpdateExtraAccountMetaList { .. } => {
+                Err(ProgramError::InvalidInstructionData)
+            }
+        };
+    }
+
     let instruction = EceTokenInstruction::try_from_slice(instruction_data)
         .map_err(|_| ProgramError::InvalidInstructionData)?;
 
@@ -52,9 +68,9 @@ pub fn process_instruction(
             msg!("Instruction: Initialize Treasury");
             Processor::process_initialize_treasury(program_id, accounts, signers, threshold)
         }
-        EceTokenInstruction::MintTokens { amount } => {
-            msg!("Instruction: Mint ECE Tokens");
-            Processor::process_mint_tokens(program_id, accounts, amount)
+        EceTokenInstruction::MintForUsdc { amount } => {
+            msg!("Instruction: Mint For USDC");
+            Processor::process_mint_for_usdc(program_id, accounts, amount)
         }
         EceTokenInstruction::BurnTokens { amount } => {
             msg!("Instruction: Burn ECE Tokens");
@@ -90,5 +106,383 @@ pub fn process_instruction(
             msg!("Instruction: Update Treasury");
             Processor::process_update_treasury(program_id, accounts, new_signers, new_threshold)
         }
+        EceTokenInstruction::InitializeStakingPool => {
+            msg!("Instruction: Initialize Staking Pool");
+            Processor::process_initialize_staking_pool(program_id, accounts)
+        }
+        EceTokenInstruction::FundStakingRewards { amount, duration } => {
+            msg!("Instruction: Fund Staking Rewards");
+            Processor::process_fund_staking_rewards(program_id, accounts, amount, duration)
+        }
+        EceTokenInstruction::Stake {
+            amount,
+            lockup_tier,
+        } => {
+            msg!("Instruction: Stake ECE");
+            Processor::process_stake(program_id, accounts, amount, lockup_tier)
+        }
+        EceTokenInstruction::Unstake { amount } => {
+            msg!("Instruction: Unstake ECE");
+            Processor::process_unstake(program_id, accounts, amount)
+        }
+        EceTokenInstruction::ClaimStakingRewards => {
+            msg!("Instruction: Claim Staking Rewards");
+            Processor::process_claim_staking_rewards(program_id, accounts)
+        }
+        EceTokenInstruction::InitializeGovernance {
+            quorum_bps,
+            voting_period,
+            proposal_threshold,
+        } => {
+            msg!("Instruction: Initialize Governance");
+            Processor::process_initialize_governance(
+                program_id,
+                accounts,
+                quorum_bps,
+                voting_period,
+                proposal_threshold,
+            )
+        }
+        EceTokenInstruction::CreateProposal { change } => {
+            msg!("Instruction: Create Proposal");
+            Processor::process_create_proposal(program_id, accounts, change)
+        }
+        EceTokenInstruction::CastVote { support } => {
+            msg!("Instruction: Cast Vote");
+            Processor::process_cast_vote(program_id, accounts, support)
+        }
+        EceTokenInstruction::ExecuteProposal => {
+            msg!("Instruction: Execute Proposal");
+            Processor::process_execute_proposal(program_id, accounts)
+        }
+        EceTokenInstruction::SetFeeSplit { recipients } => {
+            msg!("Instruction: Set Fee Split");
+            Processor::process_set_fee_split(program_id, accounts, recipients)
+        }
+        EceTokenInstruction::SetRoyaltyRegistry {
+            collection,
+            royalty_bps,
+            creators,
+        } => {
+            msg!("Instruction: Set Royalty Registry");
+            Processor::process_set_royalty_registry(program_id, accounts, collection, royalty_bps, creators)
+        }
+        EceTokenInstruction::SettleSaleWithRoyalties { price } => {
+            msg!("Instruction: Settle Sale With Royalties");
+            Processor::process_settle_sale_with_royalties(program_id, accounts, price)
+        }
+        EceTokenInstruction::InitializeCardCollection { mint_fee } => {
+            msg!("Instruction: Initialize Card Collection");
+            Processor::process_initialize_card_collection(program_id, accounts, mint_fee)
+        }
+        EceTokenInstruction::SetCardMintFee { mint_fee } => {
+            msg!("Instruction: Set Card Mint Fee");
+            Processor::process_set_card_mint_fee(program_id, accounts, mint_fee)
+        }
+        EceTokenInstruction::MintCard { card_id } => {
+            msg!("Instruction: Mint Card");
+            Processor::process_mint_card(program_id, accounts, card_id)
+        }
+        EceTokenInstruction::CreateSubscriptionPlan {
+            plan_id,
+            price,
+            period,
+            grace_period,
+        } => {
+            msg!("Instruction: Create Subscription Plan");
+            Processor::process_create_subscription_plan(
+                program_id,
+                accounts,
+                plan_id,
+                price,
+                period,
+                grace_period,
+            )
+        }
+        EceTokenInstruction::Subscribe { max_periods } => {
+            msg!("Instruction: Subscribe");
+            Processor::process_subscribe(program_id, accounts, max_periods)
+        }
+        EceTokenInstruction::CollectSubscription => {
+            msg!("Instruction: Collect Subscription");
+            Processor::process_collect_subscription(program_id, accounts)
+        }
+        EceTokenInstruction::CancelSubscription => {
+            msg!("Instruction: Cancel Subscription");
+            Processor::process_cancel_subscription(program_id, accounts)
+        }
+        EceTokenInstruction::InitializeRedemptionQueue => {
+            msg!("Instruction: Initialize Redemption Queue");
+            Processor::process_initialize_redemption_queue(program_id, accounts)
+        }
+        EceTokenInstruction::QueueRedemption { amount } => {
+            msg!("Instruction: Queue Redemption");
+            Processor::process_queue_redemption(program_id, accounts, amount)
+        }
+        EceTokenInstruction::SettleRedemption => {
+            msg!("Instruction: Settle Redemption");
+            Processor::process_settle_redemption(program_id, accounts)
+        }
+        EceTokenInstruction::ExecuteWithdrawal => {
+            msg!("Instruction: Execute Withdrawal");
+            Processor::process_execute_withdrawal(program_id, accounts)
+        }
+        EceTokenInstruction::CancelWithdrawal => {
+            msg!("Instruction: Cancel Withdrawal");
+            Processor::process_cancel_withdrawal(program_id, accounts)
+        }
+        EceTokenInstruction::SetWithdrawalTimelock { threshold, delay } => {
+            msg!("Instruction: Set Withdrawal Timelock");
+            Processor::process_set_withdrawal_timelock(program_id, accounts, threshold, delay)
+        }
+        EceTokenInstruction::SetCircuitBreaker { outflow_window, max_window_outflow_bps, reserve_floor_bps } => {
+            msg!("Instruction: Set Circuit Breaker");
+            Processor::process_set_circuit_breaker(
+                program_id,
+                accounts,
+                outflow_window,
+                max_window_outflow_bps,
+                reserve_floor_bps,
+            )
+        }
+        EceTokenInstruction::SetPauseFlags { pause_flags } => {
+            msg!("Instruction: Set Pause Flags");
+            Processor::process_set_pause_flags(program_id, accounts, pause_flags)
+        }
+        EceTokenInstruction::GrantRole { holder, role } => {
+            msg!("Instruction: Grant Role");
+            Processor::process_grant_role(program_id, accounts, holder, role)
+        }
+        EceTokenInstruction::RevokeRole { holder, role } => {
+            msg!("Instruction: Revoke Role");
+            Processor::process_revoke_role(program_id, accounts, holder, role)
+        }
+        EceTokenInstruction::ProposeAuthority { kind, new_authority } => {
+            msg!("Instruction: Propose Authority");
+            Processor::process_propose_authority(program_id, accounts, kind, new_authority)
+        }
+        EceTokenInstruction::AcceptAuthority { kind } => {
+            msg!("Instruction: Accept Authority");
+            Processor::process_accept_authority(program_id, accounts, kind)
+        }
+        EceTokenInstruction::CancelAuthorityTransfer { kind } => {
+            msg!("Instruction: Cancel Authority Transfer");
+            Processor::process_cancel_authority_transfer(program_id, accounts, kind)
+        }
+        EceTokenInstruction::SetMinSignerThreshold { min_signer_threshold } => {
+            msg!("Instruction: Set Min Signer Threshold");
+            Processor::process_set_min_signer_threshold(program_id, accounts, min_signer_threshold)
+        }
+        EceTokenInstruction::SetUsdcOracle { oracle, max_deviation_bps, max_staleness } => {
+            msg!("Instruction: Set USDC Oracle");
+            Processor::process_set_usdc_oracle(program_id, accounts, oracle, max_deviation_bps, max_staleness)
+        }
+        EceTokenInstruction::SetFees { mint_fee_bps, redeem_fee_bps } => {
+            msg!("Instruction: Set Fees");
+            Processor::process_set_fees(program_id, accounts, mint_fee_bps, redeem_fee_bps)
+        }
+        EceTokenInstruction::InitializeToken2022 {
+            decimals,
+            freeze_authority,
+            name,
+            symbol,
+            uri,
+            transfer_fee_bps,
+            maximum_fee,
+            transfer_hook,
+        } => {
+            msg!("Instruction: Initialize Token-2022");
+            Processor::process_initialize_token_2022(
+                program_id,
+                accounts,
+                decimals,
+                freeze_authority,
+                name,
+                symbol,
+                uri,
+                transfer_fee_bps,
+                maximum_fee,
+                transfer_hook,
+            )
+        }
+        EceTokenInstruction::WithdrawTransferFees => {
+            msg!("Instruction: Withdraw Transfer Fees");
+            Processor::process_withdraw_transfer_fees(program_id, accounts)
+        }
+        EceTokenInstruction::AddToDenylist { wallet } => {
+            msg!("Instruction: Add To Denylist");
+            Processor::process_add_to_denylist(program_id, accounts, wallet)
+        }
+        EceTokenInstruction::RemoveFromDenylist { wallet } => {
+            msg!("Instruction: Remove From Denylist");
+            Processor::process_remove_from_denylist(program_id, accounts, wallet)
+        }
+        EceTokenInstruction::AddCollateral { cap, haircut_bps } => {
+            msg!("Instruction: Add Collateral");
+            Processor::process_add_collateral(program_id, accounts, cap, haircut_bps)
+        }
+        EceTokenInstruction::UpdateCollateral { mint, cap, haircut_bps, is_active } => {
+            msg!("Instruction: Update Collateral");
+            Processor::process_update_collateral(program_id, accounts, mint, cap, haircut_bps, is_active)
+        }
+        EceTokenInstruction::MintWithCollateral { amount } => {
+            msg!("Instruction: Mint With Collateral");
+            Processor::process_mint_with_collateral(program_id, accounts, amount)
+        }
+        EceTokenInstruction::ConfigureMints => {
+            msg!("Instruction: Configure Mints");
+            Processor::process_configure_mints(program_id, accounts)
+        }
+        EceTokenInstruction::MintFromReserves { amount } => {
+            msg!("Instruction: Mint From Reserves");
+            Processor::process_mint_from_reserves(program_id, accounts, amount)
+        }
+        EceTokenInstruction::SetKycStatus { wallet, verified, expires_at } => {
+            msg!("Instruction: Set KYC Status");
+            Processor::process_set_kyc_status(program_id, accounts, wallet, verified, expires_at)
+        }
+        EceTokenInstruction::SetMintLimits {
+            user_mint_limit,
+            user_daily_mint_limit,
+            reserve_mint_limit,
+        } => {
+            msg!("Instruction: Set Mint Limits");
+            Processor::process_set_mint_limits(
+                program_id,
+                accounts,
+                user_mint_limit,
+                user_daily_mint_limit,
+                reserve_mint_limit,
+            )
+        }
+        EceTokenInstruction::PublishReserveSnapshot {
+            merkle_root,
+            total_balances,
+            holder_count,
+            snapshot_slot,
+        } => {
+            msg!("Instruction: Publish Reserve Snapshot");
+            Processor::process_publish_reserve_snapshot(
+                program_id,
+                accounts,
+                merkle_root,
+                total_balances,
+                holder_count,
+                snapshot_slot,
+            )
+        }
+        EceTokenInstruction::CreateDistributor {
+            distributor_id,
+            merkle_root,
+            total_amount,
+            num_claims,
+            expires_at,
+        } => {
+            msg!("Instruction: Create Distributor");
+            Processor::process_create_distributor(
+                program_id,
+                accounts,
+                distributor_id,
+                merkle_root,
+                total_amount,
+                num_claims,
+                expires_at,
+            )
+        }
+        EceTokenInstruction::Claim { index, amount, proof } => {
+            msg!("Instruction: Claim");
+            Processor::process_claim(program_id, accounts, index, amount, proof)
+        }
+        EceTokenInstruction::ClawbackDistributor => {
+            msg!("Instruction: Clawback Distributor");
+            Processor::process_clawback_distributor(program_id, accounts)
+        }
+        EceTokenInstruction::CreateVesting {
+            vesting_id,
+            beneficiary,
+            total_amount,
+            start_timestamp,
+            cliff_timestamp,
+            end_timestamp,
+            revocable,
+        } => {
+            msg!("Instruction: Create Vesting");
+            Processor::process_create_vesting(
+                program_id,
+                accounts,
+                vesting_id,
+                beneficiary,
+                total_amount,
+                start_timestamp,
+                cliff_timestamp,
+                end_timestamp,
+                revocable,
+            )
+        }
+        EceTokenInstruction::Release => {
+            msg!("Instruction: Release");
+            Processor::process_release(program_id, accounts)
+        }
+        EceTokenInstruction::RevokeVesting => {
+            msg!("Instruction: Revoke Vesting");
+            Processor::process_revoke_vesting(program_id, accounts)
+        }
+        EceTokenInstruction::ConfigureReferrals {
+            reward_bps,
+            max_reward_per_referrer,
+        } => {
+            msg!("Instruction: Configure Referrals");
+            Processor::process_configure_referrals(program_id, accounts, reward_bps, max_reward_per_referrer)
+        }
+        EceTokenInstruction::RegisterReferral { referrer } => {
+            msg!("Instruction: Register Referral");
+            Processor::process_register_referral(program_id, accounts, referrer)
+        }
+        EceTokenInstruction::ClaimReferralRewards => {
+            msg!("Instruction: Claim Referral Rewards");
+            Processor::process_claim_referral_rewards(program_id, accounts)
+        }
+        EceTokenInstruction::CreateTradeOffer {
+            offer_id,
+            offered_card_mint,
+            offered_ece_amount,
+            requested_card_mint,
+            requested_ece_amount,
+            taker,
+            expires_at,
+        } => {
+            msg!("Instruction: Create Trade Offer");
+            Processor::process_create_trade_offer(
+                program_id,
+                accounts,
+                offer_id,
+                offered_card_mint,
+                offered_ece_amount,
+                requested_card_mint,
+                requested_ece_amount,
+                taker,
+                expires_at,
+            )
+        }
+        EceTokenInstruction::FillTradeOffer => {
+            msg!("Instruction: Fill Trade Offer");
+            Processor::process_fill_trade_offer(program_id, accounts)
+        }
+        EceTokenInstruction::CancelTradeOffer => {
+            msg!("Instruction: Cancel Trade Offer");
+            Processor::process_cancel_trade_offer(program_id, accounts)
+        }
+        EceTokenInstruction::SetCompanyUsdcAccount => {
+            msg!("Instruction: Set Company USDC Account");
+            Processor::process_set_company_usdc_account(program_id, accounts)
+        }
+        EceTokenInstruction::CancelRedemption => {
+            msg!("Instruction: Cancel Redemption");
+            Processor::process_cancel_redemption(program_id, accounts)
+        }
+        EceTokenInstruction::TripCircuitBreaker => {
+            msg!("Instruction: Trip Circuit Breaker");
+            Processor::process_trip_circuit_breaker(program_id, accounts)
+        }
     }
 }
@@ -0,0 +1,101 @@
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+/// Domain separators so a leaf can never be passed off as an inner node
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Leaf for the claim at `index` paying `amount` ECE to `claimant`
+pub fn claim_leaf(index: u32, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, &index.to_le_bytes(), claimant.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+/// Parent of two nodes; the pair is sorted so proofs don't need to say which side a sibling is on
+fn parent(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Check a proof from `leaf` up to `root`
+pub fn verify_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| parent(&node, sibling));
+    &computed == root
+}
+
+/// Off-chain Merkle tree over a campaign's claims; the claim index is the
+/// recipient's position in the list
+#[derive(Debug, Clone)]
+pub struct DistributionTree {
+    /// Recipients and the ECE amount each can claim
+    pub claims: Vec<(Pubkey, u64)>,
+
+    /// Tree levels from the leaves up to the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl DistributionTree {
+    pub fn new(claims: &[(Pubkey, u64)]) -> Self {
+        let mut levels = vec![claims
+            .iter()
+            .enumerate()
+            .map(|(index, (claimant, amount))| claim_leaf(index as u32, claimant, *amount))
+            .collect::<Vec<_>>()];
+
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let parents = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => parent(left, right),
+                    // An unpaired node moves up unchanged
+                    _ => pair[0],
+                })
+                .collect();
+            levels.push(parents);
+        }
+
+        Self {
+            claims: claims.to_vec(),
+            levels,
+        }
+    }
+
+    /// Merkle root to publish with the distributor
+    pub fn root(&self) -> [u8; 32] {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or([0; 32])
+    }
+
+    /// Total ECE the distributor vault must be funded with
+    pub fn total_amount(&self) -> Option<u64> {
+        self.claims
+            .iter()
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+    }
+
+    /// Number of claims in the tree
+    pub fn num_claims(&self) -> u32 {
+        self.claims.len() as u32
+    }
+
+    /// Proof for the claim at `index`
+    pub fn proof(&self, index: u32) -> Option<Vec<[u8; 32]>> {
+        let mut index = index as usize;
+        if index >= self.claims.len() {
+            return None;
+        }
+
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+
+        Some(proof)
+    }
+}
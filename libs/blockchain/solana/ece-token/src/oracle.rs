@@ -0,0 +1,123 @@
+use crate::error::EceTokenError;
+
+/// Magic number at the start of every Pyth account
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+
+/// Pyth account type for price accounts
+pub const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+
+/// Pyth aggregate status for a price that is actively trading
+pub const PYTH_STATUS_TRADING: u32 = 1;
+
+/// Byte offsets into a Pyth v2 price account
+const MAGIC_OFFSET: usize = 0;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+const EXPONENT_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+
+/// Minimum length of a Pyth v2 price account we read from
+pub const PYTH_PRICE_ACCOUNT_LEN: usize = 240;
+
+/// Aggregate price read from a Pyth price account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OraclePrice {
+    /// Aggregate price, scaled by 10^exponent
+    pub price: i64,
+
+    /// Confidence interval, scaled by 10^exponent
+    pub confidence: u64,
+
+    /// Price exponent
+    pub exponent: i32,
+
+    /// Aggregate status
+    pub status: u32,
+
+    /// Unix timestamp of the last update
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Parse the aggregate price from Pyth v2 price account data
+    pub fn from_pyth_account_data(data: &[u8]) -> Result<Self, EceTokenError> {
+        if data.len() < PYTH_PRICE_ACCOUNT_LEN
+            || read_u32(data, MAGIC_OFFSET) != PYTH_MAGIC
+            || read_u32(data, ACCOUNT_TYPE_OFFSET) != PYTH_PRICE_ACCOUNT_TYPE
+        {
+            return Err(EceTokenError::InvalidOracleAccount);
+        }
+
+        Ok(Self {
+            price: read_u64(data, AGG_PRICE_OFFSET) as i64,
+            confidence: read_u64(data, AGG_CONF_OFFSET),
+            exponent: read_u32(data, EXPONENT_OFFSET) as i32,
+            status: read_u32(data, AGG_STATUS_OFFSET),
+            publish_time: read_u64(data, TIMESTAMP_OFFSET) as i64,
+        })
+    }
+
+    /// Encode as Pyth v2 price account data (used to mock the oracle)
+    pub fn to_pyth_account_data(&self) -> Vec<u8> {
+        let mut data = vec![0u8; PYTH_PRICE_ACCOUNT_LEN];
+        data[MAGIC_OFFSET..MAGIC_OFFSET + 4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        data[ACCOUNT_TYPE_OFFSET..ACCOUNT_TYPE_OFFSET + 4]
+            .copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[EXPONENT_OFFSET..EXPONENT_OFFSET + 4].copy_from_slice(&self.exponent.to_le_bytes());
+        data[TIMESTAMP_OFFSET..TIMESTAMP_OFFSET + 8].copy_from_slice(&self.publish_time.to_le_bytes());
+        data[AGG_PRICE_OFFSET..AGG_PRICE_OFFSET + 8].copy_from_slice(&self.price.to_le_bytes());
+        data[AGG_CONF_OFFSET..AGG_CONF_OFFSET + 8].copy_from_slice(&self.confidence.to_le_bytes());
+        data[AGG_STATUS_OFFSET..AGG_STATUS_OFFSET + 4].copy_from_slice(&self.status.to_le_bytes());
+        data
+    }
+
+    /// Price in basis points of one dollar (10000 = $1.00)
+    pub fn price_bps(&self) -> Option<u64> {
+        if self.price <= 0 {
+            return None;
+        }
+
+        let price = self.price as u128 * 10000;
+        let scaled = if self.exponent >= 0 {
+            price.checked_mul(10u128.checked_pow(self.exponent as u32)?)?
+        } else {
+            price / 10u128.checked_pow(self.exponent.unsigned_abs())?
+        };
+        u64::try_from(scaled).ok()
+    }
+
+    /// Check the price is fresh, trading and within `max_deviation_bps` of $1.00
+    pub fn check_peg(
+        &self,
+        current_timestamp: i64,
+        max_staleness: i64,
+        max_deviation_bps: u16,
+    ) -> Result<(), EceTokenError> {
+        if self.status != PYTH_STATUS_TRADING
+            || current_timestamp.saturating_sub(self.publish_time) > max_staleness
+        {
+            return Err(EceTokenError::OraclePriceStale);
+        }
+
+        let price_bps = self.price_bps().ok_or(EceTokenError::ConversionRateOutOfBounds)?;
+        if price_bps.abs_diff(10000) > max_deviation_bps as u64 {
+            return Err(EceTokenError::ConversionRateOutOfBounds);
+        }
+
+        Ok(())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
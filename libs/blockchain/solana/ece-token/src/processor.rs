@@ -1,24 +1,73 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    instruction::Instruction,
     msg,
     program::{invoke, invoke_signed},
+    program_option::COption,
     program_error::ProgramError,
-    program_pack::{IsInitialized, Pack},
+    program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
     system_instruction,
     sysvar::{clock::Clock, Sysvar},
 };
-use spl_token::{
+use spl_token_2022::{
+    extension::{
+        metadata_pointer, transfer_fee, transfer_hook, transfer_hook::TransferHookAccount,
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
     instruction as token_instruction,
+    onchain,
     state::{Account as TokenAccount, Mint},
 };
+use spl_token_metadata_interface::{instruction as metadata_instruction, state::TokenMetadata};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::{
+    collect_extra_account_metas_signer_seeds,
+    get_extra_account_metas_address_and_bump_seed,
+    instruction::ExecuteInstruction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction as associated_token_instruction,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 
 use crate::{
     error::EceTokenError,
-    state::{TreasuryState, WeeklyPayoutRecord, ComplianceRecord, TransactionType},
+    merkle_distributor::{claim_leaf, verify_proof},
+    oracle::OraclePrice,
+    state::{
+        TreasuryState,
+        StakingPool, StakeAccount, LockupTier,
+        GovernanceConfig, Proposal, VoteRecord, TreasuryParameterChange,
+        FeeSplitConfig, FeeRecipient, RoyaltyRegistry, RoyaltyCreator,
+        CardCollection, CardRecord,
+        SubscriptionPlan, Subscription, SubscriptionStatus,
+        RedemptionQueue, RedemptionTicket, PendingWithdrawal, WithdrawalStatus,
+        CircuitBreakerReason, AuthorityKind, Role, RoleRegistry, RoleAssignment, ROLE_REGISTRY_SEED, MAX_ROLE_HOLDERS,
+        Denylist, DENYLIST_SEED, MAX_DENYLISTED_WALLETS, KycRecord, KYC_RECORD_SEED,
+        ReserveAttestation, RESERVE_ATTESTATION_SEED,
+        MerkleDistributor, DISTRIBUTOR_SEED, DISTRIBUTOR_VAULT_SEED, MAX_DISTRIBUTOR_CLAIMS,
+        VestingSchedule, VESTING_SEED, VESTING_VAULT_SEED,
+        ReferralConfig, ReferralRecord, ReferrerAccount, REFERRAL_CONFIG_SEED, REFERRAL_VAULT_SEED,
+        REFERRAL_SEED, REFERRER_SEED, MAX_REFERRAL_REWARD_BPS,
+        TradeOffer, TradeOfferStatus, TRADE_OFFER_SEED, TRADE_CARD_VAULT_SEED, TRADE_ECE_VAULT_SEED,
+        CollateralAsset, CollateralRegistry, COLLATERAL_REGISTRY_SEED, COLLATERAL_RESERVE_SEED,
+        MAX_COLLATERAL_ASSETS, MAX_COLLATERAL_HAIRCUT_BPS,
+        PAUSE_MINT, PAUSE_BURN, PAUSE_DEPOSIT, PAUSE_WITHDRAW, PAUSE_PAYOUT, PAUSE_ESCROW,
+        PAUSE_STAKING, PAUSE_REWARDS, PAUSE_CARDS, PAUSE_SUBSCRIPTIONS, PAUSE_ALL,
+        STAKING_POOL_SEED, STAKE_VAULT_SEED, REWARD_VAULT_SEED, STAKE_ACCOUNT_SEED,
+        GOVERNANCE_SEED, PROPOSAL_SEED, VOTE_RECORD_SEED, FEE_SPLIT_SEED, MAX_FEE_RECIPIENTS,
+        ROYALTY_SEED, MAX_ROYALTY_CREATORS, MAX_ROYALTY_BPS,
+        CARD_COLLECTION_SEED, CARD_MINT_SEED, CARD_RECORD_SEED,
+        SUBSCRIPTION_PLAN_SEED, SUBSCRIPTION_SEED,
+        REDEMPTION_QUEUE_SEED, REDEMPTION_ESCROW_SEED, REDEMPTION_TICKET_SEED,
+        PENDING_WITHDRAWAL_SEED, MAX_MINT_REDEEM_FEE_BPS,
+        MINT_AUTHORITY_SEED, TREASURY_AUTHORITY_SEED,
+        MAX_TOKEN_NAME_LEN, MAX_TOKEN_SYMBOL_LEN, MAX_TOKEN_URI_LEN, MAX_DECIMALS_DIFFERENCE,
+    },
 };
 
 pub struct Processor;
@@ -106,7 +155,7 @@ impl Processor {
         )?;
 
         // Initialize treasury state
-        let mut treasury_state = TreasuryState {
+        let treasury_state = TreasuryState {
             is_initialized: true,
             signers,
             threshold,
@@ -116,6 +165,30 @@ impl Processor {
             ece_circulation: 0,
             usdc_reserves: 0,
             is_paused: false,
+            pause_flags: 0,
+            pending_emergency_authority: Pubkey::default(),
+            pending_compliance_authority: Pubkey::default(),
+            min_signer_threshold: threshold,
+            signer_epoch: 0,
+            usdc_oracle: Pubkey::default(), // no oracle until configured
+            max_peg_deviation_bps: 50, // 0.5% band around $1.00
+            max_oracle_staleness: 60, // 1 minute
+            mint_fee_bps: 0,
+            redeem_fee_bps: 0,
+            fee_usdc_account: Pubkey::default(),
+            total_fees_collected: 0,
+            ece_token_program: spl_token::id(),
+            collateral_reserves: 0,
+            ece_decimals: 6,
+            usdc_decimals: 6,
+            user_mint_limit: 10_000_000_000, // 10,000 USDC per self-service mint
+            user_daily_mint_limit: 25_000_000_000, // 25,000 USDC per wallet per day
+            reserve_mint_limit: 100_000_000_000, // 100,000 USDC per admin mint
+            fee_ece_account: Pubkey::default(),
+            window_withdrawals: 0,
+            pending_timelock_threshold: 0,
+            pending_timelock_delay: 0,
+            pending_timelock_at: 0,
             emergency_authority: *initializer.key,
             last_payout_timestamp: 0,
             payout_window: 604800, // 7 days in seconds
@@ -129,6 +202,19 @@ impl Processor {
             daily_volume_limit: 100_000_000_000, // 100,000 USDC daily limit
             current_daily_volume: 0,
             last_volume_reset: 0,
+            marketplace_fee_bps: 250, // 2.5% platform fee on card sales
+            queued_redemptions: 0,
+            withdrawal_timelock_threshold: 50_000_000_000, // 50,000 USDC
+            withdrawal_timelock_delay: 172800, // 48 hours
+            withdrawal_count: 0,
+            outflow_window: 86400, // 24 hours
+            max_window_outflow_bps: 2500, // 25% of reserves per window
+            reserve_floor_bps: 9500, // 95% hard floor
+            window_outflow: 0,
+            previous_window_outflow: 0,
+            outflow_window_start: 0,
+            breaker_trip_reason: CircuitBreakerReason::None,
+            breaker_tripped_at: 0,
         };
 
         treasury_state.serialize(&mut &mut treasury_account.data.borrow_mut()[..])?;
@@ -138,35 +224,47 @@ impl Processor {
         Ok(())
     }
 
-    /// Mint ECE tokens backed by USDC deposits
-    pub fn process_mint_tokens(
+    /// Mint ECE to a KYC-verified user's associated token account against their own USDC
+    pub fn process_mint_for_usdc(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         amount: u64,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
-        let authority = next_account_info(account_info_iter)?;
+        let user = next_account_info(account_info_iter)?;
         let ece_mint = next_account_info(account_info_iter)?;
         let destination_ece_account = next_account_info(account_info_iter)?;
         let source_usdc_account = next_account_info(account_info_iter)?;
         let treasury_usdc_account = next_account_info(account_info_iter)?;
         let treasury_state_account = next_account_info(account_info_iter)?;
+        let kyc_record_account = next_account_info(account_info_iter)?;
+        let mint_authority = next_account_info(account_info_iter)?;
         let token_program = next_account_info(account_info_iter)?;
         let clock_sysvar = next_account_info(account_info_iter)?;
 
-        if !authority.is_signer {
+        if !user.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
         let clock = Clock::from_account_info(clock_sysvar)?;
-        let mut treasury_state = TreasuryState::try_from_slice(&treasury_state_account.data.borrow())?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
 
-        if treasury_state.is_paused {
+        if treasury_state.is_operation_paused(PAUSE_MINT) {
             return Err(EceTokenError::EmergencyPauseActive.into());
         }
 
+        let mut kyc_record =
+            load_kyc_record(program_id, kyc_record_account, treasury_state_account.key, user.key)?;
+        if !kyc_record.is_eligible(clock.unix_timestamp) {
+            return Err(EceTokenError::KycNotVerified.into());
+        }
+
         // Check compliance limits
-        if amount > treasury_state.max_transaction_amount {
+        if amount > treasury_state.max_transaction_amount || amount > treasury_state.user_mint_limit {
+            return Err(EceTokenError::ComplianceCheckFailed.into());
+        }
+
+        if kyc_record.would_exceed_daily_mint(amount, treasury_state.user_daily_mint_limit, clock.unix_timestamp) {
             return Err(EceTokenError::ComplianceCheckFailed.into());
         }
 
@@ -174,14 +272,42 @@ impl Processor {
             return Err(EceTokenError::ComplianceCheckFailed.into());
         }
 
+        check_usdc_peg(accounts, &treasury_state, clock.unix_timestamp)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_usdc_reserve(treasury_usdc_account, token_program, &treasury_state)?;
+
+        let ece_token_program = find_ece_token_program(accounts, &treasury_state, token_program)?;
+
+        let user_ece_account =
+            get_associated_token_address_with_program_id(user.key, ece_mint.key, ece_token_program.key);
+        if destination_ece_account.key != &user_ece_account {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        let (mint_authority_address, mint_authority_bump) =
+            Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], program_id);
+        if mint_authority.key != &mint_authority_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        // The mint fee is taken in USDC; the rest backs ECE 1:1, rounding the ECE down
+        let fee = treasury_state.mint_fee(amount).ok_or(EceTokenError::NumericalOverflow)?;
+        let net_amount = amount - fee;
+        let ece_amount = treasury_state.usdc_to_ece(net_amount).ok_or(EceTokenError::NumericalOverflow)?;
+        if ece_amount == 0 {
+            return Err(EceTokenError::InvalidUsdcAmount.into());
+        }
+
         // Transfer USDC from user to treasury reserves (1:1 ratio)
-        let transfer_usdc_ix = token_instruction::transfer(
+        let reserve_before = unpack_token_account(treasury_usdc_account)?.amount;
+
+        let transfer_usdc_ix = transfer_instruction(
             token_program.key,
             source_usdc_account.key,
             treasury_usdc_account.key,
-            authority.key,
+            user.key,
             &[],
-            amount,
+            net_amount,
         )?;
 
         invoke(
@@ -189,45 +315,74 @@ impl Processor {
             &[
                 source_usdc_account.clone(),
                 treasury_usdc_account.clone(),
-                authority.clone(),
+                user.clone(),
                 token_program.clone(),
             ],
         )?;
 
-        // Mint ECE tokens to user
-        let mint_ece_ix = token_instruction::mint_to(
-            token_program.key,
-            ece_mint.key,
-            destination_ece_account.key,
-            program_id, // Program is mint authority
-            &[],
-            amount,
-        )?;
+        // ECE is only minted against USDC that actually reached the reserve
+        let reserve_after = unpack_token_account(treasury_usdc_account)?.amount;
+        if reserve_after.checked_sub(reserve_before) != Some(net_amount) {
+            return Err(EceTokenError::InvalidUsdcAmount.into());
+        }
+
+        if fee > 0 {
+            let fee_usdc_account = find_fee_account(accounts, &treasury_state)?;
 
-        let seeds = &[b"ece-mint-authority"];
-        let seeds_slice: &[&[u8]] = &[b"ece-mint-authority"];
-        let (_, bump_seed) = Pubkey::find_program_address(seeds_slice, program_id);
-        let signer_seeds = &[b"ece-mint-authority", &[bump_seed]];
+            invoke(
+                &transfer_instruction(
+                    token_program.key,
+                    source_usdc_account.key,
+                    fee_usdc_account.key,
+                    user.key,
+                    &[],
+                    fee,
+                )?,
+                &[
+                    source_usdc_account.clone(),
+                    fee_usdc_account.clone(),
+                    user.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
 
+        // Mint ECE tokens to user
         invoke_signed(
-            &mint_ece_ix,
+            &token_instruction::mint_to(
+                ece_token_program.key,
+                ece_mint.key,
+                destination_ece_account.key,
+                &mint_authority_address,
+                &[],
+                ece_amount,
+            )?,
             &[
                 ece_mint.clone(),
                 destination_ece_account.clone(),
-                treasury_state_account.clone(),
-                token_program.clone(),
+                mint_authority.clone(),
+                ece_token_program.clone(),
             ],
-            &[signer_seeds],
+            &[&[MINT_AUTHORITY_SEED, &[mint_authority_bump]]],
         )?;
 
+        kyc_record.record_mint(amount, clock.unix_timestamp);
+        kyc_record.serialize(&mut &mut kyc_record_account.data.borrow_mut()[..])?;
+
+        accrue_referral_reward(program_id, accounts, treasury_state_account.key, user.key, ece_amount)?;
+
         // Update treasury state
-        treasury_state.ece_circulation += amount;
-        treasury_state.usdc_reserves += amount;
+        treasury_state.ece_circulation += ece_amount;
+        treasury_state.usdc_reserves += net_amount;
+        treasury_state.total_fees_collected = treasury_state
+            .total_fees_collected
+            .checked_add(fee)
+            .ok_or(EceTokenError::NumericalOverflow)?;
         treasury_state.update_daily_volume(amount, clock.unix_timestamp);
 
         treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
 
-        msg!("Minted {} ECE tokens backed by {} USDC", amount, amount);
+        msg!("Minted {} ECE tokens backed by {} USDC ({} USDC fee)", ece_amount, net_amount, fee);
         Ok(())
     }
 
@@ -246,31 +401,72 @@ impl Processor {
         let treasury_state_account = next_account_info(account_info_iter)?;
         let token_program = next_account_info(account_info_iter)?;
         let clock_sysvar = next_account_info(account_info_iter)?;
+        let treasury_authority = next_account_info(account_info_iter)?;
 
         if !authority.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
         let clock = Clock::from_account_info(clock_sysvar)?;
-        let mut treasury_state = TreasuryState::try_from_slice(&treasury_state_account.data.borrow())?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
 
-        if treasury_state.is_paused {
+        if treasury_state.is_operation_paused(PAUSE_BURN) {
             return Err(EceTokenError::EmergencyPauseActive.into());
         }
 
         // Check if treasury has sufficient USDC reserves
         if !treasury_state.has_sufficient_reserves(amount) {
+            msg!("Insufficient reserves, use QueueRedemption to join the redemption queue");
+            return Err(EceTokenError::UsdcReserveInsufficient.into());
+        }
+
+        if !treasury_state.within_usdc_share(amount) {
+            msg!("Redemption exceeds the USDC share of reserves");
             return Err(EceTokenError::UsdcReserveInsufficient.into());
         }
 
+        // USDC released for the burned ECE, rounded down
+        let usdc_amount = treasury_state.ece_to_usdc(amount).ok_or(EceTokenError::NumericalOverflow)?;
+        if usdc_amount == 0 {
+            return Err(EceTokenError::InvalidUsdcAmount.into());
+        }
+
         // Check compliance limits
-        if amount > treasury_state.max_transaction_amount {
+        if usdc_amount > treasury_state.max_transaction_amount {
             return Err(EceTokenError::ComplianceCheckFailed.into());
         }
 
+        check_usdc_peg(accounts, &treasury_state, clock.unix_timestamp)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_usdc_reserve(treasury_usdc_account, token_program, &treasury_state)?;
+        let authority_bump = check_treasury_authority(program_id, treasury_authority)?;
+
+        let ece_token_program = find_ece_token_program(accounts, &treasury_state, token_program)?;
+
+        // Only a burn the holder could actually make gets to trip the breaker
+        let source = unpack_token_account(source_ece_account)?;
+        let may_burn = source.owner == *authority.key
+            || (source.delegate == COption::Some(*authority.key) && source.delegated_amount >= amount);
+        if source_ece_account.owner != ece_token_program.key || source.mint != *ece_mint.key || !may_burn {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+        if source.amount < amount {
+            return Err(EceTokenError::InsufficientFunds.into());
+        }
+
+        if check_circuit_breaker(
+            treasury_state_account,
+            &mut treasury_state,
+            usdc_amount,
+            amount,
+            clock.unix_timestamp,
+        )? {
+            return Ok(());
+        }
+
         // Burn ECE tokens
         let burn_ece_ix = token_instruction::burn(
-            token_program.key,
+            ece_token_program.key,
             source_ece_account.key,
             ece_mint.key,
             authority.key,
@@ -284,42 +480,48 @@ impl Processor {
                 source_ece_account.clone(),
                 ece_mint.clone(),
                 authority.clone(),
-                token_program.clone(),
+                ece_token_program.clone(),
             ],
         )?;
 
+        // The redeem fee is withheld from the released USDC
+        let fee = treasury_state.redeem_fee(usdc_amount).ok_or(EceTokenError::NumericalOverflow)?;
+        let net_amount = usdc_amount - fee;
+
         // Transfer USDC from treasury to user
-        let transfer_usdc_ix = token_instruction::transfer(
-            token_program.key,
-            treasury_usdc_account.key,
-            destination_usdc_account.key,
-            program_id, // Program is treasury authority
-            &[],
-            amount,
+        transfer_from_reserve(
+            token_program,
+            treasury_usdc_account,
+            destination_usdc_account,
+            treasury_authority,
+            authority_bump,
+            net_amount,
         )?;
 
-        let seeds = &[b"ece-treasury-authority"];
-        let (_, bump_seed) = Pubkey::find_program_address(seeds, program_id);
-        let signer_seeds = &[&seeds[..], &[bump_seed]];
-
-        invoke_signed(
-            &transfer_usdc_ix,
-            &[
-                treasury_usdc_account.clone(),
-                destination_usdc_account.clone(),
-                treasury_state_account.clone(),
-                token_program.clone(),
-            ],
-            &[signer_seeds],
-        )?;
+        if fee > 0 {
+            let fee_usdc_account = find_fee_account(accounts, &treasury_state)?;
+            transfer_from_reserve(
+                token_program,
+                treasury_usdc_account,
+                fee_usdc_account,
+                treasury_authority,
+                authority_bump,
+                fee,
+            )?;
+        }
 
         // Update treasury state
         treasury_state.ece_circulation -= amount;
-        treasury_state.usdc_reserves -= amount;
+        treasury_state.usdc_reserves -= usdc_amount;
+        treasury_state.total_fees_collected = treasury_state
+            .total_fees_collected
+            .checked_add(fee)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.record_outflow(usdc_amount);
 
         treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
 
-        msg!("Burned {} ECE tokens and released {} USDC", amount, amount);
+        msg!("Burned {} ECE tokens and released {} USDC ({} USDC fee)", amount, net_amount, fee);
         Ok(())
     }
 
@@ -349,9 +551,11 @@ impl Processor {
         }
 
         let clock = Clock::from_account_info(clock_sysvar)?;
-        let mut treasury_state = TreasuryState::try_from_slice(&treasury_state_account.data.borrow())?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
 
-        if treasury_state.is_paused {
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if treasury_state.is_operation_paused(PAUSE_PAYOUT) {
             return Err(EceTokenError::EmergencyPauseActive.into());
         }
 
@@ -360,18 +564,35 @@ impl Processor {
             return Err(EceTokenError::WeeklyPayoutWindowNotActive.into());
         }
 
-        // Calculate payout amounts
+        // Calculate payout amounts; the burned ECE is paid out in USDC rounded down
         let payout_amount = (revenue_amount as u128 * payout_percentage as u128 / 100) as u64;
         let remaining_ece = revenue_amount - payout_amount;
+        let usdc_amount = treasury_state.ece_to_usdc(payout_amount).ok_or(EceTokenError::NumericalOverflow)?;
 
         // Check if treasury has sufficient USDC for the payout
-        if !treasury_state.has_sufficient_reserves(payout_amount) {
+        if !treasury_state.has_available_usdc(usdc_amount) || !treasury_state.within_usdc_share(payout_amount) {
             return Err(EceTokenError::UsdcReserveInsufficient.into());
         }
 
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_usdc_reserve(treasury_usdc_account, token_program, &treasury_state)?;
+
+        let ece_token_program = find_ece_token_program(accounts, &treasury_state, token_program)?;
+
+        // Checked before the burn, so a trip leaves the company's ECE untouched
+        if check_circuit_breaker(
+            treasury_state_account,
+            &mut treasury_state,
+            usdc_amount,
+            payout_amount,
+            clock.unix_timestamp,
+        )? {
+            return Ok(());
+        }
+
         // Burn ECE tokens from company revenue
         let burn_ece_ix = token_instruction::burn(
-            token_program.key,
+            ece_token_program.key,
             company_ece_account.key,
             ece_mint.key,
             authority.key,
@@ -385,46 +606,78 @@ impl Processor {
                 company_ece_account.clone(),
                 ece_mint.clone(),
                 authority.clone(),
-                token_program.clone(),
+                ece_token_program.clone(),
             ],
         )?;
 
-        // Transfer equivalent USDC to company
-        let transfer_usdc_ix = token_instruction::transfer(
-            token_program.key,
-            treasury_usdc_account.key,
-            company_usdc_account.key,
+        // Route the USDC through the fee split table once it exists,
+        // otherwise everything goes to the configured company account
+        let fee_split_account = next_account_info(account_info_iter)?;
+        let (fee_split_address, _) = Pubkey::find_program_address(
+            &[FEE_SPLIT_SEED, treasury_state_account.key.as_ref()],
             program_id,
-            &[],
-            payout_amount,
-        )?;
+        );
+        if fee_split_account.key != &fee_split_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
 
-        let seeds = &[b"ece-treasury-authority"];
-        let (_, bump_seed) = Pubkey::find_program_address(seeds, program_id);
-        let signer_seeds = &[&seeds[..], &[bump_seed]];
+        let treasury_authority = next_account_info(account_info_iter)?;
+        let authority_bump = check_treasury_authority(program_id, treasury_authority)?;
 
-        invoke_signed(
-            &transfer_usdc_ix,
-            &[
-                treasury_usdc_account.clone(),
-                company_usdc_account.clone(),
-                treasury_state_account.clone(),
-                token_program.clone(),
-            ],
-            &[signer_seeds],
-        )?;
+        let distribution: Vec<(&AccountInfo, u64)> = if fee_split_account.data_is_empty() {
+            if treasury_state.company_usdc_account == Pubkey::default()
+                || company_usdc_account.key != &treasury_state.company_usdc_account
+            {
+                return Err(EceTokenError::InvalidTokenAccount.into());
+            }
+            vec![(company_usdc_account, usdc_amount)]
+        } else {
+            let fee_split = load_fee_split(program_id, fee_split_account, treasury_state_account.key)?;
+            let shares = fee_split
+                .split_amount(usdc_amount)
+                .ok_or(EceTokenError::NumericalOverflow)?;
+
+            let mut distribution = Vec::with_capacity(shares.len());
+            for (recipient, share) in fee_split.active_recipients().iter().zip(shares) {
+                let recipient_account = next_account_info(account_info_iter)?;
+                if recipient_account.key != &recipient.usdc_account {
+                    return Err(EceTokenError::InvalidTokenAccount.into());
+                }
+                distribution.push((recipient_account, share));
+            }
+            distribution
+        };
+
+        for (recipient_account, share) in distribution {
+            if share == 0 {
+                continue;
+            }
+
+            transfer_from_reserve(
+                token_program,
+                treasury_usdc_account,
+                recipient_account,
+                treasury_authority,
+                authority_bump,
+                share,
+            )?;
+        }
 
         // Update treasury state
-        treasury_state.ece_circulation -= payout_amount;
-        treasury_state.usdc_reserves -= payout_amount;
+        treasury_state.ece_circulation = treasury_state
+            .ece_circulation
+            .checked_sub(payout_amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.usdc_reserves -= usdc_amount;
+        treasury_state.record_outflow(usdc_amount);
         treasury_state.last_payout_timestamp = clock.unix_timestamp;
         treasury_state.total_revenue_processed += revenue_amount;
         treasury_state.payout_count += 1;
 
         treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
 
-        msg!("Processed weekly payout: {} ECE burned, {} USDC released to company, {} ECE retained",
-             payout_amount, payout_amount, remaining_ece);
+        msg!("Processed weekly payout: {} ECE burned, {} USDC released, {} ECE retained",
+             payout_amount, usdc_amount, remaining_ece);
         Ok(())
     }
 
@@ -440,21 +693,24 @@ impl Processor {
         let treasury_usdc_account = next_account_info(account_info_iter)?;
         let treasury_state_account = next_account_info(account_info_iter)?;
         let token_program = next_account_info(account_info_iter)?;
-        let clock_sysvar = next_account_info(account_info_iter)?;
+        let _clock_sysvar = next_account_info(account_info_iter)?;
 
         if !depositor.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let clock = Clock::from_account_info(clock_sysvar)?;
-        let mut treasury_state = TreasuryState::try_from_slice(&treasury_state_account.data.borrow())?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
 
-        if treasury_state.is_paused {
+        if treasury_state.is_operation_paused(PAUSE_DEPOSIT) {
             return Err(EceTokenError::EmergencyPauseActive.into());
         }
 
+        check_usdc_reserve(treasury_usdc_account, token_program, &treasury_state)?;
+
         // Transfer USDC to treasury
-        let transfer_ix = token_instruction::transfer(
+        let reserve_before = unpack_token_account(treasury_usdc_account)?.amount;
+
+        let transfer_ix = transfer_instruction(
             token_program.key,
             source_usdc_account.key,
             treasury_usdc_account.key,
@@ -473,11 +729,20 @@ impl Processor {
             ],
         )?;
 
+        // Credit only what actually reached the reserve
+        let received = unpack_token_account(treasury_usdc_account)?
+            .amount
+            .checked_sub(reserve_before)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
         // Update treasury reserves
-        treasury_state.usdc_reserves += amount;
+        treasury_state.usdc_reserves = treasury_state
+            .usdc_reserves
+            .checked_add(received)
+            .ok_or(EceTokenError::NumericalOverflow)?;
         treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
 
-        msg!("Deposited {} USDC to treasury reserves", amount);
+        msg!("Deposited {} USDC to treasury reserves", received);
         Ok(())
     }
 
@@ -494,58 +759,121 @@ impl Processor {
         let treasury_state_account = next_account_info(account_info_iter)?;
         let token_program = next_account_info(account_info_iter)?;
         let clock_sysvar = next_account_info(account_info_iter)?;
+        let treasury_authority = next_account_info(account_info_iter)?;
 
-        // Validate multi-signature authority (simplified for example)
         if !authority.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let mut treasury_state = TreasuryState::try_from_slice(&treasury_state_account.data.borrow())?;
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
 
-        if treasury_state.is_paused {
+        if treasury_state.is_operation_paused(PAUSE_WITHDRAW) {
             return Err(EceTokenError::EmergencyPauseActive.into());
         }
 
-        if treasury_state.usdc_reserves < amount {
+        check_usdc_reserve(treasury_usdc_account, token_program, &treasury_state)?;
+        let authority_bump = check_treasury_authority(program_id, treasury_authority)?;
+
+        if !treasury_state.has_available_usdc(amount) {
             return Err(EceTokenError::UsdcReserveInsufficient.into());
         }
 
         // Check if withdrawal would compromise reserve ratio
-        let new_reserves = treasury_state.usdc_reserves - amount;
-        if treasury_state.ece_circulation > 0 {
-            let new_ratio = (new_reserves as u128 * 10000) / treasury_state.ece_circulation as u128;
-            if new_ratio < treasury_state.min_reserve_ratio as u128 {
-                return Err(EceTokenError::ReserveRatioBelowMinimum.into());
+        if treasury_state.would_breach_min_reserve_ratio(amount) {
+            return Err(EceTokenError::ReserveRatioBelowMinimum.into());
+        }
+
+        // Large withdrawals, alone or together with others this window, become
+        // pending behind the timelock instead of executing now
+        treasury_state.roll_outflow_window(clock.unix_timestamp);
+        if treasury_state.requires_withdrawal_timelock(amount) {
+            let pending_withdrawal_account = next_account_info(account_info_iter)?;
+            let system_program = next_account_info(account_info_iter)?;
+            let rent_sysvar = next_account_info(account_info_iter)?;
+
+            // Withdrawals large enough for the timelock need the signer quorum, not one admin
+            verify_multisig_approval(&treasury_state, accounts)?;
+
+            let withdrawal_id = treasury_state.withdrawal_count;
+            let (pending_address, bump) = Pubkey::find_program_address(
+                &[
+                    PENDING_WITHDRAWAL_SEED,
+                    treasury_state_account.key.as_ref(),
+                    &withdrawal_id.to_le_bytes(),
+                ],
+                program_id,
+            );
+            if pending_withdrawal_account.key != &pending_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
             }
+
+            let rent = Rent::from_account_info(rent_sysvar)?;
+            create_pda_account(
+                authority,
+                pending_withdrawal_account,
+                system_program,
+                &rent,
+                PendingWithdrawal::LEN,
+                program_id,
+                &[
+                    PENDING_WITHDRAWAL_SEED,
+                    treasury_state_account.key.as_ref(),
+                    &withdrawal_id.to_le_bytes(),
+                    &[bump],
+                ],
+            )?;
+
+            let pending_withdrawal = PendingWithdrawal {
+                is_initialized: true,
+                treasury: *treasury_state_account.key,
+                withdrawal_id,
+                proposer: *authority.key,
+                destination_usdc_account: *destination_usdc_account.key,
+                amount,
+                created_at: clock.unix_timestamp,
+                unlock_at: clock
+                    .unix_timestamp
+                    .checked_add(treasury_state.withdrawal_timelock_delay)
+                    .ok_or(EceTokenError::NumericalOverflow)?,
+                status: WithdrawalStatus::Pending,
+                bump,
+                signer_epoch: treasury_state.signer_epoch,
+            };
+
+            treasury_state.withdrawal_count = treasury_state
+                .withdrawal_count
+                .checked_add(1)
+                .ok_or(EceTokenError::NumericalOverflow)?;
+
+            pending_withdrawal.serialize(&mut &mut pending_withdrawal_account.data.borrow_mut()[..])?;
+            treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+            msg!("Withdrawal {} of {} USDC pending until {}",
+                 withdrawal_id, amount, pending_withdrawal.unlock_at);
+            return Ok(());
+        }
+
+        if check_circuit_breaker(treasury_state_account, &mut treasury_state, amount, 0, clock.unix_timestamp)? {
+            return Ok(());
         }
 
         // Transfer USDC from treasury
-        let transfer_ix = token_instruction::transfer(
-            token_program.key,
-            treasury_usdc_account.key,
-            destination_usdc_account.key,
-            program_id,
-            &[],
+        transfer_from_reserve(
+            token_program,
+            treasury_usdc_account,
+            destination_usdc_account,
+            treasury_authority,
+            authority_bump,
             amount,
         )?;
 
-        let seeds = &[b"ece-treasury-authority"];
-        let (_, bump_seed) = Pubkey::find_program_address(seeds, program_id);
-        let signer_seeds = &[&seeds[..], &[bump_seed]];
-
-        invoke_signed(
-            &transfer_ix,
-            &[
-                treasury_usdc_account.clone(),
-                destination_usdc_account.clone(),
-                treasury_state_account.clone(),
-                token_program.clone(),
-            ],
-            &[signer_seeds],
-        )?;
-
         // Update treasury reserves
         treasury_state.usdc_reserves -= amount;
+        treasury_state.record_outflow(amount);
+        treasury_state.window_withdrawals = treasury_state.window_withdrawals.saturating_add(amount);
         treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
 
         msg!("Withdrew {} USDC from treasury reserves", amount);
@@ -565,11 +893,9 @@ impl Processor {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let mut treasury_state = TreasuryState::try_from_slice(&treasury_state_account.data.borrow())?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
 
-        if emergency_authority.key != &treasury_state.emergency_authority {
-            return Err(EceTokenError::UnauthorizedSigner.into());
-        }
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, emergency_authority, Role::Pauser)?;
 
         treasury_state.is_paused = true;
         treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
@@ -591,24 +917,25 @@ impl Processor {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let mut treasury_state = TreasuryState::try_from_slice(&treasury_state_account.data.borrow())?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
 
-        if emergency_authority.key != &treasury_state.emergency_authority {
-            return Err(EceTokenError::UnauthorizedSigner.into());
-        }
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, emergency_authority, Role::Pauser)?;
 
         if !treasury_state.is_paused {
             return Err(EceTokenError::NotPaused.into());
         }
 
+        // Clearing the pause also clears a breaker trip. The outflow window keeps its
+        // history, so outflows right after the unpause still count against the same limit.
         treasury_state.is_paused = false;
+        treasury_state.breaker_trip_reason = CircuitBreakerReason::None;
         treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
 
         msg!("Emergency pause deactivated by authority");
         Ok(())
     }
 
-    /// Update treasury signers and threshold
+    /// Update treasury signers and threshold (requires threshold approval from the current set)
     pub fn process_update_treasury(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
@@ -623,24 +950,6381 @@ impl Processor {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let mut treasury_state = TreasuryState::try_from_slice(&treasury_state_account.data.borrow())?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
 
-        // Validate that current authority can make this change
-        if !treasury_state.signers.contains(authority.key) {
-            return Err(EceTokenError::UnauthorizedSigner.into());
-        }
+        // Replacing the signer set needs the current quorum, not a single member
+        verify_multisig_approval(&treasury_state, accounts)?;
 
-        if new_signers.len() < new_threshold as usize || new_threshold == 0 {
+        if !treasury_state.is_valid_signer_set(&new_signers, new_threshold) {
             return Err(EceTokenError::InvalidSignatureThreshold.into());
         }
 
         treasury_state.signers = new_signers;
         treasury_state.threshold = new_threshold;
+        treasury_state.signer_epoch = treasury_state
+            .signer_epoch
+            .checked_add(1)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Treasury updated with {} signers, threshold {}, epoch {}", 
+             treasury_state.signers.len(), new_threshold, treasury_state.signer_epoch);
+        Ok(())
+    }
+
+    /// Set the lowest threshold signer-set updates may use (requires threshold approval)
+    pub fn process_set_min_signer_threshold(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        min_signer_threshold: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
 
+        verify_multisig_approval(&treasury_state, accounts)?;
+
+        if min_signer_threshold == 0 || min_signer_threshold > treasury_state.threshold {
+            return Err(EceTokenError::InvalidSignatureThreshold.into());
+        }
+
+        treasury_state.min_signer_threshold = min_signer_threshold;
         treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
 
-        msg!("Treasury updated with {} signers, threshold {}", 
-             treasury_state.signers.len(), new_threshold);
+        msg!("Minimum signer threshold set to {}", min_signer_threshold);
+        Ok(())
+    }
+
+    /// Initialize the ECE staking pool and its vaults
+    pub fn process_initialize_staking_pool(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let staking_pool_account = next_account_info(account_info_iter)?;
+        let stake_vault = next_account_info(account_info_iter)?;
+        let reward_vault = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if ece_mint.key != &treasury_state.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        check_ece_token_program(token_program, &treasury_state)?;
+
+        if !staking_pool_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let (pool_address, pool_bump) = Pubkey::find_program_address(
+            &[STAKING_POOL_SEED, treasury_state_account.key.as_ref()],
+            program_id,
+        );
+        let (stake_vault_address, stake_vault_bump) = Pubkey::find_program_address(
+            &[STAKE_VAULT_SEED, pool_address.as_ref()],
+            program_id,
+        );
+        let (reward_vault_address, reward_vault_bump) = Pubkey::find_program_address(
+            &[REWARD_VAULT_SEED, pool_address.as_ref()],
+            program_id,
+        );
+
+        if staking_pool_account.key != &pool_address
+            || stake_vault.key != &stake_vault_address
+            || reward_vault.key != &reward_vault_address
+        {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+
+        create_pda_account(
+            authority,
+            staking_pool_account,
+            system_program,
+            &rent,
+            StakingPool::LEN,
+            program_id,
+            &[STAKING_POOL_SEED, treasury_state_account.key.as_ref(), &[pool_bump]],
+        )?;
+
+        create_pda_token_account(
+            authority,
+            stake_vault,
+            ece_mint,
+            &pool_address,
+            token_program,
+            system_program,
+            &rent,
+            &[STAKE_VAULT_SEED, pool_address.as_ref(), &[stake_vault_bump]],
+        )?;
+
+        create_pda_token_account(
+            authority,
+            reward_vault,
+            ece_mint,
+            &pool_address,
+            token_program,
+            system_program,
+            &rent,
+            &[REWARD_VAULT_SEED, pool_address.as_ref(), &[reward_vault_bump]],
+        )?;
+
+        let staking_pool = StakingPool {
+            is_initialized: true,
+            treasury: *treasury_state_account.key,
+            ece_mint: *ece_mint.key,
+            stake_vault: stake_vault_address,
+            reward_vault: reward_vault_address,
+            total_staked: 0,
+            total_weighted_stake: 0,
+            reward_rate: 0,
+            reward_period_end: 0,
+            reward_per_token_stored: 0,
+            last_update_timestamp: 0,
+            accrued_rewards: 0,
+            bump: pool_bump,
+        };
+
+        staking_pool.serialize(&mut &mut staking_pool_account.data.borrow_mut()[..])?;
+
+        msg!("Staking pool initialized for treasury {}", treasury_state_account.key);
+        Ok(())
+    }
+
+    /// Fund the staking reward vault and start a new reward period
+    pub fn process_fund_staking_rewards(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        duration: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let source_ece_account = next_account_info(account_info_iter)?;
+        let staking_pool_account = next_account_info(account_info_iter)?;
+        let reward_vault = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if duration <= 0 {
+            return Err(EceTokenError::InvalidRewardDuration.into());
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        let mut staking_pool = load_staking_pool(program_id, staking_pool_account, treasury_state_account.key)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if reward_vault.key != &staking_pool.reward_vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+
+        // Only what reaches the vault after any transfer fee can be emitted
+        let received = transfer_checked(
+            token_program,
+            source_ece_account,
+            ece_mint,
+            reward_vault,
+            authority,
+            accounts,
+            amount,
+            &[],
+        )?;
+
+        staking_pool
+            .notify_reward_amount(received, duration, clock.unix_timestamp)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        // The emission schedule must be fully covered by the vault balance left after
+        // the rewards stakers have already earned but not yet claimed
+        let vault_balance = unpack_token_account(reward_vault)?.amount;
+        let scheduled = (staking_pool.reward_rate as u128)
+            .checked_mul(duration as u128)
+            .and_then(|scheduled| scheduled.checked_add(staking_pool.accrued_rewards as u128))
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        if scheduled > vault_balance as u128 {
+            return Err(EceTokenError::InsufficientFunds.into());
+        }
+
+        staking_pool.serialize(&mut &mut staking_pool_account.data.borrow_mut()[..])?;
+
+        msg!("Funded {} ECE staking rewards over {} seconds, rate {}/s",
+             received, duration, staking_pool.reward_rate);
         Ok(())
     }
+
+    /// Lock ECE into the staking vault
+    pub fn process_stake(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        lockup_tier: LockupTier,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let staker = next_account_info(account_info_iter)?;
+        let source_ece_account = next_account_info(account_info_iter)?;
+        let staking_pool_account = next_account_info(account_info_iter)?;
+        let stake_account_info = next_account_info(account_info_iter)?;
+        let stake_vault = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !staker.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if amount == 0 {
+            return Err(EceTokenError::InsufficientFunds.into());
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        let mut staking_pool = load_staking_pool(program_id, staking_pool_account, treasury_state_account.key)?;
+
+        if stake_vault.key != &staking_pool.stake_vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        if treasury_state.is_operation_paused(PAUSE_STAKING) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+
+        staking_pool
+            .update_reward_per_token(clock.unix_timestamp)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        let (stake_address, stake_bump) = Pubkey::find_program_address(
+            &[STAKE_ACCOUNT_SEED, staking_pool_account.key.as_ref(), staker.key.as_ref()],
+            program_id,
+        );
+        if stake_account_info.key != &stake_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let mut stake_account = if stake_account_info.data_is_empty() {
+            let rent = Rent::from_account_info(rent_sysvar)?;
+            create_pda_account(
+                staker,
+                stake_account_info,
+                system_program,
+                &rent,
+                StakeAccount::LEN,
+                program_id,
+                &[
+                    STAKE_ACCOUNT_SEED,
+                    staking_pool_account.key.as_ref(),
+                    staker.key.as_ref(),
+                    &[stake_bump],
+                ],
+            )?;
+
+            StakeAccount {
+                is_initialized: true,
+                pool: *staking_pool_account.key,
+                owner: *staker.key,
+                amount: 0,
+                weighted_amount: 0,
+                lockup_tier,
+                lockup_end: 0,
+                last_stake_timestamp: 0,
+                reward_per_token_paid: staking_pool.reward_per_token_stored,
+                pending_rewards: 0,
+                bump: stake_bump,
+            }
+        } else {
+            StakeAccount::try_from_slice(&stake_account_info.data.borrow())?
+        };
+
+        stake_account
+            .update_rewards(staking_pool.reward_per_token_stored)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        // A position can move to a longer tier but never shorten its lockup
+        let tier = if stake_account.amount == 0 {
+            lockup_tier
+        } else {
+            stake_account.lockup_tier.max(lockup_tier)
+        };
+        let lockup_end = clock
+            .unix_timestamp
+            .checked_add(tier.duration())
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        // Credit the position with what the vault received, net of any transfer fee
+        let received = transfer_checked(
+            token_program,
+            source_ece_account,
+            ece_mint,
+            stake_vault,
+            staker,
+            accounts,
+            amount,
+            &[],
+        )?;
+
+        let new_amount = stake_account
+            .amount
+            .checked_add(received)
+            .ok_or(EceTokenError::AmountOverflow)?;
+        let new_weighted = tier
+            .weighted_amount(new_amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        staking_pool.total_staked = staking_pool
+            .total_staked
+            .checked_add(received)
+            .ok_or(EceTokenError::AmountOverflow)?;
+        staking_pool.total_weighted_stake = staking_pool
+            .total_weighted_stake
+            .checked_sub(stake_account.weighted_amount)
+            .and_then(|total| total.checked_add(new_weighted))
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        stake_account.amount = new_amount;
+        stake_account.weighted_amount = new_weighted;
+        stake_account.lockup_tier = tier;
+        stake_account.lockup_end = stake_account.lockup_end.max(lockup_end);
+        stake_account.last_stake_timestamp = clock.unix_timestamp;
+
+        staking_pool.serialize(&mut &mut staking_pool_account.data.borrow_mut()[..])?;
+        stake_account.serialize(&mut &mut stake_account_info.data.borrow_mut()[..])?;
+
+        msg!("Staked {} ECE, locked until {}", received, stake_account.lockup_end);
+        Ok(())
+    }
+
+    /// Withdraw staked ECE once the lockup has expired
+    pub fn process_unstake(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let staker = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let staking_pool_account = next_account_info(account_info_iter)?;
+        let stake_account_info = next_account_info(account_info_iter)?;
+        let stake_vault = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !staker.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        let mut staking_pool = load_staking_pool(program_id, staking_pool_account, treasury_state_account.key)?;
+        let mut stake_account =
+            load_stake_account(program_id, stake_account_info, staking_pool_account.key, staker.key)?;
+
+        if stake_vault.key != &staking_pool.stake_vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        if treasury_state.is_operation_paused(PAUSE_STAKING) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+
+        if stake_account.is_locked(clock.unix_timestamp) {
+            return Err(EceTokenError::StakeLocked.into());
+        }
+
+        if amount == 0 || amount > stake_account.amount {
+            return Err(EceTokenError::InsufficientStakedBalance.into());
+        }
+
+        staking_pool
+            .update_reward_per_token(clock.unix_timestamp)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        stake_account
+            .update_rewards(staking_pool.reward_per_token_stored)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        transfer_checked(
+            token_program,
+            stake_vault,
+            ece_mint,
+            destination_ece_account,
+            staking_pool_account,
+            accounts,
+            amount,
+            &[&[STAKING_POOL_SEED, staking_pool.treasury.as_ref(), &[staking_pool.bump]]],
+        )?;
+
+        let new_amount = stake_account.amount - amount;
+        let new_weighted = stake_account
+            .lockup_tier
+            .weighted_amount(new_amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        staking_pool.total_staked = staking_pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        staking_pool.total_weighted_stake = staking_pool
+            .total_weighted_stake
+            .checked_sub(stake_account.weighted_amount)
+            .and_then(|total| total.checked_add(new_weighted))
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        stake_account.amount = new_amount;
+        stake_account.weighted_amount = new_weighted;
+
+        staking_pool.serialize(&mut &mut staking_pool_account.data.borrow_mut()[..])?;
+        stake_account.serialize(&mut &mut stake_account_info.data.borrow_mut()[..])?;
+
+        msg!("Unstaked {} ECE, {} ECE remaining", amount, new_amount);
+        Ok(())
+    }
+
+    /// Claim accrued staking rewards
+    pub fn process_claim_staking_rewards(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let staker = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let staking_pool_account = next_account_info(account_info_iter)?;
+        let stake_account_info = next_account_info(account_info_iter)?;
+        let reward_vault = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !staker.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        let mut staking_pool = load_staking_pool(program_id, staking_pool_account, treasury_state_account.key)?;
+        let mut stake_account =
+            load_stake_account(program_id, stake_account_info, staking_pool_account.key, staker.key)?;
+
+        if reward_vault.key != &staking_pool.reward_vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        if treasury_state.is_operation_paused(PAUSE_REWARDS) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+
+        staking_pool
+            .update_reward_per_token(clock.unix_timestamp)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        stake_account
+            .update_rewards(staking_pool.reward_per_token_stored)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        let rewards = stake_account.pending_rewards;
+
+        if rewards > 0 {
+            transfer_checked(
+                token_program,
+                reward_vault,
+                ece_mint,
+                destination_ece_account,
+                staking_pool_account,
+                accounts,
+                rewards,
+                &[&[STAKING_POOL_SEED, staking_pool.treasury.as_ref(), &[staking_pool.bump]]],
+            )?;
+
+            stake_account.pending_rewards = 0;
+            staking_pool.accrued_rewards = staking_pool.accrued_rewards.saturating_sub(rewards);
+        }
+
+        staking_pool.serialize(&mut &mut staking_pool_account.data.borrow_mut()[..])?;
+        stake_account.serialize(&mut &mut stake_account_info.data.borrow_mut()[..])?;
+
+        msg!("Claimed {} ECE staking rewards", rewards);
+        Ok(())
+    }
+
+    /// Initialize staker governance over treasury parameters
+    pub fn process_initialize_governance(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        quorum_bps: u16,
+        voting_period: i64,
+        proposal_threshold: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let governance_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let staking_pool_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if quorum_bps == 0 || quorum_bps > 10000 || voting_period <= 0 {
+            return Err(EceTokenError::InvalidGovernanceParameter.into());
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        load_staking_pool(program_id, staking_pool_account, treasury_state_account.key)?;
+
+        if !governance_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let (governance_address, bump) = Pubkey::find_program_address(
+            &[GOVERNANCE_SEED, treasury_state_account.key.as_ref()],
+            program_id,
+        );
+        if governance_account.key != &governance_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+        create_pda_account(
+            authority,
+            governance_account,
+            system_program,
+            &rent,
+            GovernanceConfig::LEN,
+            program_id,
+            &[GOVERNANCE_SEED, treasury_state_account.key.as_ref(), &[bump]],
+        )?;
+
+        let governance = GovernanceConfig {
+            is_initialized: true,
+            treasury: *treasury_state_account.key,
+            staking_pool: *staking_pool_account.key,
+            quorum_bps,
+            voting_period,
+            proposal_threshold,
+            proposal_count: 0,
+            bump,
+        };
+
+        governance.serialize(&mut &mut governance_account.data.borrow_mut()[..])?;
+
+        msg!("Governance initialized: quorum {} bps, voting period {}s",
+             quorum_bps, voting_period);
+        Ok(())
+    }
+
+    /// Create a proposal to change a whitelisted treasury parameter
+    pub fn process_create_proposal(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        change: TreasuryParameterChange,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let proposer = next_account_info(account_info_iter)?;
+        let governance_account = next_account_info(account_info_iter)?;
+        let proposal_account = next_account_info(account_info_iter)?;
+        let stake_account_info = next_account_info(account_info_iter)?;
+        let staking_pool_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !proposer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !change.is_valid() {
+            return Err(EceTokenError::InvalidGovernanceParameter.into());
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut governance = load_governance(program_id, governance_account)?;
+
+        if staking_pool_account.key != &governance.staking_pool {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let staking_pool = load_staking_pool(program_id, staking_pool_account, &governance.treasury)?;
+        let stake_account =
+            load_stake_account(program_id, stake_account_info, staking_pool_account.key, proposer.key)?;
+
+        if stake_account.amount < governance.proposal_threshold {
+            return Err(EceTokenError::InsufficientVotingPower.into());
+        }
+
+        let proposal_id = governance.proposal_count;
+        let (proposal_address, bump) = Pubkey::find_program_address(
+            &[PROPOSAL_SEED, governance_account.key.as_ref(), &proposal_id.to_le_bytes()],
+            program_id,
+        );
+        if proposal_account.key != &proposal_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+        create_pda_account(
+            proposer,
+            proposal_account,
+            system_program,
+            &rent,
+            Proposal::LEN,
+            program_id,
+            &[
+                PROPOSAL_SEED,
+                governance_account.key.as_ref(),
+                &proposal_id.to_le_bytes(),
+                &[bump],
+            ],
+        )?;
+
+        let voting_ends_at = clock
+            .unix_timestamp
+            .checked_add(governance.voting_period)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        let proposal = Proposal {
+            is_initialized: true,
+            proposal_id,
+            proposer: *proposer.key,
+            change,
+            created_at: clock.unix_timestamp,
+            voting_ends_at,
+            snapshot_total_staked: staking_pool.total_staked,
+            votes_for: 0,
+            votes_against: 0,
+            executed: false,
+            bump,
+        };
+
+        governance.proposal_count = governance
+            .proposal_count
+            .checked_add(1)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        proposal.serialize(&mut &mut proposal_account.data.borrow_mut()[..])?;
+        governance.serialize(&mut &mut governance_account.data.borrow_mut()[..])?;
+
+        msg!("Proposal {} created, voting ends at {}", proposal_id, voting_ends_at);
+        Ok(())
+    }
+
+    /// Cast a stake-weighted vote on a proposal
+    pub fn process_cast_vote(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        support: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let voter = next_account_info(account_info_iter)?;
+        let governance_account = next_account_info(account_info_iter)?;
+        let proposal_account = next_account_info(account_info_iter)?;
+        let vote_record_account = next_account_info(account_info_iter)?;
+        let stake_account_info = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !voter.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let governance = load_governance(program_id, governance_account)?;
+        let mut proposal = load_proposal(program_id, proposal_account, governance_account.key)?;
+        let stake_account =
+            load_stake_account(program_id, stake_account_info, &governance.staking_pool, voter.key)?;
+
+        if !proposal.is_voting_active(clock.unix_timestamp) {
+            return Err(EceTokenError::VotingPeriodEnded.into());
+        }
+
+        let weight = stake_account.voting_weight_at(proposal.created_at);
+        if weight == 0 {
+            return Err(EceTokenError::InsufficientVotingPower.into());
+        }
+
+        // The vote record PDA can only be created once per voter and proposal
+        if !vote_record_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let (vote_record_address, bump) = Pubkey::find_program_address(
+            &[VOTE_RECORD_SEED, proposal_account.key.as_ref(), voter.key.as_ref()],
+            program_id,
+        );
+        if vote_record_account.key != &vote_record_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+        create_pda_account(
+            voter,
+            vote_record_account,
+            system_program,
+            &rent,
+            VoteRecord::LEN,
+            program_id,
+            &[
+                VOTE_RECORD_SEED,
+                proposal_account.key.as_ref(),
+                voter.key.as_ref(),
+                &[bump],
+            ],
+        )?;
+
+        if support {
+            proposal.votes_for = proposal
+                .votes_for
+                .checked_add(weight)
+                .ok_or(EceTokenError::NumericalOverflow)?;
+        } else {
+            proposal.votes_against = proposal
+                .votes_against
+                .checked_add(weight)
+                .ok_or(EceTokenError::NumericalOverflow)?;
+        }
+
+        let vote_record = VoteRecord {
+            is_initialized: true,
+            proposal: *proposal_account.key,
+            voter: *voter.key,
+            support,
+            weight,
+        };
+
+        vote_record.serialize(&mut &mut vote_record_account.data.borrow_mut()[..])?;
+        proposal.serialize(&mut &mut proposal_account.data.borrow_mut()[..])?;
+
+        msg!("Vote cast on proposal {}: {} with weight {}",
+             proposal.proposal_id, if support { "for" } else { "against" }, weight);
+        Ok(())
+    }
+
+    /// Execute a passed proposal against the treasury state
+    pub fn process_execute_proposal(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let executor = next_account_info(account_info_iter)?;
+        let governance_account = next_account_info(account_info_iter)?;
+        let proposal_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+
+        if !executor.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let governance = load_governance(program_id, governance_account)?;
+        let mut proposal = load_proposal(program_id, proposal_account, governance_account.key)?;
+
+        if treasury_state_account.key != &governance.treasury {
+            return Err(EceTokenError::InvalidTreasuryAccount.into());
+        }
+
+        if proposal.executed {
+            return Err(EceTokenError::ProposalAlreadyExecuted.into());
+        }
+
+        if proposal.is_voting_active(clock.unix_timestamp) {
+            return Err(EceTokenError::VotingPeriodActive.into());
+        }
+
+        if !proposal.has_passed(governance.quorum_bps) {
+            return Err(EceTokenError::ProposalNotPassed.into());
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        proposal.change.apply(&mut treasury_state);
+        proposal.executed = true;
+
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+        proposal.serialize(&mut &mut proposal_account.data.borrow_mut()[..])?;
+
+        msg!("Executed proposal {}: {:?}", proposal.proposal_id, proposal.change);
+        Ok(())
+    }
+
+    /// Create or replace the weekly payout fee split table
+    pub fn process_set_fee_split(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        recipients: Vec<FeeRecipient>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let fee_split_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if !FeeSplitConfig::validate_recipients(&recipients) {
+            return Err(EceTokenError::InvalidFeeSplit.into());
+        }
+
+        let mut fee_split = if fee_split_account.data_is_empty() {
+            let (fee_split_address, bump) = Pubkey::find_program_address(
+                &[FEE_SPLIT_SEED, treasury_state_account.key.as_ref()],
+                program_id,
+            );
+            if fee_split_account.key != &fee_split_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
+            }
+
+            let rent = Rent::from_account_info(rent_sysvar)?;
+            create_pda_account(
+                authority,
+                fee_split_account,
+                system_program,
+                &rent,
+                FeeSplitConfig::LEN,
+                program_id,
+                &[FEE_SPLIT_SEED, treasury_state_account.key.as_ref(), &[bump]],
+            )?;
+
+            FeeSplitConfig {
+                is_initialized: true,
+                treasury: *treasury_state_account.key,
+                recipient_count: 0,
+                recipients: [FeeRecipient::default(); MAX_FEE_RECIPIENTS],
+                bump,
+            }
+        } else {
+            load_fee_split(program_id, fee_split_account, treasury_state_account.key)?
+        };
+
+        fee_split.set_recipients(&recipients);
+        fee_split.serialize(&mut &mut fee_split_account.data.borrow_mut()[..])?;
+
+        msg!("Fee split updated with {} recipients", recipients.len());
+        Ok(())
+    }
+
+    /// Set the company USDC account receiving weekly payouts while no fee split exists
+    pub fn process_set_company_usdc_account(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let company_usdc_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if unpack_token_account(company_usdc_account)?.mint != treasury_state.usdc_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        treasury_state.company_usdc_account = *company_usdc_account.key;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Company USDC account set to {}", company_usdc_account.key);
+        Ok(())
+    }
+
+    /// Create or replace the royalty registry for a card collection
+    pub fn process_set_royalty_registry(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        collection: Pubkey,
+        royalty_bps: u16,
+        creators: Vec<RoyaltyCreator>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let royalty_registry_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if royalty_bps > MAX_ROYALTY_BPS || !RoyaltyRegistry::validate_creators(&creators) {
+            return Err(EceTokenError::InvalidRoyaltyConfig.into());
+        }
+
+        let (registry_address, bump) = Pubkey::find_program_address(
+            &[ROYALTY_SEED, collection.as_ref()],
+            program_id,
+        );
+        if royalty_registry_account.key != &registry_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let mut royalty_registry = if royalty_registry_account.data_is_empty() {
+            let rent = Rent::from_account_info(rent_sysvar)?;
+            create_pda_account(
+                authority,
+                royalty_registry_account,
+                system_program,
+                &rent,
+                RoyaltyRegistry::LEN,
+                program_id,
+                &[ROYALTY_SEED, collection.as_ref(), &[bump]],
+            )?;
+
+            RoyaltyRegistry {
+                is_initialized: true,
+                collection,
+                royalty_bps: 0,
+                creator_count: 0,
+                creators: [RoyaltyCreator::default(); MAX_ROYALTY_CREATORS],
+                bump,
+            }
+        } else {
+            load_royalty_registry(program_id, royalty_registry_account)?
+        };
+
+        royalty_registry.royalty_bps = royalty_bps;
+        royalty_registry.set_creators(&creators);
+        royalty_registry.serialize(&mut &mut royalty_registry_account.data.borrow_mut()[..])?;
+
+        msg!("Royalty registry for {} set to {} bps across {} creators",
+             collection, royalty_bps, creators.len());
+        Ok(())
+    }
+
+    /// Settle a card sale in ECE, routing royalties and the platform fee
+    pub fn process_settle_sale_with_royalties(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        price: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let buyer = next_account_info(account_info_iter)?;
+        let seller = next_account_info(account_info_iter)?;
+        let buyer_ece_account = next_account_info(account_info_iter)?;
+        let seller_ece_account = next_account_info(account_info_iter)?;
+        let platform_fee_account = next_account_info(account_info_iter)?;
+        let card_record_account = next_account_info(account_info_iter)?;
+        let seller_card_account = next_account_info(account_info_iter)?;
+        let buyer_card_account = next_account_info(account_info_iter)?;
+        let royalty_registry_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let card_token_program = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let card_mint = next_account_info(account_info_iter)?;
+
+        if !buyer.is_signer || !seller.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_ESCROW) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_token_program(card_token_program)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_fee_ece_account(platform_fee_account, &treasury_state)?;
+        check_token_account(seller_ece_account, seller.key, &treasury_state.ece_mint)?;
+
+        // Royalties are always those of the card's own collection
+        let card_record = load_card_record(program_id, card_record_account, treasury_state_account.key)?;
+        let (registry_address, _) = Pubkey::find_program_address(
+            &[ROYALTY_SEED, card_record.collection.as_ref()],
+            program_id,
+        );
+        if royalty_registry_account.key != &registry_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        if card_mint.key != &card_record.mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        if seller_card_account.owner != card_token_program.key || buyer_card_account.owner != card_token_program.key {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        check_token_account(seller_card_account, seller.key, &card_record.mint)?;
+        check_token_account(buyer_card_account, buyer.key, &card_record.mint)?;
+
+        let royalty_registry = load_royalty_registry(program_id, royalty_registry_account)?;
+        let settlement = royalty_registry
+            .settle(price, treasury_state.marketplace_fee_bps)
+            .ok_or(EceTokenError::InvalidRoyaltyConfig)?;
+
+        // Creator ECE accounts follow in registry order and must be owned by the creator
+        let mut payments: Vec<(&AccountInfo, u64)> = Vec::with_capacity(settlement.creator_amounts.len() + 2);
+        for (creator, amount) in royalty_registry
+            .active_creators()
+            .iter()
+            .zip(settlement.creator_amounts.iter())
+        {
+            let creator_ece_account = next_account_info(account_info_iter)?;
+            let token_account = unpack_token_account(creator_ece_account)?;
+            if token_account.owner != creator.wallet || token_account.mint != treasury_state.ece_mint {
+                return Err(EceTokenError::InvalidTokenAccount.into());
+            }
+            payments.push((creator_ece_account, *amount));
+        }
+        payments.push((platform_fee_account, settlement.platform_fee));
+        payments.push((seller_ece_account, settlement.seller_amount));
+
+        for (destination, amount) in payments {
+            if amount == 0 {
+                continue;
+            }
+
+            transfer_checked(
+                token_program,
+                buyer_ece_account,
+                ece_mint,
+                destination,
+                buyer,
+                accounts,
+                amount,
+                &[],
+            )?;
+        }
+
+        // The card moves in the same instruction as the payment
+        transfer_checked(
+            card_token_program,
+            seller_card_account,
+            card_mint,
+            buyer_card_account,
+            seller,
+            accounts,
+            1,
+            &[],
+        )?;
+
+        msg!("Settled sale of card {} for {} ECE: {} to seller, {} platform fee, {} royalties",
+             card_record.mint, price, settlement.seller_amount, settlement.platform_fee,
+             settlement.creator_amounts.iter().sum::<u64>());
+        Ok(())
+    }
+
+    /// Initialize the trading card NFT collection
+    pub fn process_initialize_card_collection(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        mint_fee: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let collection_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if !collection_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let (collection_address, bump) = Pubkey::find_program_address(
+            &[CARD_COLLECTION_SEED, treasury_state_account.key.as_ref()],
+            program_id,
+        );
+        if collection_account.key != &collection_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+        create_pda_account(
+            authority,
+            collection_account,
+            system_program,
+            &rent,
+            CardCollection::LEN,
+            program_id,
+            &[CARD_COLLECTION_SEED, treasury_state_account.key.as_ref(), &[bump]],
+        )?;
+
+        let collection = CardCollection {
+            is_initialized: true,
+            treasury: *treasury_state_account.key,
+            mint_fee,
+            cards_minted: 0,
+            bump,
+        };
+
+        collection.serialize(&mut &mut collection_account.data.borrow_mut()[..])?;
+
+        msg!("Card collection initialized with mint fee {} ECE", mint_fee);
+        Ok(())
+    }
+
+    /// Update the ECE fee charged per card mint
+    pub fn process_set_card_mint_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        mint_fee: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let collection_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        let mut collection = load_card_collection(program_id, collection_account, treasury_state_account.key)?;
+        collection.mint_fee = mint_fee;
+        collection.serialize(&mut &mut collection_account.data.borrow_mut()[..])?;
+
+        msg!("Card mint fee set to {} ECE", mint_fee);
+        Ok(())
+    }
+
+    /// Mint a trading card as a 0-decimal, supply-1 SPL token paid for in ECE
+    pub fn process_mint_card(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        card_id: [u8; 32],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payer = next_account_info(account_info_iter)?;
+        let collection_account = next_account_info(account_info_iter)?;
+        let card_mint = next_account_info(account_info_iter)?;
+        let card_record_account = next_account_info(account_info_iter)?;
+        let recipient_card_account = next_account_info(account_info_iter)?;
+        let payer_ece_account = next_account_info(account_info_iter)?;
+        let fee_ece_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let associated_token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let minter = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let rent = Rent::from_account_info(rent_sysvar)?;
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_CARDS) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        // Cards are only minted for ids the platform has issued
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, minter, Role::Minter)?;
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_fee_ece_account(fee_ece_account, &treasury_state)?;
+
+        let mut collection = load_card_collection(program_id, collection_account, treasury_state_account.key)?;
+
+        let (card_mint_address, card_mint_bump) = Pubkey::find_program_address(
+            &[CARD_MINT_SEED, collection_account.key.as_ref(), &card_id],
+            program_id,
+        );
+        let (card_record_address, card_record_bump) = Pubkey::find_program_address(
+            &[CARD_RECORD_SEED, card_mint_address.as_ref()],
+            program_id,
+        );
+        if card_mint.key != &card_mint_address || card_record_account.key != &card_record_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        if !card_mint.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        // Mint fee flows to the treasury fee account
+        if collection.mint_fee > 0 {
+            transfer_checked(
+                token_program,
+                payer_ece_account,
+                ece_mint,
+                fee_ece_account,
+                payer,
+                accounts,
+                collection.mint_fee,
+                &[],
+            )?;
+        }
+
+        // The collection PDA is mint and freeze authority of every card
+        create_pda_account(
+            payer,
+            card_mint,
+            system_program,
+            &rent,
+            Mint::LEN,
+            token_program.key,
+            &[CARD_MINT_SEED, collection_account.key.as_ref(), &card_id, &[card_mint_bump]],
+        )?;
+
+        invoke(
+            &token_instruction::initialize_mint2(
+                token_program.key,
+                card_mint.key,
+                collection_account.key,
+                Some(collection_account.key),
+                0,
+            )?,
+            &[card_mint.clone(), token_program.clone()],
+        )?;
+
+        invoke(
+            &associated_token_instruction::create_associated_token_account(
+                payer.key,
+                payer.key,
+                card_mint.key,
+                token_program.key,
+            ),
+            &[
+                payer.clone(),
+                recipient_card_account.clone(),
+                payer.clone(),
+                card_mint.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+
+        let collection_seeds: &[&[u8]] = &[
+            CARD_COLLECTION_SEED,
+            collection.treasury.as_ref(),
+            &[collection.bump],
+        ];
+
+        invoke_signed(
+            &token_instruction::mint_to(
+                token_program.key,
+                card_mint.key,
+                recipient_card_account.key,
+                collection_account.key,
+                &[],
+                1,
+            )?,
+            &[
+                card_mint.clone(),
+                recipient_card_account.clone(),
+                collection_account.clone(),
+                token_program.clone(),
+            ],
+            &[collection_seeds],
+        )?;
+
+        // Drop the mint authority so the supply is fixed at one
+        invoke_signed(
+            &token_instruction::set_authority(
+                token_program.key,
+                card_mint.key,
+                None,
+                token_instruction::AuthorityType::MintTokens,
+                collection_account.key,
+                &[],
+            )?,
+            &[card_mint.clone(), collection_account.clone(), token_program.clone()],
+            &[collection_seeds],
+        )?;
+
+        create_pda_account(
+            payer,
+            card_record_account,
+            system_program,
+            &rent,
+            CardRecord::LEN,
+            program_id,
+            &[CARD_RECORD_SEED, card_mint.key.as_ref(), &[card_record_bump]],
+        )?;
+
+        collection.cards_minted = collection
+            .cards_minted
+            .checked_add(1)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        let card_record = CardRecord {
+            is_initialized: true,
+            collection: *collection_account.key,
+            mint: *card_mint.key,
+            card_id,
+            minted_to: *payer.key,
+            minted_at: clock.unix_timestamp,
+            serial_number: collection.cards_minted,
+        };
+
+        card_record.serialize(&mut &mut card_record_account.data.borrow_mut()[..])?;
+        collection.serialize(&mut &mut collection_account.data.borrow_mut()[..])?;
+
+        msg!("Minted card #{} as {}, fee {} ECE", collection.cards_minted, card_mint.key, collection.mint_fee);
+        Ok(())
+    }
+
+    /// Create a recurring ECE subscription plan
+    pub fn process_create_subscription_plan(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        plan_id: u64,
+        price: u64,
+        period: i64,
+        grace_period: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let plan_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if price == 0 || period <= 0 || grace_period < 0 {
+            return Err(EceTokenError::InvalidSubscriptionPlan.into());
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if !plan_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let (plan_address, bump) = Pubkey::find_program_address(
+            &[SUBSCRIPTION_PLAN_SEED, &plan_id.to_le_bytes()],
+            program_id,
+        );
+        if plan_account.key != &plan_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+        create_pda_account(
+            authority,
+            plan_account,
+            system_program,
+            &rent,
+            SubscriptionPlan::LEN,
+            program_id,
+            &[SUBSCRIPTION_PLAN_SEED, &plan_id.to_le_bytes(), &[bump]],
+        )?;
+
+        let plan = SubscriptionPlan {
+            is_initialized: true,
+            plan_id,
+            price,
+            period,
+            grace_period,
+            is_active: true,
+            bump,
+        };
+
+        plan.serialize(&mut &mut plan_account.data.borrow_mut()[..])?;
+
+        msg!("Subscription plan {} created: {} ECE every {}s", plan_id, price, period);
+        Ok(())
+    }
+
+    /// Subscribe to a plan, paying the first period and delegating a capped allowance
+    pub fn process_subscribe(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        max_periods: u32,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let subscriber = next_account_info(account_info_iter)?;
+        let plan_account = next_account_info(account_info_iter)?;
+        let subscription_account = next_account_info(account_info_iter)?;
+        let subscriber_ece_account = next_account_info(account_info_iter)?;
+        let fee_ece_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !subscriber.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_SUBSCRIPTIONS) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_fee_ece_account(fee_ece_account, &treasury_state)?;
+        check_token_account(subscriber_ece_account, subscriber.key, &treasury_state.ece_mint)?;
+
+        let plan = load_subscription_plan(program_id, plan_account)?;
+        if !plan.is_active {
+            return Err(EceTokenError::InvalidSubscriptionPlan.into());
+        }
+
+        let (subscription_address, bump) = Pubkey::find_program_address(
+            &[SUBSCRIPTION_SEED, plan_account.key.as_ref(), subscriber.key.as_ref()],
+            program_id,
+        );
+        if subscription_account.key != &subscription_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        // Cancelled or expired subscriptions can be renewed in place
+        let previous = if subscription_account.data_is_empty() {
+            let rent = Rent::from_account_info(rent_sysvar)?;
+            create_pda_account(
+                subscriber,
+                subscription_account,
+                system_program,
+                &rent,
+                Subscription::LEN,
+                program_id,
+                &[
+                    SUBSCRIPTION_SEED,
+                    plan_account.key.as_ref(),
+                    subscriber.key.as_ref(),
+                    &[bump],
+                ],
+            )?;
+            None
+        } else {
+            let existing = load_subscription(program_id, subscription_account)?;
+            if matches!(existing.status, SubscriptionStatus::Active | SubscriptionStatus::PastDue) {
+                return Err(EceTokenError::AccountAlreadyInitialized.into());
+            }
+            Some(existing)
+        };
+
+        // Resubscribing inside a period that was already paid for keeps its schedule
+        let paid_through = previous
+            .as_ref()
+            .filter(|existing| existing.has_access(clock.unix_timestamp))
+            .map(|existing| existing.next_charge_at);
+
+        let (next_charge_at, charged) = match paid_through {
+            Some(next_charge_at) => (next_charge_at, 0),
+            None => {
+                // Pay the first period up front
+                let received = transfer_checked(
+                    token_program,
+                    subscriber_ece_account,
+                    ece_mint,
+                    fee_ece_account,
+                    subscriber,
+                    accounts,
+                    plan.price,
+                    &[],
+                )?;
+
+                let next_charge_at = clock
+                    .unix_timestamp
+                    .checked_add(plan.period)
+                    .ok_or(EceTokenError::NumericalOverflow)?;
+                (next_charge_at, received)
+            }
+        };
+        let (periods_paid, total_collected) = previous
+            .as_ref()
+            .map_or((0, 0), |existing| (existing.periods_paid, existing.total_collected));
+
+        // Delegate a capped allowance covering the remaining periods
+        let allowance = plan
+            .price
+            .checked_mul(max_periods as u64)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        let approve_ix = token_instruction::approve(
+            token_program.key,
+            subscriber_ece_account.key,
+            subscription_account.key,
+            subscriber.key,
+            &[],
+            allowance,
+        )?;
+
+        invoke(
+            &approve_ix,
+            &[
+                subscriber_ece_account.clone(),
+                subscription_account.clone(),
+                subscriber.clone(),
+                token_program.clone(),
+            ],
+        )?;
+
+        let subscription = Subscription {
+            is_initialized: true,
+            plan: *plan_account.key,
+            subscriber: *subscriber.key,
+            subscriber_ece_account: *subscriber_ece_account.key,
+            price: plan.price,
+            period: plan.period,
+            grace_period: plan.grace_period,
+            next_charge_at,
+            periods_paid: periods_paid
+                .checked_add(u64::from(charged > 0))
+                .ok_or(EceTokenError::NumericalOverflow)?,
+            total_collected: total_collected
+                .checked_add(charged)
+                .ok_or(EceTokenError::NumericalOverflow)?,
+            status: SubscriptionStatus::Active,
+            bump,
+        };
+
+        subscription.serialize(&mut &mut subscription_account.data.borrow_mut()[..])?;
+
+        msg!("Subscribed to plan {}, allowance {} ECE, next charge at {}",
+             plan.plan_id, allowance, subscription.next_charge_at);
+        Ok(())
+    }
+
+    /// Collect a due subscription period (permissionless crank)
+    pub fn process_collect_subscription(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let subscription_account = next_account_info(account_info_iter)?;
+        let subscriber_ece_account = next_account_info(account_info_iter)?;
+        let fee_ece_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_SUBSCRIPTIONS) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_fee_ece_account(fee_ece_account, &treasury_state)?;
+
+        let mut subscription = load_subscription(program_id, subscription_account)?;
+
+        if subscriber_ece_account.key != &subscription.subscriber_ece_account {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        if !matches!(subscription.status, SubscriptionStatus::Active | SubscriptionStatus::PastDue) {
+            return Err(EceTokenError::SubscriptionNotActive.into());
+        }
+
+        if !subscription.is_due(clock.unix_timestamp) {
+            return Err(EceTokenError::SubscriptionPeriodNotElapsed.into());
+        }
+
+        // A failed transfer would roll back the status change, so check funds first
+        let token_account = unpack_token_account(subscriber_ece_account)?;
+        let can_pay = token_account.delegate == COption::Some(*subscription_account.key)
+            && token_account.delegated_amount >= subscription.price
+            && token_account.amount >= subscription.price;
+
+        if !can_pay {
+            subscription.status = if subscription.is_within_grace(clock.unix_timestamp) {
+                SubscriptionStatus::PastDue
+            } else {
+                SubscriptionStatus::Expired
+            };
+            subscription.serialize(&mut &mut subscription_account.data.borrow_mut()[..])?;
+
+            msg!("Subscription payment missed, status {:?}", subscription.status);
+            return Ok(());
+        }
+
+        let received = transfer_checked(
+            token_program,
+            subscriber_ece_account,
+            ece_mint,
+            fee_ece_account,
+            subscription_account,
+            accounts,
+            subscription.price,
+            &[&[
+                SUBSCRIPTION_SEED,
+                subscription.plan.as_ref(),
+                subscription.subscriber.as_ref(),
+                &[subscription.bump],
+            ]],
+        )?;
+
+        subscription.next_charge_at = subscription
+            .next_charge_at
+            .checked_add(subscription.period)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        subscription.periods_paid = subscription
+            .periods_paid
+            .checked_add(1)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        subscription.total_collected = subscription
+            .total_collected
+            .checked_add(received)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        subscription.status = SubscriptionStatus::Active;
+
+        subscription.serialize(&mut &mut subscription_account.data.borrow_mut()[..])?;
+
+        msg!("Collected {} ECE subscription fee, next charge at {}",
+             subscription.price, subscription.next_charge_at);
+        Ok(())
+    }
+
+    /// Cancel a subscription and revoke its allowance; access runs to the end of the paid period
+    pub fn process_cancel_subscription(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let subscriber = next_account_info(account_info_iter)?;
+        let subscription_account = next_account_info(account_info_iter)?;
+        let subscriber_ece_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+
+        if !subscriber.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut subscription = load_subscription(program_id, subscription_account)?;
+
+        if subscriber.key != &subscription.subscriber {
+            return Err(EceTokenError::UnauthorizedSigner.into());
+        }
+
+        if subscriber_ece_account.key != &subscription.subscriber_ece_account {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        if !matches!(subscription.status, SubscriptionStatus::Active | SubscriptionStatus::PastDue) {
+            return Err(EceTokenError::SubscriptionNotActive.into());
+        }
+
+        // Only revoke if the allowance still belongs to this subscription
+        let token_account = unpack_token_account(subscriber_ece_account)?;
+        if token_account.delegate == COption::Some(*subscription_account.key) {
+            invoke(
+                &token_instruction::revoke(
+                    token_program.key,
+                    subscriber_ece_account.key,
+                    subscriber.key,
+                    &[],
+                )?,
+                &[
+                    subscriber_ece_account.clone(),
+                    subscriber.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        subscription.status = SubscriptionStatus::Cancelled;
+        subscription.serialize(&mut &mut subscription_account.data.borrow_mut()[..])?;
+
+        msg!("Subscription cancelled, access until {}", subscription.next_charge_at);
+        Ok(())
+    }
+
+    /// Initialize the FIFO redemption queue and its ECE escrow vault
+    pub fn process_initialize_redemption_queue(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let queue_account = next_account_info(account_info_iter)?;
+        let escrow_vault = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if ece_mint.key != &treasury_state.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        if !queue_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let (queue_address, queue_bump) = Pubkey::find_program_address(
+            &[REDEMPTION_QUEUE_SEED, treasury_state_account.key.as_ref()],
+            program_id,
+        );
+        let (escrow_address, escrow_bump) = Pubkey::find_program_address(
+            &[REDEMPTION_ESCROW_SEED, queue_address.as_ref()],
+            program_id,
+        );
+        if queue_account.key != &queue_address || escrow_vault.key != &escrow_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+
+        create_pda_account(
+            authority,
+            queue_account,
+            system_program,
+            &rent,
+            RedemptionQueue::LEN,
+            program_id,
+            &[REDEMPTION_QUEUE_SEED, treasury_state_account.key.as_ref(), &[queue_bump]],
+        )?;
+
+        create_pda_token_account(
+            authority,
+            escrow_vault,
+            ece_mint,
+            &queue_address,
+            token_program,
+            system_program,
+            &rent,
+            &[REDEMPTION_ESCROW_SEED, queue_address.as_ref(), &[escrow_bump]],
+        )?;
+
+        let queue = RedemptionQueue {
+            is_initialized: true,
+            treasury: *treasury_state_account.key,
+            escrow_vault: escrow_address,
+            head: 0,
+            tail: 0,
+            bump: queue_bump,
+        };
+
+        queue.serialize(&mut &mut queue_account.data.borrow_mut()[..])?;
+
+        msg!("Redemption queue initialized");
+        Ok(())
+    }
+
+    /// Lock ECE into a queued redemption ticket
+    pub fn process_queue_redemption(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let source_ece_account = next_account_info(account_info_iter)?;
+        let destination_usdc_account = next_account_info(account_info_iter)?;
+        let queue_account = next_account_info(account_info_iter)?;
+        let ticket_account = next_account_info(account_info_iter)?;
+        let escrow_vault = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if amount == 0 {
+            return Err(EceTokenError::InsufficientFunds.into());
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_BURN) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_token_account(destination_usdc_account, owner.key, &treasury_state.usdc_mint)?;
+
+        let usdc_amount = treasury_state.ece_to_usdc(amount).ok_or(EceTokenError::NumericalOverflow)?;
+        if usdc_amount > treasury_state.max_transaction_amount {
+            return Err(EceTokenError::ComplianceCheckFailed.into());
+        }
+
+        if !treasury_state.within_usdc_share(amount) {
+            msg!("Redemption exceeds the USDC share of reserves");
+            return Err(EceTokenError::UsdcReserveInsufficient.into());
+        }
+
+        let mut queue = load_redemption_queue(program_id, queue_account, treasury_state_account.key)?;
+
+        if escrow_vault.key != &queue.escrow_vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        let ticket_id = queue.tail;
+        let (ticket_address, ticket_bump) = Pubkey::find_program_address(
+            &[REDEMPTION_TICKET_SEED, queue_account.key.as_ref(), &ticket_id.to_le_bytes()],
+            program_id,
+        );
+        if ticket_account.key != &ticket_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        // The ticket is for what the escrow received, net of any transfer fee
+        let received = transfer_checked(
+            token_program,
+            source_ece_account,
+            ece_mint,
+            escrow_vault,
+            owner,
+            accounts,
+            amount,
+            &[],
+        )?;
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+        create_pda_account(
+            owner,
+            ticket_account,
+            system_program,
+            &rent,
+            RedemptionTicket::LEN,
+            program_id,
+            &[
+                REDEMPTION_TICKET_SEED,
+                queue_account.key.as_ref(),
+                &ticket_id.to_le_bytes(),
+                &[ticket_bump],
+            ],
+        )?;
+
+        let ticket = RedemptionTicket {
+            is_initialized: true,
+            queue: *queue_account.key,
+            ticket_id,
+            owner: *owner.key,
+            destination_usdc_account: *destination_usdc_account.key,
+            amount: received,
+            settled_amount: 0,
+            created_at: clock.unix_timestamp,
+            cancelled: false,
+            bump: ticket_bump,
+        };
+
+        queue.tail = queue.tail.checked_add(1).ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.queued_redemptions = treasury_state
+            .queued_redemptions
+            .checked_add(received)
+            .ok_or(EceTokenError::AmountOverflow)?;
+
+        ticket.serialize(&mut &mut ticket_account.data.borrow_mut()[..])?;
+        queue.serialize(&mut &mut queue_account.data.borrow_mut()[..])?;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Queued redemption ticket {} for {} ECE", ticket_id, received);
+        Ok(())
+    }
+
+    /// Settle the ticket at the head of the redemption queue from available reserves (permissionless crank)
+    pub fn process_settle_redemption(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let queue_account = next_account_info(account_info_iter)?;
+        let ticket_account = next_account_info(account_info_iter)?;
+        let escrow_vault = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let destination_usdc_account = next_account_info(account_info_iter)?;
+        let treasury_usdc_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let treasury_authority = next_account_info(account_info_iter)?;
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_BURN) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        let mut queue = load_redemption_queue(program_id, queue_account, treasury_state_account.key)?;
+        let mut ticket = load_redemption_ticket(program_id, ticket_account, queue_account.key)?;
+
+        if ticket.ticket_id != queue.head {
+            return Err(EceTokenError::RedemptionTicketOutOfOrder.into());
+        }
+
+        // Cancelled tickets hold nothing, so the crank just moves past them
+        if ticket.cancelled {
+            queue.head = queue.head.checked_add(1).ok_or(EceTokenError::NumericalOverflow)?;
+            queue.serialize(&mut &mut queue_account.data.borrow_mut()[..])?;
+
+            msg!("Skipped cancelled redemption ticket {}", ticket.ticket_id);
+            return Ok(());
+        }
+
+        if escrow_vault.key != &queue.escrow_vault
+            || destination_usdc_account.key != &ticket.destination_usdc_account
+        {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_usdc_reserve(treasury_usdc_account, token_program, &treasury_state)?;
+        let authority_bump = check_treasury_authority(program_id, treasury_authority)?;
+
+        let clock = Clock::get()?;
+        check_usdc_peg(accounts, &treasury_state, clock.unix_timestamp)?;
+
+        // Queued tickets have priority over all reserves, so settle as much as is on hand,
+        // paying USDC rounded down for the ECE burned
+        let reserves_in_ece = treasury_state
+            .usdc_to_ece(treasury_state.usdc_reserves)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        let settle_amount = ticket.remaining().min(reserves_in_ece);
+        let usdc_amount = treasury_state.ece_to_usdc(settle_amount).ok_or(EceTokenError::NumericalOverflow)?;
+        if usdc_amount == 0 {
+            return Err(EceTokenError::UsdcReserveInsufficient.into());
+        }
+
+        if check_circuit_breaker(
+            treasury_state_account,
+            &mut treasury_state,
+            usdc_amount,
+            settle_amount,
+            clock.unix_timestamp,
+        )? {
+            return Ok(());
+        }
+
+        let ece_token_program = find_ece_token_program(accounts, &treasury_state, token_program)?;
+
+        invoke_signed(
+            &token_instruction::burn(
+                ece_token_program.key,
+                escrow_vault.key,
+                ece_mint.key,
+                queue_account.key,
+                &[],
+                settle_amount,
+            )?,
+            &[
+                escrow_vault.clone(),
+                ece_mint.clone(),
+                queue_account.clone(),
+                ece_token_program.clone(),
+            ],
+            &[&[REDEMPTION_QUEUE_SEED, queue.treasury.as_ref(), &[queue.bump]]],
+        )?;
+
+        // The redeem fee is withheld from the released USDC, as for an instant burn
+        let fee = treasury_state.redeem_fee(usdc_amount).ok_or(EceTokenError::NumericalOverflow)?;
+        let net_amount = usdc_amount - fee;
+
+        transfer_from_reserve(
+            token_program,
+            treasury_usdc_account,
+            destination_usdc_account,
+            treasury_authority,
+            authority_bump,
+            net_amount,
+        )?;
+
+        if fee > 0 {
+            let fee_usdc_account = find_fee_account(accounts, &treasury_state)?;
+            transfer_from_reserve(
+                token_program,
+                treasury_usdc_account,
+                fee_usdc_account,
+                treasury_authority,
+                authority_bump,
+                fee,
+            )?;
+        }
+
+        ticket.settled_amount = ticket
+            .settled_amount
+            .checked_add(settle_amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        if ticket.remaining() == 0 {
+            queue.head = queue.head.checked_add(1).ok_or(EceTokenError::NumericalOverflow)?;
+        }
+
+        treasury_state.ece_circulation = treasury_state
+            .ece_circulation
+            .checked_sub(settle_amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.usdc_reserves -= usdc_amount;
+        treasury_state.total_fees_collected = treasury_state
+            .total_fees_collected
+            .checked_add(fee)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.record_outflow(usdc_amount);
+        treasury_state.queued_redemptions = treasury_state
+            .queued_redemptions
+            .saturating_sub(settle_amount);
+
+        ticket.serialize(&mut &mut ticket_account.data.borrow_mut()[..])?;
+        queue.serialize(&mut &mut queue_account.data.borrow_mut()[..])?;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Settled {} ECE of redemption ticket {} for {} USDC ({} USDC fee), {} remaining",
+             settle_amount, ticket.ticket_id, net_amount, fee, ticket.remaining());
+        Ok(())
+    }
+
+    /// Cancel a queued redemption, returning the unsettled ECE to the ticket owner
+    pub fn process_cancel_redemption(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let queue_account = next_account_info(account_info_iter)?;
+        let ticket_account = next_account_info(account_info_iter)?;
+        let escrow_vault = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        check_ece_token_program(token_program, &treasury_state)?;
+        check_ece_mint(ece_mint, &treasury_state)?;
+        check_token_account(destination_ece_account, owner.key, &treasury_state.ece_mint)?;
+
+        let mut queue = load_redemption_queue(program_id, queue_account, treasury_state_account.key)?;
+        let mut ticket = load_redemption_ticket(program_id, ticket_account, queue_account.key)?;
+
+        if owner.key != &ticket.owner {
+            return Err(EceTokenError::UnauthorizedSigner.into());
+        }
+
+        if escrow_vault.key != &queue.escrow_vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        let refund = ticket.remaining();
+        if refund == 0 {
+            return Err(EceTokenError::RedemptionTicketClosed.into());
+        }
+
+        transfer_checked(
+            token_program,
+            escrow_vault,
+            ece_mint,
+            destination_ece_account,
+            queue_account,
+            accounts,
+            refund,
+            &[&[REDEMPTION_QUEUE_SEED, queue.treasury.as_ref(), &[queue.bump]]],
+        )?;
+
+        ticket.cancelled = true;
+        if ticket.ticket_id == queue.head {
+            queue.head = queue.head.checked_add(1).ok_or(EceTokenError::NumericalOverflow)?;
+        }
+        treasury_state.queued_redemptions = treasury_state
+            .queued_redemptions
+            .saturating_sub(refund);
+
+        ticket.serialize(&mut &mut ticket_account.data.borrow_mut()[..])?;
+        queue.serialize(&mut &mut queue_account.data.borrow_mut()[..])?;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Cancelled redemption ticket {}, returned {} ECE", ticket.ticket_id, refund);
+        Ok(())
+    }
+
+    /// Execute a timelocked withdrawal once its delay has elapsed
+    pub fn process_execute_withdrawal(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let pending_withdrawal_account = next_account_info(account_info_iter)?;
+        let treasury_usdc_account = next_account_info(account_info_iter)?;
+        let destination_usdc_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let treasury_authority = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if treasury_state.is_operation_paused(PAUSE_WITHDRAW) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        let mut pending_withdrawal =
+            load_pending_withdrawal(program_id, pending_withdrawal_account, treasury_state_account.key)?;
+
+        if pending_withdrawal.status != WithdrawalStatus::Pending {
+            return Err(EceTokenError::WithdrawalNotPending.into());
+        }
+
+        check_usdc_reserve(treasury_usdc_account, token_program, &treasury_state)?;
+        let authority_bump = check_treasury_authority(program_id, treasury_authority)?;
+
+        if pending_withdrawal.signer_epoch != treasury_state.signer_epoch {
+            return Err(EceTokenError::StaleSignerEpoch.into());
+        }
+
+        // The quorum approves again at execution, after the delay gave it time to object
+        verify_multisig_approval(&treasury_state, accounts)?;
+
+        if !pending_withdrawal.is_unlocked(clock.unix_timestamp) {
+            return Err(EceTokenError::WithdrawalTimelockActive.into());
+        }
+
+        if destination_usdc_account.key != &pending_withdrawal.destination_usdc_account {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        let amount = pending_withdrawal.amount;
+
+        // Reserves may have moved during the delay, so re-check at execution
+        if !treasury_state.has_available_usdc(amount) {
+            return Err(EceTokenError::UsdcReserveInsufficient.into());
+        }
+
+        if treasury_state.would_breach_min_reserve_ratio(amount) {
+            return Err(EceTokenError::ReserveRatioBelowMinimum.into());
+        }
+
+        if check_circuit_breaker(treasury_state_account, &mut treasury_state, amount, 0, clock.unix_timestamp)? {
+            return Ok(());
+        }
+
+        transfer_from_reserve(
+            token_program,
+            treasury_usdc_account,
+            destination_usdc_account,
+            treasury_authority,
+            authority_bump,
+            amount,
+        )?;
+
+        treasury_state.usdc_reserves -= amount;
+        treasury_state.record_outflow(amount);
+        pending_withdrawal.status = WithdrawalStatus::Executed;
+
+        pending_withdrawal.serialize(&mut &mut pending_withdrawal_account.data.borrow_mut()[..])?;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Executed withdrawal {} of {} USDC", pending_withdrawal.withdrawal_id, amount);
+        Ok(())
+    }
+
+    /// Cancel a timelocked withdrawal (emergency authority or any treasury signer)
+    pub fn process_cancel_withdrawal(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let pending_withdrawal_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Pauser)
+            .or_else(|_| {
+                require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)
+            })?;
+
+        let mut pending_withdrawal =
+            load_pending_withdrawal(program_id, pending_withdrawal_account, treasury_state_account.key)?;
+
+        if pending_withdrawal.status != WithdrawalStatus::Pending {
+            return Err(EceTokenError::WithdrawalNotPending.into());
+        }
+
+        pending_withdrawal.status = WithdrawalStatus::Cancelled;
+        pending_withdrawal.serialize(&mut &mut pending_withdrawal_account.data.borrow_mut()[..])?;
+
+        msg!("Withdrawal {} cancelled by {}", pending_withdrawal.withdrawal_id, authority.key);
+        Ok(())
+    }
+
+    /// Configure the withdrawal timelock threshold and delay
+    pub fn process_set_withdrawal_timelock(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        threshold: u64,
+        delay: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if delay < 0 {
+            return Err(EceTokenError::InvalidInstruction.into());
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        verify_multisig_approval(&treasury_state, accounts)?;
+
+        // Tightening applies at once; loosening is staged behind the current delay
+        // and applied by submitting the same change again once it has elapsed
+        let staged = treasury_state.pending_timelock_at != 0
+            && treasury_state.pending_timelock_threshold == threshold
+            && treasury_state.pending_timelock_delay == delay;
+
+        if !treasury_state.is_timelock_tightening(threshold, delay) {
+            if !staged {
+                treasury_state.pending_timelock_threshold = threshold;
+                treasury_state.pending_timelock_delay = delay;
+                treasury_state.pending_timelock_at = clock
+                    .unix_timestamp
+                    .checked_add(treasury_state.withdrawal_timelock_delay)
+                    .ok_or(EceTokenError::NumericalOverflow)?
+                    .max(1);
+                treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+                msg!("Withdrawal timelock change to {} USDC / {}s staged until {}",
+                     threshold, delay, treasury_state.pending_timelock_at);
+                return Ok(());
+            }
+
+            if clock.unix_timestamp < treasury_state.pending_timelock_at {
+                return Err(EceTokenError::WithdrawalTimelockActive.into());
+            }
+        }
+
+        treasury_state.withdrawal_timelock_threshold = threshold;
+        treasury_state.withdrawal_timelock_delay = delay;
+        treasury_state.pending_timelock_threshold = 0;
+        treasury_state.pending_timelock_delay = 0;
+        treasury_state.pending_timelock_at = 0;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Withdrawals above {} USDC now timelocked for {}s", threshold, delay);
+        Ok(())
+    }
+
+    /// Configure the circuit breaker outflow window, outflow limit and reserve floor
+    pub fn process_set_circuit_breaker(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        outflow_window: i64,
+        max_window_outflow_bps: u16,
+        reserve_floor_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if outflow_window <= 0 || max_window_outflow_bps > 10000 || reserve_floor_bps > 10000 {
+            return Err(EceTokenError::InvalidCircuitBreakerConfig.into());
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        treasury_state.outflow_window = outflow_window;
+        treasury_state.max_window_outflow_bps = max_window_outflow_bps;
+        treasury_state.reserve_floor_bps = reserve_floor_bps;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Circuit breaker set: {} bps outflow per {}s window, {} bps reserve floor",
+             max_window_outflow_bps, outflow_window, reserve_floor_bps);
+        Ok(())
+    }
+
+    /// Pause the treasury when it is already past a circuit breaker limit (permissionless crank)
+    pub fn process_trip_circuit_breaker(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_paused {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        // Only the treasury's current state counts, so the crank can't be used to pause
+        // it with an outflow nobody is allowed to make
+        let reason = treasury_state
+            .circuit_breaker_check(0, 0, clock.unix_timestamp)
+            .ok_or(EceTokenError::CircuitBreakerWithinLimits)?;
+
+        treasury_state.trip_circuit_breaker(reason, clock.unix_timestamp);
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("CircuitBreakerTripped: reason={:?} window_outflow={} reserves={} reserve_ratio={}",
+             reason,
+             treasury_state.sliding_window_outflow(clock.unix_timestamp),
+             treasury_state.usdc_reserves,
+             treasury_state.reserve_ratio());
+        Ok(())
+    }
+
+    /// Pause or resume individual operation types
+    pub fn process_set_pause_flags(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pause_flags: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let emergency_authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !emergency_authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, emergency_authority, Role::Pauser)?;
+
+        if pause_flags & !PAUSE_ALL != 0 {
+            return Err(EceTokenError::InvalidPauseFlags.into());
+        }
+
+        treasury_state.pause_flags = pause_flags;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Pause flags set to {:#012b} by authority", pause_flags);
+        Ok(())
+    }
+
+    /// Grant a role in the role registry, creating the registry on first use (multisig approval)
+    pub fn process_grant_role(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        holder: Pubkey,
+        role: Role,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payer = next_account_info(account_info_iter)?;
+        let role_registry_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        verify_multisig_approval(&treasury_state, accounts)?;
+
+        let mut role_registry = if role_registry_account.data_is_empty() {
+            let (role_registry_address, bump) = Pubkey::find_program_address(
+                &[ROLE_REGISTRY_SEED, treasury_state_account.key.as_ref()],
+                program_id,
+            );
+            if role_registry_account.key != &role_registry_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
+            }
+
+            let rent = Rent::from_account_info(rent_sysvar)?;
+            create_pda_account(
+                payer,
+                role_registry_account,
+                system_program,
+                &rent,
+                RoleRegistry::LEN,
+                program_id,
+                &[ROLE_REGISTRY_SEED, treasury_state_account.key.as_ref(), &[bump]],
+            )?;
+
+            RoleRegistry {
+                is_initialized: true,
+                treasury: *treasury_state_account.key,
+                assignment_count: 0,
+                assignments: [RoleAssignment::default(); MAX_ROLE_HOLDERS],
+                bump,
+            }
+        } else {
+            load_role_registry(program_id, role_registry_account, treasury_state_account.key)?
+        };
+
+        if !role_registry.grant(&holder, role) {
+            return Err(EceTokenError::RoleRegistryFull.into());
+        }
+
+        role_registry.serialize(&mut &mut role_registry_account.data.borrow_mut()[..])?;
+
+        msg!("Granted {:?} to {}", role, holder);
+        Ok(())
+    }
+
+    /// Revoke a role from the role registry (multisig approval)
+    pub fn process_revoke_role(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        holder: Pubkey,
+        role: Role,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let role_registry_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        verify_multisig_approval(&treasury_state, accounts)?;
+
+        let mut role_registry =
+            load_role_registry(program_id, role_registry_account, treasury_state_account.key)?;
+
+        role_registry.revoke(&holder, role);
+        role_registry.serialize(&mut &mut role_registry_account.data.borrow_mut()[..])?;
+
+        msg!("Revoked {:?} from {}", role, holder);
+        Ok(())
+    }
+
+    /// Propose a new emergency or compliance authority (current holder or multisig approval)
+    pub fn process_propose_authority(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: AuthorityKind,
+        new_authority: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if new_authority == Pubkey::default() {
+            return Err(EceTokenError::InvalidInstruction.into());
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if authority.key != treasury_state.authority(kind) {
+            verify_multisig_approval(&treasury_state, accounts)?;
+        }
+
+        *treasury_state.pending_authority_mut(kind) = new_authority;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Proposed {:?} authority {}", kind, new_authority);
+        Ok(())
+    }
+
+    /// Accept a proposed authority handover (must be signed by the proposed key)
+    pub fn process_accept_authority(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: AuthorityKind,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let new_authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !new_authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        let pending = *treasury_state.pending_authority(kind);
+        if pending == Pubkey::default() {
+            return Err(EceTokenError::NoPendingAuthority.into());
+        }
+
+        if new_authority.key != &pending {
+            return Err(EceTokenError::UnauthorizedSigner.into());
+        }
+
+        match kind {
+            AuthorityKind::Emergency => treasury_state.emergency_authority = pending,
+            AuthorityKind::Compliance => treasury_state.compliance_authority = pending,
+        }
+        *treasury_state.pending_authority_mut(kind) = Pubkey::default();
+
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("{:?} authority handed over to {}", kind, pending);
+        Ok(())
+    }
+
+    /// Cancel a pending authority handover (current holder or multisig approval)
+    pub fn process_cancel_authority_transfer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: AuthorityKind,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        // The same approval that can propose a handover is needed to cancel one
+        if authority.key != treasury_state.authority(kind) {
+            verify_multisig_approval(&treasury_state, accounts)?;
+        }
+
+        if treasury_state.pending_authority(kind) == &Pubkey::default() {
+            return Err(EceTokenError::NoPendingAuthority.into());
+        }
+
+        *treasury_state.pending_authority_mut(kind) = Pubkey::default();
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Cancelled pending {:?} authority transfer", kind);
+        Ok(())
+    }
+
+    /// Configure the USDC/USD oracle used to guard mint and burn against a depeg
+    pub fn process_set_usdc_oracle(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        oracle: Pubkey,
+        max_deviation_bps: u16,
+        max_staleness: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if max_deviation_bps > 10000 || max_staleness <= 0 {
+            return Err(EceTokenError::InvalidInstruction.into());
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        // Make sure a new oracle actually holds a Pyth price before relying on it
+        if oracle != Pubkey::default() {
+            let oracle_account = next_account_info(account_info_iter)?;
+            if oracle_account.key != &oracle {
+                return Err(EceTokenError::InvalidOracleAccount.into());
+            }
+            OraclePrice::from_pyth_account_data(&oracle_account.data.borrow())?;
+        }
+
+        treasury_state.usdc_oracle = oracle;
+        treasury_state.max_peg_deviation_bps = max_deviation_bps;
+        treasury_state.max_oracle_staleness = max_staleness;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("USDC oracle set to {} ({} bps band, {}s staleness)",
+             oracle, max_deviation_bps, max_staleness);
+        Ok(())
+    }
+
+    /// Configure mint and redeem fees and the USDC account collecting them
+    pub fn process_set_fees(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        mint_fee_bps: u16,
+        redeem_fee_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let fee_usdc_account = next_account_info(account_info_iter)?;
+        let fee_ece_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if mint_fee_bps > MAX_MINT_REDEEM_FEE_BPS || redeem_fee_bps > MAX_MINT_REDEEM_FEE_BPS {
+            return Err(EceTokenError::InvalidFeeConfig.into());
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        let fee_token_account = unpack_token_account(fee_usdc_account)?;
+        if fee_token_account.mint != treasury_state.usdc_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        if unpack_token_account(fee_ece_account)?.mint != treasury_state.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        treasury_state.mint_fee_bps = mint_fee_bps;
+        treasury_state.redeem_fee_bps = redeem_fee_bps;
+        treasury_state.fee_usdc_account = *fee_usdc_account.key;
+        treasury_state.fee_ece_account = *fee_ece_account.key;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Fees set: mint {} bps, redeem {} bps, collected into {} and {}",
+             mint_fee_bps, redeem_fee_bps, fee_usdc_account.key, fee_ece_account.key);
+        Ok(())
+    }
+
+    /// Create ECE as a Token-2022 mint with metadata and an optional transfer fee
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_initialize_token_2022(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        decimals: u8,
+        freeze_authority: Option<Pubkey>,
+        name: String,
+        symbol: String,
+        uri: String,
+        transfer_fee_bps: u16,
+        maximum_fee: u64,
+        transfer_hook: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let mint_authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer || !mint_account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if token_program.key != &spl_token_2022::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if name.is_empty()
+            || name.len() > MAX_TOKEN_NAME_LEN
+            || symbol.is_empty()
+            || symbol.len() > MAX_TOKEN_SYMBOL_LEN
+            || uri.len() > MAX_TOKEN_URI_LEN
+        {
+            return Err(EceTokenError::InvalidTokenMetadata.into());
+        }
+
+        if transfer_fee_bps > MAX_MINT_REDEEM_FEE_BPS {
+            return Err(EceTokenError::InvalidFeeConfig.into());
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if treasury_state.ece_circulation > 0 {
+            return Err(EceTokenError::MintConfigurationLocked.into());
+        }
+
+        if decimals.abs_diff(treasury_state.usdc_decimals) > MAX_DECIMALS_DIFFERENCE {
+            return Err(EceTokenError::DecimalsMismatch.into());
+        }
+
+        let (mint_authority_address, mint_authority_bump) =
+            Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], program_id);
+        if mint_authority.key != &mint_authority_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+        let (treasury_authority, _) = Pubkey::find_program_address(&[TREASURY_AUTHORITY_SEED], program_id);
+
+        let mut extensions = vec![ExtensionType::MetadataPointer];
+        if transfer_fee_bps > 0 {
+            extensions.push(ExtensionType::TransferFeeConfig);
+        }
+        if transfer_hook {
+            extensions.push(ExtensionType::TransferHook);
+        }
+        let mint_len = ExtensionType::try_calculate_account_len::<Mint>(&extensions)?;
+
+        // The metadata is appended by the token program, so prefund its rent up front
+        let token_metadata = TokenMetadata {
+            mint: *mint_account.key,
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            ..Default::default()
+        };
+        let metadata_len = token_metadata.tlv_size_of()?;
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+        invoke(
+            &system_instruction::create_account(
+                authority.key,
+                mint_account.key,
+                rent.minimum_balance(mint_len + metadata_len),
+                mint_len as u64,
+                token_program.key,
+            ),
+            &[authority.clone(), mint_account.clone(), system_program.clone()],
+        )?;
+
+        invoke(
+            &metadata_pointer::instruction::initialize(
+                token_program.key,
+                mint_account.key,
+                Some(mint_authority_address),
+                Some(*mint_account.key),
+            )?,
+            &[mint_account.clone(), token_program.clone()],
+        )?;
+
+        // Withheld transfer fees can only be withdrawn by the treasury authority
+        if transfer_fee_bps > 0 {
+            invoke(
+                &transfer_fee::instruction::initialize_transfer_fee_config(
+                    token_program.key,
+                    mint_account.key,
+                    Some(&treasury_authority),
+                    Some(&treasury_authority),
+                    transfer_fee_bps,
+                    maximum_fee,
+                )?,
+                &[mint_account.clone(), token_program.clone()],
+            )?;
+        }
+
+        // The hook still needs its extra account metas (InitializeExtraAccountMetaList)
+        // before any transfer can go through
+        if transfer_hook {
+            invoke(
+                &transfer_hook::instruction::initialize(
+                    token_program.key,
+                    mint_account.key,
+                    Some(treasury_authority),
+                    Some(*program_id),
+                )?,
+                &[mint_account.clone(), token_program.clone()],
+            )?;
+        }
+
+        invoke(
+            &token_instruction::initialize_mint2(
+                token_program.key,
+                mint_account.key,
+                &mint_authority_address,
+                freeze_authority.as_ref(),
+                decimals,
+            )?,
+            &[mint_account.clone(), token_program.clone()],
+        )?;
+
+        invoke_signed(
+            &metadata_instruction::initialize(
+                token_program.key,
+                mint_account.key,
+                &mint_authority_address,
+                mint_account.key,
+                &mint_authority_address,
+                name,
+                symbol,
+                uri,
+            ),
+            &[mint_account.clone(), mint_authority.clone(), token_program.clone()],
+            &[&[MINT_AUTHORITY_SEED, &[mint_authority_bump]]],
+        )?;
+
+        treasury_state.ece_mint = *mint_account.key;
+        treasury_state.ece_decimals = decimals;
+        treasury_state.ece_token_program = *token_program.key;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("ECE Token-2022 mint initialized with {} decimals, {} bps transfer fee, transfer hook {}",
+             decimals, transfer_fee_bps, if transfer_hook { "on" } else { "off" });
+        Ok(())
+    }
+
+    /// Sweep withheld Token-2022 transfer fees into the treasury's ECE account
+    pub fn process_withdraw_transfer_fees(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let treasury_ece_account = next_account_info(account_info_iter)?;
+        let treasury_authority = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let source_accounts: Vec<&AccountInfo> = account_info_iter.collect();
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if token_program.key != &spl_token_2022::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if ece_mint.key != &treasury_state.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        let (treasury_authority_address, bump_seed) =
+            Pubkey::find_program_address(&[TREASURY_AUTHORITY_SEED], program_id);
+        if treasury_authority.key != &treasury_authority_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        // Fees always land in the treasury authority's ECE account
+        let expected_destination = get_associated_token_address_with_program_id(
+            &treasury_authority_address,
+            ece_mint.key,
+            token_program.key,
+        );
+        if treasury_ece_account.key != &expected_destination {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        let signer_seeds: &[&[u8]] = &[TREASURY_AUTHORITY_SEED, &[bump_seed]];
+
+        invoke_signed(
+            &transfer_fee::instruction::withdraw_withheld_tokens_from_mint(
+                token_program.key,
+                ece_mint.key,
+                treasury_ece_account.key,
+                treasury_authority.key,
+                &[],
+            )?,
+            &[
+                ece_mint.clone(),
+                treasury_ece_account.clone(),
+                treasury_authority.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        if !source_accounts.is_empty() {
+            let source_keys: Vec<&Pubkey> = source_accounts.iter().map(|a| a.key).collect();
+            let mut account_infos = vec![
+                ece_mint.clone(),
+                treasury_ece_account.clone(),
+                treasury_authority.clone(),
+                token_program.clone(),
+            ];
+            account_infos.extend(source_accounts.iter().map(|a| (*a).clone()));
+
+            invoke_signed(
+                &transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
+                    token_program.key,
+                    ece_mint.key,
+                    treasury_ece_account.key,
+                    treasury_authority.key,
+                    &[],
+                    &source_keys,
+                )?,
+                &account_infos,
+                &[signer_seeds],
+            )?;
+        }
+
+        msg!("Withdrew withheld ECE transfer fees to treasury from mint and {} accounts",
+             source_accounts.len());
+        Ok(())
+    }
+
+    /// Add a wallet to the transfer denylist (compliance officer)
+    pub fn process_add_to_denylist(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        wallet: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let denylist_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(
+            program_id,
+            accounts,
+            treasury_state_account,
+            &treasury_state,
+            authority,
+            Role::ComplianceOfficer,
+        )?;
+
+        let mut denylist = if denylist_account.data_is_empty() {
+            let (denylist_address, bump) = Pubkey::find_program_address(
+                &[DENYLIST_SEED, treasury_state_account.key.as_ref()],
+                program_id,
+            );
+            if denylist_account.key != &denylist_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
+            }
+
+            let rent = Rent::from_account_info(rent_sysvar)?;
+            create_pda_account(
+                authority,
+                denylist_account,
+                system_program,
+                &rent,
+                Denylist::LEN,
+                program_id,
+                &[DENYLIST_SEED, treasury_state_account.key.as_ref(), &[bump]],
+            )?;
+
+            Denylist {
+                is_initialized: true,
+                treasury: *treasury_state_account.key,
+                wallet_count: 0,
+                wallets: [Pubkey::default(); MAX_DENYLISTED_WALLETS],
+                bump,
+            }
+        } else {
+            load_denylist(program_id, denylist_account, treasury_state_account.key)?
+        };
+
+        if !denylist.add(&wallet) {
+            return Err(EceTokenError::DenylistFull.into());
+        }
+
+        denylist.serialize(&mut &mut denylist_account.data.borrow_mut()[..])?;
+
+        msg!("Denylisted {}", wallet);
+        Ok(())
+    }
+
+    /// Remove a wallet from the transfer denylist (compliance officer)
+    pub fn process_remove_from_denylist(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        wallet: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let denylist_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(
+            program_id,
+            accounts,
+            treasury_state_account,
+            &treasury_state,
+            authority,
+            Role::ComplianceOfficer,
+        )?;
+
+        let mut denylist = load_denylist(program_id, denylist_account, treasury_state_account.key)?;
+        denylist.remove(&wallet);
+        denylist.serialize(&mut &mut denylist_account.data.borrow_mut()[..])?;
+
+        msg!("Removed {} from denylist", wallet);
+        Ok(())
+    }
+
+    /// Create the transfer hook's extra account metas for the ECE mint (treasury admin).
+    /// The list is fixed by the program; a caller-supplied list must be empty or match it.
+    pub fn process_initialize_extra_account_meta_list(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        extra_account_metas: Vec<ExtraAccountMeta>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let validation_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let authority = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if mint_account.key != &treasury_state.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        let metas = transfer_hook_extra_account_metas(treasury_state_account.key)?;
+        if !extra_account_metas.is_empty() && extra_account_metas != metas {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (validation_address, bump_seed) =
+            get_extra_account_metas_address_and_bump_seed(mint_account.key, program_id);
+        if validation_account.key != &validation_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let bump_seed = [bump_seed];
+        let signer_seeds = collect_extra_account_metas_signer_seeds(mint_account.key, &bump_seed);
+        let rent = Rent::get()?;
+        create_pda_account(
+            authority,
+            validation_account,
+            system_program,
+            &rent,
+            ExtraAccountMetaList::size_of(metas.len())?,
+            program_id,
+            &signer_seeds,
+        )?;
+
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut validation_account.try_borrow_mut_data()?,
+            &metas,
+        )?;
+
+        msg!("Transfer hook accounts initialized for mint {}", mint_account.key);
+        Ok(())
+    }
+
+    /// Transfer hook called by Token-2022 on every ECE transfer: rejects
+    /// denylisted wallets, and amounts above the treasury's transaction limit
+    /// unless the program itself is moving ECE out of one of its vaults
+    pub fn process_transfer_hook_execute(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        instruction_data: &[u8],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let source_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let destination_account = next_account_info(account_info_iter)?;
+        let owner = next_account_info(account_info_iter)?;
+        let validation_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let denylist_account = next_account_info(account_info_iter)?;
+
+        // Only trust the call when Token-2022 is mid-transfer on the source account
+        let source_data = source_account.try_borrow_data()?;
+        let source = StateWithExtensions::<TokenAccount>::unpack(&source_data)?;
+        let transferring = source
+            .get_extension::<TransferHookAccount>()
+            .map(|extension| bool::from(extension.transferring))
+            .unwrap_or(false);
+        if !transferring {
+            return Err(EceTokenError::NotTransferring.into());
+        }
+
+        let (validation_address, _) =
+            get_extra_account_metas_address_and_bump_seed(mint_account.key, program_id);
+        if validation_account.key != &validation_address || validation_account.owner != program_id {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        ExtraAccountMetaList::check_account_infos::<ExecuteInstruction>(
+            accounts,
+            instruction_data,
+            program_id,
+            &validation_account.data.borrow(),
+        )?;
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        if mint_account.key != &treasury_state.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        // The denylist PDA is only created on first use
+        if !denylist_account.data_is_empty() {
+            let denylist = load_denylist(program_id, denylist_account, treasury_state_account.key)?;
+            let destination = unpack_token_account(destination_account)?;
+
+            if denylist.contains(&source.base.owner)
+                || denylist.contains(owner.key)
+                || denylist.contains(&destination.owner)
+            {
+                return Err(EceTokenError::WalletDenylisted.into());
+            }
+        }
+
+        // Vault movements signed by this program were limited by the instruction that made them
+        if is_program_authority(program_id, owner, treasury_state_account.key) {
+            return Ok(());
+        }
+
+        let usdc_amount = treasury_state.ece_to_usdc(amount).ok_or(EceTokenError::NumericalOverflow)?;
+        if usdc_amount > treasury_state.max_transaction_amount {
+            return Err(EceTokenError::ComplianceCheckFailed.into());
+        }
+
+        Ok(())
+    }
+
+    /// Approve a non-USDC stablecoin as collateral (treasury admin)
+    pub fn process_add_collateral(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        cap: u64,
+        haircut_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let collateral_registry_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let collateral_mint = next_account_info(account_info_iter)?;
+        let collateral_reserve = next_account_info(account_info_iter)?;
+        let treasury_authority = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if cap == 0 || haircut_bps > MAX_COLLATERAL_HAIRCUT_BPS {
+            return Err(EceTokenError::InvalidCollateralConfig.into());
+        }
+
+        // USDC backs ECE through the regular mint path
+        if collateral_mint.key == &treasury_state.usdc_mint || collateral_mint.key == &treasury_state.ece_mint {
+            return Err(EceTokenError::InvalidCollateralConfig.into());
+        }
+
+        if ece_mint.key != &treasury_state.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        check_token_program(token_program)?;
+        if collateral_mint.owner != token_program.key {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let decimals = unpack_mint(collateral_mint)?.decimals;
+
+        let (treasury_authority_address, _) = Pubkey::find_program_address(&[TREASURY_AUTHORITY_SEED], program_id);
+        if treasury_authority.key != &treasury_authority_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+
+        let mut collateral_registry = if collateral_registry_account.data_is_empty() {
+            let (collateral_registry_address, bump) = Pubkey::find_program_address(
+                &[COLLATERAL_REGISTRY_SEED, treasury_state_account.key.as_ref()],
+                program_id,
+            );
+            if collateral_registry_account.key != &collateral_registry_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
+            }
+
+            create_pda_account(
+                authority,
+                collateral_registry_account,
+                system_program,
+                &rent,
+                CollateralRegistry::LEN,
+                program_id,
+                &[COLLATERAL_REGISTRY_SEED, treasury_state_account.key.as_ref(), &[bump]],
+            )?;
+
+            CollateralRegistry {
+                is_initialized: true,
+                treasury: *treasury_state_account.key,
+                asset_count: 0,
+                assets: [CollateralAsset::default(); MAX_COLLATERAL_ASSETS],
+                bump,
+            }
+        } else {
+            load_collateral_registry(program_id, collateral_registry_account, treasury_state_account.key)?
+        };
+
+        if collateral_registry.find(collateral_mint.key).is_some() {
+            return Err(EceTokenError::InvalidCollateralConfig.into());
+        }
+
+        if collateral_registry.asset_count as usize >= MAX_COLLATERAL_ASSETS {
+            return Err(EceTokenError::CollateralRegistryFull.into());
+        }
+
+        let (reserve_address, reserve_bump) = Pubkey::find_program_address(
+            &[COLLATERAL_RESERVE_SEED, treasury_state_account.key.as_ref(), collateral_mint.key.as_ref()],
+            program_id,
+        );
+        if collateral_reserve.key != &reserve_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        create_pda_token_account(
+            authority,
+            collateral_reserve,
+            collateral_mint,
+            &treasury_authority_address,
+            token_program,
+            system_program,
+            &rent,
+            &[
+                COLLATERAL_RESERVE_SEED,
+                treasury_state_account.key.as_ref(),
+                collateral_mint.key.as_ref(),
+                &[reserve_bump],
+            ],
+        )?;
+
+        collateral_registry.add(CollateralAsset {
+            mint: *collateral_mint.key,
+            reserve: reserve_address,
+            decimals,
+            cap,
+            haircut_bps,
+            deposited: 0,
+            is_active: true,
+        });
+        collateral_registry.serialize(&mut &mut collateral_registry_account.data.borrow_mut()[..])?;
+
+        treasury_state.collateral_reserves = collateral_registry
+            .total_value(treasury_state.ece_decimals)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Approved collateral {} ({} decimals), cap {}, haircut {} bps",
+             collateral_mint.key, decimals, cap, haircut_bps);
+        Ok(())
+    }
+
+    /// Update a collateral asset and revalue the basket (treasury admin)
+    pub fn process_update_collateral(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        mint: Pubkey,
+        cap: u64,
+        haircut_bps: u16,
+        is_active: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let collateral_registry_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if haircut_bps > MAX_COLLATERAL_HAIRCUT_BPS {
+            return Err(EceTokenError::InvalidCollateralConfig.into());
+        }
+
+        if ece_mint.key != &treasury_state.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        let mut collateral_registry =
+            load_collateral_registry(program_id, collateral_registry_account, treasury_state_account.key)?;
+
+        // A cap below current deposits only blocks further mints
+        let asset = collateral_registry
+            .find_mut(&mint)
+            .ok_or(EceTokenError::CollateralNotApproved)?;
+        asset.cap = cap;
+        asset.haircut_bps = haircut_bps;
+        asset.is_active = is_active;
+
+        collateral_registry.serialize(&mut &mut collateral_registry_account.data.borrow_mut()[..])?;
+
+        treasury_state.collateral_reserves = collateral_registry
+            .total_value(treasury_state.ece_decimals)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Updated collateral {}: cap {}, haircut {} bps, active {}", mint, cap, haircut_bps, is_active);
+        Ok(())
+    }
+
+    /// Mint ECE against an approved non-USDC stablecoin
+    pub fn process_mint_with_collateral(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let user = next_account_info(account_info_iter)?;
+        let source_collateral_account = next_account_info(account_info_iter)?;
+        let collateral_mint = next_account_info(account_info_iter)?;
+        let collateral_reserve = next_account_info(account_info_iter)?;
+        let collateral_registry_account = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let mint_authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let kyc_record_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+
+        if !user.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_MINT) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        let mut kyc_record =
+            load_kyc_record(program_id, kyc_record_account, treasury_state_account.key, user.key)?;
+        if !kyc_record.is_eligible(clock.unix_timestamp) {
+            return Err(EceTokenError::KycNotVerified.into());
+        }
+
+        check_ece_mint(ece_mint, &treasury_state)?;
+
+        let mut collateral_registry =
+            load_collateral_registry(program_id, collateral_registry_account, treasury_state_account.key)?;
+        let asset = *collateral_registry
+            .find(collateral_mint.key)
+            .filter(|asset| asset.is_active)
+            .ok_or(EceTokenError::CollateralNotApproved)?;
+
+        if collateral_reserve.key != &asset.reserve {
+            return Err(EceTokenError::InvalidTreasuryAccount.into());
+        }
+
+        if !asset.has_capacity(amount) {
+            return Err(EceTokenError::CollateralCapExceeded.into());
+        }
+
+        let (mint_authority_address, mint_authority_bump) =
+            Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], program_id);
+        if mint_authority.key != &mint_authority_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        check_token_program(token_program)?;
+        let ece_token_program = find_ece_token_program(accounts, &treasury_state, token_program)?;
+
+        // Credit what actually lands in reserve, in case the collateral charges transfer fees
+        let reserve_before = unpack_token_account(collateral_reserve)?.amount;
+        invoke(
+            &token_instruction::transfer_checked(
+                token_program.key,
+                source_collateral_account.key,
+                collateral_mint.key,
+                collateral_reserve.key,
+                user.key,
+                &[],
+                amount,
+                asset.decimals,
+            )?,
+            &[
+                source_collateral_account.clone(),
+                collateral_mint.clone(),
+                collateral_reserve.clone(),
+                user.clone(),
+                token_program.clone(),
+            ],
+        )?;
+        let received = unpack_token_account(collateral_reserve)?
+            .amount
+            .checked_sub(reserve_before)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        let value = asset
+            .collateral_value(received, treasury_state.ece_decimals)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        // The mint fee is withheld from the minted ECE and stays in reserve as surplus
+        let fee = treasury_state.mint_fee(value).ok_or(EceTokenError::NumericalOverflow)?;
+        let net_amount = value - fee;
+
+        if net_amount == 0 {
+            return Err(EceTokenError::InvalidUsdcAmount.into());
+        }
+
+        // Check compliance limits, which are denominated in USDC
+        let usdc_value = treasury_state.ece_to_usdc_ceil(net_amount).ok_or(EceTokenError::NumericalOverflow)?;
+        if usdc_value > treasury_state.max_transaction_amount || usdc_value > treasury_state.user_mint_limit {
+            return Err(EceTokenError::ComplianceCheckFailed.into());
+        }
+
+        if kyc_record.would_exceed_daily_mint(usdc_value, treasury_state.user_daily_mint_limit, clock.unix_timestamp) {
+            return Err(EceTokenError::ComplianceCheckFailed.into());
+        }
+
+        if treasury_state.would_exceed_daily_limit(usdc_value, clock.unix_timestamp) {
+            return Err(EceTokenError::ComplianceCheckFailed.into());
+        }
+
+        invoke_signed(
+            &token_instruction::mint_to(
+                ece_token_program.key,
+                ece_mint.key,
+                destination_ece_account.key,
+                &mint_authority_address,
+                &[],
+                net_amount,
+            )?,
+            &[
+                ece_mint.clone(),
+                destination_ece_account.clone(),
+                mint_authority.clone(),
+                ece_token_program.clone(),
+            ],
+            &[&[MINT_AUTHORITY_SEED, &[mint_authority_bump]]],
+        )?;
+
+        if let Some(asset) = collateral_registry.find_mut(collateral_mint.key) {
+            asset.deposited = asset
+                .deposited
+                .checked_add(received)
+                .ok_or(EceTokenError::NumericalOverflow)?;
+        }
+        collateral_registry.serialize(&mut &mut collateral_registry_account.data.borrow_mut()[..])?;
+
+        kyc_record.record_mint(usdc_value, clock.unix_timestamp);
+        kyc_record.serialize(&mut &mut kyc_record_account.data.borrow_mut()[..])?;
+
+        accrue_referral_reward(program_id, accounts, treasury_state_account.key, user.key, net_amount)?;
+
+        treasury_state.ece_circulation = treasury_state
+            .ece_circulation
+            .checked_add(net_amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.collateral_reserves = collateral_registry
+            .total_value(treasury_state.ece_decimals)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.update_daily_volume(usdc_value, clock.unix_timestamp);
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Minted {} ECE against {} of collateral {} (valued {})",
+             net_amount, received, collateral_mint.key, value);
+        Ok(())
+    }
+
+    /// Point the treasury at its ECE and USDC mints and record their decimals (treasury admin).
+    /// Only allowed while no ECE is outstanding.
+    pub fn process_configure_mints(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let usdc_mint = next_account_info(account_info_iter)?;
+        let usdc_reserve = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if treasury_state.ece_circulation > 0 {
+            return Err(EceTokenError::MintConfigurationLocked.into());
+        }
+
+        if (ece_mint.owner != &spl_token::id() && ece_mint.owner != &spl_token_2022::id())
+            || (usdc_mint.owner != &spl_token::id() && usdc_mint.owner != &spl_token_2022::id())
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if ece_mint.key == usdc_mint.key {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        let ece_decimals = unpack_mint(ece_mint)?.decimals;
+        let usdc_decimals = unpack_mint(usdc_mint)?.decimals;
+        if ece_decimals.abs_diff(usdc_decimals) > MAX_DECIMALS_DIFFERENCE {
+            return Err(EceTokenError::DecimalsMismatch.into());
+        }
+
+        // Every reserve outflow is signed by the treasury authority PDA, so it must own the reserve
+        let (treasury_authority, _) = Pubkey::find_program_address(&[TREASURY_AUTHORITY_SEED], program_id);
+        let reserve = unpack_token_account(usdc_reserve)?;
+        if usdc_reserve.owner != usdc_mint.owner
+            || reserve.mint != *usdc_mint.key
+            || reserve.owner != treasury_authority
+        {
+            return Err(EceTokenError::InvalidTreasuryAccount.into());
+        }
+
+        treasury_state.ece_mint = *ece_mint.key;
+        treasury_state.ece_decimals = ece_decimals;
+        treasury_state.ece_token_program = *ece_mint.owner;
+        treasury_state.usdc_mint = *usdc_mint.key;
+        treasury_state.usdc_decimals = usdc_decimals;
+        treasury_state.usdc_reserve = *usdc_reserve.key;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Configured ECE mint {} ({} decimals) backed by USDC mint {} ({} decimals)",
+             ece_mint.key, ece_decimals, usdc_mint.key, usdc_decimals);
+        Ok(())
+    }
+
+    /// Mint ECE against surplus reserves without a new deposit (minter role)
+    pub fn process_mint_from_reserves(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let mint_authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Minter)?;
+
+        if treasury_state.is_operation_paused(PAUSE_MINT) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        if amount == 0 {
+            return Err(EceTokenError::InvalidInstruction.into());
+        }
+
+        let usdc_value = treasury_state.ece_to_usdc_ceil(amount).ok_or(EceTokenError::NumericalOverflow)?;
+        if usdc_value > treasury_state.reserve_mint_limit {
+            return Err(EceTokenError::ComplianceCheckFailed.into());
+        }
+
+        if !treasury_state.can_mint_from_reserves(amount) {
+            return Err(EceTokenError::ReserveRatioBelowMinimum.into());
+        }
+
+        check_ece_mint(ece_mint, &treasury_state)?;
+
+        if token_program.key != &treasury_state.ece_token_program {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let (mint_authority_address, mint_authority_bump) =
+            Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], program_id);
+        if mint_authority.key != &mint_authority_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        invoke_signed(
+            &token_instruction::mint_to(
+                token_program.key,
+                ece_mint.key,
+                destination_ece_account.key,
+                &mint_authority_address,
+                &[],
+                amount,
+            )?,
+            &[
+                ece_mint.clone(),
+                destination_ece_account.clone(),
+                mint_authority.clone(),
+                token_program.clone(),
+            ],
+            &[&[MINT_AUTHORITY_SEED, &[mint_authority_bump]]],
+        )?;
+
+        treasury_state.ece_circulation = treasury_state
+            .ece_circulation
+            .checked_add(amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Minted {} ECE from surplus reserves, reserve ratio now {} bps",
+             amount, treasury_state.reserve_ratio());
+        Ok(())
+    }
+
+    /// Set a wallet's KYC status for self-service minting (compliance officer)
+    pub fn process_set_kyc_status(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        wallet: Pubkey,
+        verified: bool,
+        expires_at: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let kyc_record_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(
+            program_id,
+            accounts,
+            treasury_state_account,
+            &treasury_state,
+            authority,
+            Role::ComplianceOfficer,
+        )?;
+
+        let clock = Clock::get()?;
+
+        let mut kyc_record = if kyc_record_account.data_is_empty() {
+            let (kyc_record_address, bump) = Pubkey::find_program_address(
+                &[KYC_RECORD_SEED, treasury_state_account.key.as_ref(), wallet.as_ref()],
+                program_id,
+            );
+            if kyc_record_account.key != &kyc_record_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
+            }
+
+            let rent = Rent::from_account_info(rent_sysvar)?;
+            create_pda_account(
+                authority,
+                kyc_record_account,
+                system_program,
+                &rent,
+                KycRecord::LEN,
+                program_id,
+                &[KYC_RECORD_SEED, treasury_state_account.key.as_ref(), wallet.as_ref(), &[bump]],
+            )?;
+
+            KycRecord {
+                is_initialized: true,
+                treasury: *treasury_state_account.key,
+                wallet,
+                verified: false,
+                updated_at: 0,
+                expires_at: 0,
+                daily_minted: 0,
+                mint_day: 0,
+                bump,
+            }
+        } else {
+            load_kyc_record(program_id, kyc_record_account, treasury_state_account.key, &wallet)?
+        };
+
+        kyc_record.verified = verified;
+        kyc_record.expires_at = expires_at;
+        kyc_record.updated_at = clock.unix_timestamp;
+        kyc_record.serialize(&mut &mut kyc_record_account.data.borrow_mut()[..])?;
+
+        msg!("KYC status for {}: verified {}, expires at {}", wallet, verified, expires_at);
+        Ok(())
+    }
+
+    /// Set the self-service and admin mint limits (treasury admin)
+    pub fn process_set_mint_limits(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        user_mint_limit: u64,
+        user_daily_mint_limit: u64,
+        reserve_mint_limit: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        treasury_state.user_mint_limit = user_mint_limit;
+        treasury_state.user_daily_mint_limit = user_daily_mint_limit;
+        treasury_state.reserve_mint_limit = reserve_mint_limit;
+        treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+        msg!("Mint limits set: {} per user mint, {} per user per day, {} per reserve mint",
+             user_mint_limit, user_daily_mint_limit, reserve_mint_limit);
+        Ok(())
+    }
+
+    /// Publish a proof-of-reserves snapshot of ECE holder balances (auditor)
+    pub fn process_publish_reserve_snapshot(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        merkle_root: [u8; 32],
+        total_balances: u64,
+        holder_count: u32,
+        snapshot_slot: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let auditor = next_account_info(account_info_iter)?;
+        let attestation_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+
+        if !auditor.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, auditor, Role::Auditor)?;
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        if snapshot_slot > clock.slot {
+            return Err(EceTokenError::InvalidInstruction.into());
+        }
+
+        let (attestation_address, bump) = Pubkey::find_program_address(
+            &[RESERVE_ATTESTATION_SEED, treasury_state_account.key.as_ref()],
+            program_id,
+        );
+        if attestation_account.key != &attestation_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        if attestation_account.data_is_empty() {
+            let rent = Rent::from_account_info(rent_sysvar)?;
+            create_pda_account(
+                auditor,
+                attestation_account,
+                system_program,
+                &rent,
+                ReserveAttestation::LEN,
+                program_id,
+                &[RESERVE_ATTESTATION_SEED, treasury_state_account.key.as_ref(), &[bump]],
+            )?;
+        } else {
+            if attestation_account.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            let previous = ReserveAttestation::try_from_slice(&attestation_account.data.borrow())?;
+            if previous.is_initialized && snapshot_slot <= previous.snapshot_slot {
+                return Err(EceTokenError::StaleReserveSnapshot.into());
+            }
+        }
+
+        let attestation = ReserveAttestation {
+            is_initialized: true,
+            treasury: *treasury_state_account.key,
+            merkle_root,
+            total_balances,
+            holder_count,
+            snapshot_slot,
+            usdc_reserves: treasury_state.usdc_reserves,
+            collateral_reserves: treasury_state.collateral_reserves,
+            ece_circulation: treasury_state.ece_circulation,
+            published_at: clock.unix_timestamp,
+            auditor: *auditor.key,
+            bump,
+        };
+        attestation.serialize(&mut &mut attestation_account.data.borrow_mut()[..])?;
+
+        msg!("Reserve snapshot at slot {}: {} ECE across {} holders, {} USDC in reserve",
+             snapshot_slot, total_balances, holder_count, treasury_state.usdc_reserves);
+        Ok(())
+    }
+
+    /// Create and fund a merkle distributor (treasury admin)
+    pub fn process_create_distributor(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        distributor_id: u64,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_claims: u32,
+        expires_at: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let distributor_account = next_account_info(account_info_iter)?;
+        let vault = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let funding_ece_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        check_ece_mint(ece_mint, &treasury_state)?;
+
+        if token_program.key != &treasury_state.ece_token_program {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        if total_amount == 0
+            || num_claims == 0
+            || num_claims > MAX_DISTRIBUTOR_CLAIMS
+            || expires_at <= clock.unix_timestamp
+        {
+            return Err(EceTokenError::InvalidDistributorConfig.into());
+        }
+
+        if !distributor_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let id_bytes = distributor_id.to_le_bytes();
+        let (distributor_address, distributor_bump) = Pubkey::find_program_address(
+            &[DISTRIBUTOR_SEED, treasury_state_account.key.as_ref(), &id_bytes],
+            program_id,
+        );
+        let (vault_address, vault_bump) = Pubkey::find_program_address(
+            &[DISTRIBUTOR_VAULT_SEED, distributor_address.as_ref()],
+            program_id,
+        );
+
+        if distributor_account.key != &distributor_address || vault.key != &vault_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+
+        create_pda_account(
+            authority,
+            distributor_account,
+            system_program,
+            &rent,
+            MerkleDistributor::space(num_claims),
+            program_id,
+            &[DISTRIBUTOR_SEED, treasury_state_account.key.as_ref(), &id_bytes, &[distributor_bump]],
+        )?;
+
+        create_pda_token_account(
+            authority,
+            vault,
+            ece_mint,
+            &distributor_address,
+            token_program,
+            system_program,
+            &rent,
+            &[DISTRIBUTOR_VAULT_SEED, distributor_address.as_ref(), &[vault_bump]],
+        )?;
+
+        // Every leaf must be claimable, so the vault has to receive the full total after any transfer fee
+        let funding_amount = gross_up_transfer_fee(ece_mint, total_amount, clock.epoch)?;
+        let received = transfer_checked(
+            token_program,
+            funding_ece_account,
+            ece_mint,
+            vault,
+            authority,
+            accounts,
+            funding_amount,
+            &[],
+        )?;
+        if received < total_amount {
+            return Err(EceTokenError::InsufficientFunds.into());
+        }
+
+        let distributor = MerkleDistributor {
+            is_initialized: true,
+            treasury: *treasury_state_account.key,
+            distributor_id,
+            merkle_root,
+            ece_mint: *ece_mint.key,
+            vault: vault_address,
+            total_amount,
+            total_claimed: 0,
+            num_claims,
+            num_claimed: 0,
+            expires_at,
+            is_clawed_back: false,
+            bump: distributor_bump,
+            claimed_bitmap: vec![0; MerkleDistributor::bitmap_len(num_claims)],
+        };
+
+        distributor.serialize(&mut &mut distributor_account.data.borrow_mut()[..])?;
+
+        msg!("Distributor {} created with {} ECE across {} claims", distributor_id, total_amount, num_claims);
+        Ok(())
+    }
+
+    /// Claim ECE from a merkle distributor with a proof
+    pub fn process_claim(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let claimant = next_account_info(account_info_iter)?;
+        let distributor_account = next_account_info(account_info_iter)?;
+        let vault = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !claimant.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        check_token_program(token_program)?;
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut distributor = load_distributor(program_id, distributor_account)?;
+
+        if treasury_state_account.key != &distributor.treasury {
+            return Err(EceTokenError::InvalidTreasuryAccount.into());
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        if treasury_state.is_operation_paused(PAUSE_REWARDS) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        if vault.key != &distributor.vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        if ece_mint.key != &distributor.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        if distributor.is_clawed_back || distributor.is_expired(clock.unix_timestamp) {
+            return Err(EceTokenError::DistributorExpired.into());
+        }
+
+        let claimant_ece_account =
+            get_associated_token_address_with_program_id(claimant.key, &distributor.ece_mint, token_program.key);
+        if destination_ece_account.key != &claimant_ece_account {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        if !verify_proof(&distributor.merkle_root, claim_leaf(index, claimant.key, amount), &proof) {
+            return Err(EceTokenError::InvalidMerkleProof.into());
+        }
+
+        if !distributor.set_claimed(index) {
+            return Err(EceTokenError::AlreadyClaimed.into());
+        }
+
+        distributor.total_claimed = distributor
+            .total_claimed
+            .checked_add(amount)
+            .filter(|claimed| *claimed <= distributor.total_amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        let id_bytes = distributor.distributor_id.to_le_bytes();
+        transfer_checked(
+            token_program,
+            vault,
+            ece_mint,
+            destination_ece_account,
+            distributor_account,
+            accounts,
+            amount,
+            &[&[DISTRIBUTOR_SEED, distributor.treasury.as_ref(), &id_bytes, &[distributor.bump]]],
+        )?;
+
+        distributor.serialize(&mut &mut distributor_account.data.borrow_mut()[..])?;
+
+        msg!("Claim {} paid {} ECE from distributor {}", index, amount, distributor.distributor_id);
+        Ok(())
+    }
+
+    /// Claw back an expired distributor's unclaimed ECE (treasury admin)
+    pub fn process_clawback_distributor(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let distributor_account = next_account_info(account_info_iter)?;
+        let vault = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        check_token_program(token_program)?;
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut distributor = load_distributor(program_id, distributor_account)?;
+
+        if &distributor.treasury != treasury_state_account.key {
+            return Err(EceTokenError::InvalidTreasuryAccount.into());
+        }
+
+        if vault.key != &distributor.vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        if ece_mint.key != &distributor.ece_mint {
+            return Err(EceTokenError::InvalidTokenMint.into());
+        }
+
+        if distributor.is_clawed_back || !distributor.is_expired(clock.unix_timestamp) {
+            return Err(EceTokenError::DistributorNotExpired.into());
+        }
+
+        let remaining = unpack_token_account(vault)?.amount;
+        let id_bytes = distributor.distributor_id.to_le_bytes();
+        if remaining > 0 {
+            transfer_checked(
+                token_program,
+                vault,
+                ece_mint,
+                destination_ece_account,
+                distributor_account,
+                accounts,
+                remaining,
+                &[&[DISTRIBUTOR_SEED, distributor.treasury.as_ref(), &id_bytes, &[distributor.bump]]],
+            )?;
+        }
+
+        distributor.is_clawed_back = true;
+        distributor.serialize(&mut &mut distributor_account.data.borrow_mut()[..])?;
+
+        msg!("Clawed back {} unclaimed ECE from distributor {}", remaining, distributor.distributor_id);
+        Ok(())
+    }
+
+    /// Create and fund a vesting schedule (treasury admin)
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_create_vesting(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        vesting_id: u64,
+        beneficiary: Pubkey,
+        total_amount: u64,
+        start_timestamp: i64,
+        cliff_timestamp: i64,
+        end_timestamp: i64,
+        revocable: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let vesting_account = next_account_info(account_info_iter)?;
+        let vault = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let funding_ece_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        check_ece_mint(ece_mint, &treasury_state)?;
+
+        if token_program.key != &treasury_state.ece_token_program {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if total_amount == 0 || !VestingSchedule::is_valid_schedule(start_timestamp, cliff_timestamp, end_timestamp) {
+            return Err(EceTokenError::InvalidVestingSchedule.into());
+        }
+
+        if !vesting_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let id_bytes = vesting_id.to_le_bytes();
+        let (vesting_address, vesting_bump) = Pubkey::find_program_address(
+            &[VESTING_SEED, treasury_state_account.key.as_ref(), beneficiary.as_ref(), &id_bytes],
+            program_id,
+        );
+        let (vault_address, vault_bump) = Pubkey::find_program_address(
+            &[VESTING_VAULT_SEED, vesting_address.as_ref()],
+            program_id,
+        );
+
+        if vesting_account.key != &vesting_address || vault.key != &vault_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+
+        create_pda_account(
+            authority,
+            vesting_account,
+            system_program,
+            &rent,
+            VestingSchedule::LEN,
+            program_id,
+            &[
+                VESTING_SEED,
+                treasury_state_account.key.as_ref(),
+                beneficiary.as_ref(),
+                &id_bytes,
+                &[vesting_bump],
+            ],
+        )?;
+
+        create_pda_token_account(
+            authority,
+            vault,
+            ece_mint,
+            &vesting_address,
+            token_program,
+            system_program,
+            &rent,
+            &[VESTING_VAULT_SEED, vesting_address.as_ref(), &[vault_bump]],
+        )?;
+
+        // The schedule vests what the vault received, net of any transfer fee
+        let received = transfer_checked(
+            token_program,
+            funding_ece_account,
+            ece_mint,
+            vault,
+            authority,
+            accounts,
+            total_amount,
+            &[],
+        )?;
+        if received == 0 {
+            return Err(EceTokenError::InvalidVestingSchedule.into());
+        }
+
+        let vesting = VestingSchedule {
+            is_initialized: true,
+            treasury: *treasury_state_account.key,
+            beneficiary,
+            vesting_id,
+            ece_mint: *ece_mint.key,
+            vault: vault_address,
+            total_amount: received,
+            released_amount: 0,
+            start_timestamp,
+            cliff_timestamp,
+            end_timestamp,
+            revocable,
+            revoked_at: 0,
+            bump: vesting_bump,
+        };
+
+        vesting.serialize(&mut &mut vesting_account.data.borrow_mut()[..])?;
+
+        msg!("Vesting {} of {} ECE created for {}", vesting_id, received, beneficiary);
+        Ok(())
+    }
+
+    /// Release vested ECE to the beneficiary's associated token account (permissionless)
+    pub fn process_release(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let vesting_account = next_account_info(account_info_iter)?;
+        let vault = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        check_token_program(token_program)?;
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut vesting = load_vesting_schedule(program_id, vesting_account)?;
+
+        if treasury_state_account.key != &vesting.treasury {
+            return Err(EceTokenError::InvalidTreasuryAccount.into());
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        if treasury_state.is_operation_paused(PAUSE_REWARDS) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        if vault.key != &vesting.vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        let beneficiary_ece_account = get_associated_token_address_with_program_id(
+            &vesting.beneficiary,
+            &vesting.ece_mint,
+            token_program.key,
+        );
+        if destination_ece_account.key != &beneficiary_ece_account {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        let amount = vesting.releasable_amount(clock.unix_timestamp);
+        if amount == 0 {
+            return Err(EceTokenError::NothingToRelease.into());
+        }
+
+        vesting_transfer(
+            vesting_account,
+            &vesting,
+            vault,
+            ece_mint,
+            destination_ece_account,
+            token_program,
+            accounts,
+            amount,
+        )?;
+
+        vesting.released_amount += amount;
+        vesting.serialize(&mut &mut vesting_account.data.borrow_mut()[..])?;
+
+        msg!("Released {} ECE to {}, {} of {} released",
+             amount, vesting.beneficiary, vesting.released_amount, vesting.total_amount);
+        Ok(())
+    }
+
+    /// Revoke a vesting schedule's unvested ECE (treasury multisig)
+    pub fn process_revoke_vesting(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let vesting_account = next_account_info(account_info_iter)?;
+        let vault = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        verify_multisig_approval(&treasury_state, accounts)?;
+
+        check_token_program(token_program)?;
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut vesting = load_vesting_schedule(program_id, vesting_account)?;
+
+        if &vesting.treasury != treasury_state_account.key {
+            return Err(EceTokenError::InvalidTreasuryAccount.into());
+        }
+
+        if vault.key != &vesting.vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        if !vesting.revocable || vesting.is_revoked() {
+            return Err(EceTokenError::VestingNotRevocable.into());
+        }
+
+        let vested = vesting.vested_amount(clock.unix_timestamp);
+        let unvested = vesting.total_amount - vested;
+
+        if unvested > 0 {
+            vesting_transfer(
+                vesting_account,
+                &vesting,
+                vault,
+                ece_mint,
+                destination_ece_account,
+                token_program,
+                accounts,
+                unvested,
+            )?;
+        }
+
+        vesting.total_amount = vested;
+        vesting.revoked_at = clock.unix_timestamp;
+        vesting.serialize(&mut &mut vesting_account.data.borrow_mut()[..])?;
+
+        msg!("Vesting {} revoked, {} unvested ECE returned, {} ECE remains with {}",
+             vesting.vesting_id, unvested, vested, vesting.beneficiary);
+        Ok(())
+    }
+
+    /// Configure referral rewards (treasury admin)
+    pub fn process_configure_referrals(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        reward_bps: u16,
+        max_reward_per_referrer: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let referral_config_account = next_account_info(account_info_iter)?;
+        let vault = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+        require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Admin)?;
+
+        if reward_bps > MAX_REFERRAL_REWARD_BPS {
+            return Err(EceTokenError::InvalidReferral.into());
+        }
+
+        let mut referral_config = if referral_config_account.data_is_empty() {
+            check_ece_mint(ece_mint, &treasury_state)?;
+
+            if token_program.key != &treasury_state.ece_token_program {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            let (config_address, config_bump) = Pubkey::find_program_address(
+                &[REFERRAL_CONFIG_SEED, treasury_state_account.key.as_ref()],
+                program_id,
+            );
+            let (vault_address, vault_bump) = Pubkey::find_program_address(
+                &[REFERRAL_VAULT_SEED, config_address.as_ref()],
+                program_id,
+            );
+
+            if referral_config_account.key != &config_address || vault.key != &vault_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
+            }
+
+            let rent = Rent::from_account_info(rent_sysvar)?;
+
+            create_pda_account(
+                authority,
+                referral_config_account,
+                system_program,
+                &rent,
+                ReferralConfig::LEN,
+                program_id,
+                &[REFERRAL_CONFIG_SEED, treasury_state_account.key.as_ref(), &[config_bump]],
+            )?;
+
+            create_pda_token_account(
+                authority,
+                vault,
+                ece_mint,
+                &config_address,
+                token_program,
+                system_program,
+                &rent,
+                &[REFERRAL_VAULT_SEED, config_address.as_ref(), &[vault_bump]],
+            )?;
+
+            ReferralConfig {
+                is_initialized: true,
+                treasury: *treasury_state_account.key,
+                vault: vault_address,
+                reward_bps: 0,
+                max_reward_per_referrer: 0,
+                total_accrued: 0,
+                total_claimed: 0,
+                bump: config_bump,
+            }
+        } else {
+            load_referral_config(program_id, referral_config_account, treasury_state_account.key)?
+        };
+
+        referral_config.reward_bps = reward_bps;
+        referral_config.max_reward_per_referrer = max_reward_per_referrer;
+        referral_config.serialize(&mut &mut referral_config_account.data.borrow_mut()[..])?;
+
+        msg!("Referral rewards set to {} bps, capped at {} ECE per referrer", reward_bps, max_reward_per_referrer);
+        Ok(())
+    }
+
+    /// Register the signing user's referrer
+    pub fn process_register_referral(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        referrer: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let user = next_account_info(account_info_iter)?;
+        let referral_account = next_account_info(account_info_iter)?;
+        let referrer_account_info = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+
+        if !user.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if treasury_state_account.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if &referrer == user.key || referrer == Pubkey::default() {
+            return Err(EceTokenError::InvalidReferral.into());
+        }
+
+        if !referral_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let (referral_address, referral_bump) = Pubkey::find_program_address(
+            &[REFERRAL_SEED, treasury_state_account.key.as_ref(), user.key.as_ref()],
+            program_id,
+        );
+        if referral_account.key != &referral_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let rent = Rent::from_account_info(rent_sysvar)?;
+
+        let mut referrer_account = if referrer_account_info.data_is_empty() {
+            let (referrer_address, referrer_bump) = Pubkey::find_program_address(
+                &[REFERRER_SEED, treasury_state_account.key.as_ref(), referrer.as_ref()],
+                program_id,
+            );
+            if referrer_account_info.key != &referrer_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
+            }
+
+            create_pda_account(
+                user,
+                referrer_account_info,
+                system_program,
+                &rent,
+                ReferrerAccount::LEN,
+                program_id,
+                &[REFERRER_SEED, treasury_state_account.key.as_ref(), referrer.as_ref(), &[referrer_bump]],
+            )?;
+
+            ReferrerAccount {
+                is_initialized: true,
+                treasury: *treasury_state_account.key,
+                referrer,
+                referral_count: 0,
+                total_earned: 0,
+                claimable: 0,
+                bump: referrer_bump,
+            }
+        } else {
+            load_referrer_account(program_id, referrer_account_info, treasury_state_account.key, &referrer)?
+        };
+
+        create_pda_account(
+            user,
+            referral_account,
+            system_program,
+            &rent,
+            ReferralRecord::LEN,
+            program_id,
+            &[REFERRAL_SEED, treasury_state_account.key.as_ref(), user.key.as_ref(), &[referral_bump]],
+        )?;
+
+        let referral = ReferralRecord {
+            is_initialized: true,
+            treasury: *treasury_state_account.key,
+            user: *user.key,
+            referrer,
+            registered_at: clock.unix_timestamp,
+            bump: referral_bump,
+        };
+        referral.serialize(&mut &mut referral_account.data.borrow_mut()[..])?;
+
+        referrer_account.referral_count = referrer_account.referral_count.saturating_add(1);
+        referrer_account.serialize(&mut &mut referrer_account_info.data.borrow_mut()[..])?;
+
+        msg!("{} registered as referred by {}", user.key, referrer);
+        Ok(())
+    }
+
+    /// Pay a referrer's accrued rewards from the referral vault
+    pub fn process_claim_referral_rewards(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let referrer = next_account_info(account_info_iter)?;
+        let referrer_account_info = next_account_info(account_info_iter)?;
+        let referral_config_account = next_account_info(account_info_iter)?;
+        let vault = next_account_info(account_info_iter)?;
+        let destination_ece_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let ece_mint = next_account_info(account_info_iter)?;
+
+        if !referrer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_REWARDS) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        if token_program.key != &treasury_state.ece_token_program {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        check_ece_mint(ece_mint, &treasury_state)?;
+
+        let mut referrer_account =
+            load_referrer_account(program_id, referrer_account_info, treasury_state_account.key, referrer.key)?;
+        let mut referral_config =
+            load_referral_config(program_id, referral_config_account, treasury_state_account.key)?;
+
+        if vault.key != &referral_config.vault {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        let referrer_ece_account = get_associated_token_address_with_program_id(
+            referrer.key,
+            &treasury_state.ece_mint,
+            token_program.key,
+        );
+        if destination_ece_account.key != &referrer_ece_account {
+            return Err(EceTokenError::InvalidTokenAccount.into());
+        }
+
+        let amount = referrer_account.claimable;
+        if amount == 0 {
+            return Err(EceTokenError::NoReferralRewards.into());
+        }
+
+        transfer_checked(
+            token_program,
+            vault,
+            ece_mint,
+            destination_ece_account,
+            referral_config_account,
+            accounts,
+            amount,
+            &[&[REFERRAL_CONFIG_SEED, treasury_state_account.key.as_ref(), &[referral_config.bump]]],
+        )?;
+
+        referrer_account.claimable = 0;
+        referral_config.total_claimed = referral_config
+            .total_claimed
+            .checked_add(amount)
+            .ok_or(EceTokenError::NumericalOverflow)?;
+
+        referrer_account.serialize(&mut &mut referrer_account_info.data.borrow_mut()[..])?;
+        referral_config.serialize(&mut &mut referral_config_account.data.borrow_mut()[..])?;
+
+        msg!("Paid {} ECE of referral rewards to {}", amount, referrer.key);
+        Ok(())
+    }
+
+    /// Open a card trade offer, escrowing the maker's side
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_create_trade_offer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        offer_id: u64,
+        offered_card_mint: Pubkey,
+        offered_ece_amount: u64,
+        requested_card_mint: Pubkey,
+        requested_ece_amount: u64,
+        taker: Pubkey,
+        expires_at: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let maker = next_account_info(account_info_iter)?;
+        let offer_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let card_token_program = next_account_info(account_info_iter)?;
+        let ece_token_program = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent_sysvar = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+
+        if !maker.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_ESCROW) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        check_token_program(card_token_program)?;
+        if ece_token_program.key != &treasury_state.ece_token_program {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let valid_terms = TradeOffer::is_valid_terms(
+            &offered_card_mint,
+            offered_ece_amount,
+            &requested_card_mint,
+            requested_ece_amount,
+        );
+        if !valid_terms || (expires_at != 0 && expires_at <= clock.unix_timestamp)
+        {
+            return Err(EceTokenError::InvalidTradeOffer.into());
+        }
+
+        if !offer_account.data_is_empty() {
+            return Err(EceTokenError::AccountAlreadyInitialized.into());
+        }
+
+        let id_bytes = offer_id.to_le_bytes();
+        let (offer_address, offer_bump) = Pubkey::find_program_address(
+            &[TRADE_OFFER_SEED, treasury_state_account.key.as_ref(), maker.key.as_ref(), &id_bytes],
+            program_id,
+        );
+        if offer_account.key != &offer_address {
+            return Err(EceTokenError::InvalidProgramAddress.into());
+        }
+
+        let rent = Rent::from_account_info(rent_sysvar)?;
+
+        create_pda_account(
+            maker,
+            offer_account,
+            system_program,
+            &rent,
+            TradeOffer::LEN,
+            program_id,
+            &[
+                TRADE_OFFER_SEED,
+                treasury_state_account.key.as_ref(),
+                maker.key.as_ref(),
+                &id_bytes,
+                &[offer_bump],
+            ],
+        )?;
+
+        let offer = TradeOffer {
+            is_initialized: true,
+            treasury: *treasury_state_account.key,
+            maker: *maker.key,
+            offer_id,
+            ece_mint: treasury_state.ece_mint,
+            card_token_program: *card_token_program.key,
+            offered_card_mint,
+            offered_ece_amount,
+            requested_card_mint,
+            requested_ece_amount,
+            taker,
+            expires_at,
+            status: TradeOfferStatus::Open,
+            created_at: clock.unix_timestamp,
+            bump: offer_bump,
+        };
+
+        if offer.offers_card() {
+            let maker_card_account = next_account_info(account_info_iter)?;
+            let card_mint = next_account_info(account_info_iter)?;
+            let card_vault = next_account_info(account_info_iter)?;
+
+            if card_mint.key != &offered_card_mint
+                || card_mint.owner != card_token_program.key
+                || unpack_mint(card_mint)?.decimals != 0
+            {
+                return Err(EceTokenError::InvalidTokenMint.into());
+            }
+
+            let (card_vault_address, card_vault_bump) =
+                Pubkey::find_program_address(&[TRADE_CARD_VAULT_SEED, offer_address.as_ref()], program_id);
+            if card_vault.key != &card_vault_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
+            }
+
+            create_pda_token_account(
+                maker,
+                card_vault,
+                card_mint,
+                &offer_address,
+                card_token_program,
+                system_program,
+                &rent,
+                &[TRADE_CARD_VAULT_SEED, offer_address.as_ref(), &[card_vault_bump]],
+            )?;
+
+            transfer_checked(
+                card_token_program,
+                maker_card_account,
+                card_mint,
+                card_vault,
+                maker,
+                accounts,
+                1,
+                &[],
+            )?;
+        }
+
+        if offered_ece_amount > 0 {
+            let maker_ece_account = next_account_info(account_info_iter)?;
+            let ece_mint = next_account_info(account_info_iter)?;
+            let ece_vault = next_account_info(account_info_iter)?;
+
+            check_ece_mint(ece_mint, &treasury_state)?;
+
+            let (ece_vault_address, ece_vault_bump) =
+                Pubkey::find_program_address(&[TRADE_ECE_VAULT_SEED, offer_address.as_ref()], program_id);
+            if ece_vault.key != &ece_vault_address {
+                return Err(EceTokenError::InvalidProgramAddress.into());
+            }
+
+            create_pda_token_account(
+                maker,
+                ece_vault,
+                ece_mint,
+                &offer_address,
+                ece_token_program,
+                system_program,
+                &rent,
+                &[TRADE_ECE_VAULT_SEED, offer_address.as_ref(), &[ece_vault_bump]],
+            )?;
+
+            transfer_checked(
+                ece_token_program,
+                maker_ece_account,
+                ece_mint,
+                ece_vault,
+                maker,
+                accounts,
+                offered_ece_amount,
+                &[],
+            )?;
+        }
+
+        offer.serialize(&mut &mut offer_account.data.borrow_mut()[..])?;
+
+        msg!("Trade offer {} opened by {}", offer_id, maker.key);
+        Ok(())
+    }
+
+    /// Fill a card trade offer, swapping both sides atomically
+    pub fn process_fill_trade_offer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let taker = next_account_info(account_info_iter)?;
+        let maker = next_account_info(account_info_iter)?;
+        let offer_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let card_token_program = next_account_info(account_info_iter)?;
+        let ece_token_program = next_account_info(account_info_iter)?;
+        let platform_fee_account = next_account_info(account_info_iter)?;
+        let clock_sysvar = next_account_info(account_info_iter)?;
+
+        if !taker.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+
+        if treasury_state.is_operation_paused(PAUSE_ESCROW) {
+            return Err(EceTokenError::EmergencyPauseActive.into());
+        }
+
+        let clock = Clock::from_account_info(clock_sysvar)?;
+        let mut offer = load_trade_offer(program_id, offer_account, treasury_state_account.key)?;
+
+        if !offer.is_fillable_by(taker.key, clock.unix_timestamp) {
+            return Err(EceTokenError::TradeOfferNotFillable.into());
+        }
+
+        if maker.key != &offer.maker
+            || card_token_program.key != &offer.card_token_program
+            || ece_token_program.key != &treasury_state.ece_token_program
+        {
+            return Err(EceTokenError::InvalidTradeOffer.into());
+        }
+
+        check_fee_ece_account(platform_fee_account, &treasury_state)?;
+
+        let fee_bps = treasury_state.marketplace_fee_bps;
+        let mut total_fees = 0u64;
+
+        if offer.offers_card() {
+            let card_vault = next_account_info(account_info_iter)?;
+            let taker_card_account = next_account_info(account_info_iter)?;
+            let card_mint = next_account_info(account_info_iter)?;
+
+            if card_mint.key != &offer.offered_card_mint {
+                return Err(EceTokenError::InvalidTokenMint.into());
+            }
+
+            check_token_account(taker_card_account, taker.key, &offer.offered_card_mint)?;
+            release_trade_vault(
+                offer_account,
+                &offer,
+                TRADE_CARD_VAULT_SEED,
+                card_vault,
+                card_mint,
+                maker,
+                card_token_program,
+                accounts,
+                &[(taker_card_account, 1)],
+            )?;
+        }
+
+        if offer.offered_ece_amount > 0 {
+            let ece_vault = next_account_info(account_info_iter)?;
+            let taker_ece_account = next_account_info(account_info_iter)?;
+            let ece_mint = next_account_info(account_info_iter)?;
+
+            if ece_mint.key != &offer.ece_mint {
+                return Err(EceTokenError::InvalidTokenMint.into());
+            }
+
+            check_token_account(taker_ece_account, taker.key, &offer.ece_mint)?;
+
+            // Pay out what actually reached the vault, net of any token-level transfer fee
+            let escrowed = unpack_token_account(ece_vault)?.amount;
+            let fee = TradeOffer::fee_for(escrowed, fee_bps);
+            release_trade_vault(
+                offer_account,
+                &offer,
+                TRADE_ECE_VAULT_SEED,
+                ece_vault,
+                ece_mint,
+                maker,
+                ece_token_program,
+                accounts,
+                &[(platform_fee_account, fee), (taker_ece_account, escrowed - fee)],
+            )?;
+            total_fees += fee;
+        }
+
+        if offer.requests_card() {
+            let taker_card_account = next_account_info(account_info_iter)?;
+            let maker_card_account = next_account_info(account_info_iter)?;
+            let card_mint = next_account_info(account_info_iter)?;
+
+            if card_mint.key != &offer.requested_card_mint {
+                return Err(EceTokenError::InvalidTokenMint.into());
+            }
+
+            check_token_account(maker_card_account, maker.key, &offer.requested_card_mint)?;
+
+            transfer_checked(
+                card_token_program,
+                taker_card_account,
+                card_mint,
+                maker_card_account,
+                taker,
+                accounts,
+                1,
+                &[],
+            )?;
+        }
+
+        if offer.requested_ece_amount > 0 {
+            let taker_ece_account = next_account_info(account_info_iter)?;
+            let maker_ece_account = next_account_info(account_info_iter)?;
+            let ece_mint = next_account_info(account_info_iter)?;
+
+            if ece_mint.key != &offer.ece_mint {
+                return Err(EceTokenError::InvalidTokenMint.into());
+            }
+
+            check_token_account(maker_ece_account, maker.key, &offer.ece_mint)?;
+
+            let fee = TradeOffer::fee_for(offer.requested_ece_amount, fee_bps);
+            for (destination, amount) in [
+                (platform_fee_account, fee),
+                (maker_ece_account, offer.requested_ece_amount - fee),
+            ] {
+                if amount == 0 {
+                    continue;
+                }
+
+                transfer_checked(
+                    ece_token_program,
+                    taker_ece_account,
+                    ece_mint,
+                    destination,
+                    taker,
+                    accounts,
+                    amount,
+                    &[],
+                )?;
+            }
+            total_fees += fee;
+        }
+
+        offer.status = TradeOfferStatus::Filled;
+        offer.serialize(&mut &mut offer_account.data.borrow_mut()[..])?;
+
+        msg!("Trade offer {} from {} filled by {}, {} ECE in fees",
+             offer.offer_id, offer.maker, taker.key, total_fees);
+        Ok(())
+    }
+
+    /// Cancel a card trade offer, returning the escrow to the maker
+    pub fn process_cancel_trade_offer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority = next_account_info(account_info_iter)?;
+        let maker = next_account_info(account_info_iter)?;
+        let offer_account = next_account_info(account_info_iter)?;
+        let treasury_state_account = next_account_info(account_info_iter)?;
+        let card_token_program = next_account_info(account_info_iter)?;
+        let ece_token_program = next_account_info(account_info_iter)?;
+
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if treasury_state_account.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut offer = load_trade_offer(program_id, offer_account, treasury_state_account.key)?;
+
+        // Resolvers can unwind offers (e.g. disputes or stale listings); escrow still only goes back to the maker
+        if authority.key != &offer.maker {
+            let treasury_state = load_treasury_state(program_id, treasury_state_account)?;
+            require_role(program_id, accounts, treasury_state_account, &treasury_state, authority, Role::Resolver)?;
+        }
+
+        if offer.status != TradeOfferStatus::Open {
+            return Err(EceTokenError::TradeOfferNotFillable.into());
+        }
+
+        if maker.key != &offer.maker || card_token_program.key != &offer.card_token_program {
+            return Err(EceTokenError::InvalidTradeOffer.into());
+        }
+
+        if offer.offers_card() {
+            let card_vault = next_account_info(account_info_iter)?;
+            let maker_card_account = next_account_info(account_info_iter)?;
+            let card_mint = next_account_info(account_info_iter)?;
+
+            if card_mint.key != &offer.offered_card_mint {
+                return Err(EceTokenError::InvalidTokenMint.into());
+            }
+
+            check_token_account(maker_card_account, maker.key, &offer.offered_card_mint)?;
+            release_trade_vault(
+                offer_account,
+                &offer,
+                TRADE_CARD_VAULT_SEED,
+                card_vault,
+                card_mint,
+                maker,
+                card_token_program,
+                accounts,
+                &[(maker_card_account, 1)],
+            )?;
+        }
+
+        if offer.offered_ece_amount > 0 {
+            let ece_vault = next_account_info(account_info_iter)?;
+            let maker_ece_account = next_account_info(account_info_iter)?;
+            let ece_mint = next_account_info(account_info_iter)?;
+
+            if ece_mint.key != &offer.ece_mint {
+                return Err(EceTokenError::InvalidTokenMint.into());
+            }
+
+            check_token_account(maker_ece_account, maker.key, &offer.ece_mint)?;
+
+            let escrowed = unpack_token_account(ece_vault)?.amount;
+            release_trade_vault(
+                offer_account,
+                &offer,
+                TRADE_ECE_VAULT_SEED,
+                ece_vault,
+                ece_mint,
+                maker,
+                ece_token_program,
+                accounts,
+                &[(maker_ece_account, escrowed)],
+            )?;
+        }
+
+        offer.status = TradeOfferStatus::Cancelled;
+        offer.serialize(&mut &mut offer_account.data.borrow_mut()[..])?;
+
+        msg!("Trade offer {} from {} cancelled", offer.offer_id, offer.maker);
+        Ok(())
+    }
+}
+
+/// Load the treasury state and verify it is owned by this program
+fn load_treasury_state(
+    program_id: &Pubkey,
+    treasury_state_account: &AccountInfo,
+) -> Result<TreasuryState, ProgramError> {
+    if treasury_state_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // The account is sized for the largest signer set, so read past any zero padding
+    let treasury_state = TreasuryState::deserialize(&mut &treasury_state_account.data.borrow()[..])?;
+
+    if !treasury_state.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    Ok(treasury_state)
+}
+
+/// Find the treasury's fee account among the instruction's accounts
+fn find_fee_account<'a, 'b>(
+    accounts: &'a [AccountInfo<'b>],
+    treasury_state: &TreasuryState,
+) -> Result<&'a AccountInfo<'b>, ProgramError> {
+    if treasury_state.fee_usdc_account == Pubkey::default() {
+        return Err(EceTokenError::InvalidTokenAccount.into());
+    }
+
+    accounts
+        .iter()
+        .find(|a| a.key == &treasury_state.fee_usdc_account)
+        .ok_or_else(|| EceTokenError::InvalidTokenAccount.into())
+}
+
+/// Check that an account is the treasury's configured ECE fee account
+fn check_fee_ece_account(fee_ece_account: &AccountInfo, treasury_state: &TreasuryState) -> ProgramResult {
+    if treasury_state.fee_ece_account == Pubkey::default()
+        || fee_ece_account.key != &treasury_state.fee_ece_account
+    {
+        return Err(EceTokenError::InvalidTokenAccount.into());
+    }
+    Ok(())
+}
+
+/// Check the USDC peg against the configured oracle, if any. The oracle account is
+/// found among the instruction's accounts, so it can be appended to mint and burn.
+fn check_usdc_peg(
+    accounts: &[AccountInfo],
+    treasury_state: &TreasuryState,
+    current_timestamp: i64,
+) -> ProgramResult {
+    if treasury_state.usdc_oracle == Pubkey::default() {
+        return Ok(());
+    }
+
+    let oracle_account = accounts
+        .iter()
+        .find(|a| a.key == &treasury_state.usdc_oracle)
+        .ok_or(EceTokenError::InvalidOracleAccount)?;
+    let price = OraclePrice::from_pyth_account_data(&oracle_account.data.borrow())?;
+
+    price.check_peg(
+        current_timestamp,
+        treasury_state.max_oracle_staleness,
+        treasury_state.max_peg_deviation_bps,
+    )?;
+    Ok(())
+}
+
+/// Check that `authority` signed and holds `role`. The treasury's built-in authorities
+/// keep their roles (signers are Admin, the emergency authority is Pauser, the compliance
+/// authority is ComplianceOfficer); any other holder needs a grant in the role registry,
+/// which is found among the instruction's accounts so it can be appended to any instruction.
+fn require_role(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    treasury_state_account: &AccountInfo,
+    treasury_state: &TreasuryState,
+    authority: &AccountInfo,
+    role: Role,
+) -> ProgramResult {
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let built_in = match role {
+        Role::Admin => treasury_state.signers.contains(authority.key),
+        Role::Pauser => authority.key == &treasury_state.emergency_authority,
+        Role::ComplianceOfficer => authority.key == &treasury_state.compliance_authority,
+        Role::Minter | Role::Auditor | Role::Resolver => false,
+    };
+    if built_in {
+        return Ok(());
+    }
+
+    let (role_registry_address, _) = Pubkey::find_program_address(
+        &[ROLE_REGISTRY_SEED, treasury_state_account.key.as_ref()],
+        program_id,
+    );
+    if let Some(role_registry_account) = accounts.iter().find(|a| a.key == &role_registry_address) {
+        let role_registry =
+            load_role_registry(program_id, role_registry_account, treasury_state_account.key)?;
+        if role_registry.has_role(authority.key, role) {
+            return Ok(());
+        }
+    }
+
+    Err(EceTokenError::MissingRole.into())
+}
+
+/// Check that enough distinct treasury signers signed the instruction to meet the threshold
+fn verify_multisig_approval(treasury_state: &TreasuryState, accounts: &[AccountInfo]) -> ProgramResult {
+    let mut approvals: Vec<&Pubkey> = Vec::with_capacity(treasury_state.signers.len());
+    for account in accounts {
+        if account.is_signer && treasury_state.signers.contains(account.key) && !approvals.contains(&account.key) {
+            approvals.push(account.key);
+        }
+    }
+
+    if approvals.len() < treasury_state.threshold as usize {
+        return Err(EceTokenError::InsufficientSigners.into());
+    }
+
+    Ok(())
+}
+
+/// Load the role registry and verify it belongs to the given treasury
+fn load_role_registry(
+    program_id: &Pubkey,
+    role_registry_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<RoleRegistry, ProgramError> {
+    if role_registry_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let role_registry = RoleRegistry::try_from_slice(&role_registry_account.data.borrow())?;
+
+    if !role_registry.is_initialized || &role_registry.treasury != treasury {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[ROLE_REGISTRY_SEED, treasury.as_ref(), &[role_registry.bump]],
+        program_id,
+    )?;
+    if role_registry_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(role_registry)
+}
+
+/// Check an outflow against the circuit breaker, tripping it when the outflow would exceed
+/// its limits: the treasury is paused with the reason recorded and saved here, and `true`
+/// tells the caller to return `Ok` without moving funds, since an error would roll the
+/// pause back with the rest of the instruction.
+fn check_circuit_breaker(
+    treasury_state_account: &AccountInfo,
+    treasury_state: &mut TreasuryState,
+    usdc_amount: u64,
+    ece_amount: u64,
+    current_timestamp: i64,
+) -> Result<bool, ProgramError> {
+    let reason = match treasury_state.circuit_breaker_check(usdc_amount, ece_amount, current_timestamp) {
+        Some(reason) => reason,
+        None => return Ok(false),
+    };
+
+    treasury_state.trip_circuit_breaker(reason, current_timestamp);
+    treasury_state.serialize(&mut &mut treasury_state_account.data.borrow_mut()[..])?;
+
+    msg!("CircuitBreakerTripped: reason={:?} attempted_outflow={} window_outflow={} reserves={} reserve_ratio={}",
+         reason,
+         usdc_amount,
+         treasury_state.sliding_window_outflow(current_timestamp),
+         treasury_state.usdc_reserves,
+         treasury_state.reserve_ratio());
+    Ok(true)
+}
+
+/// Load the denylist and verify it belongs to the given treasury
+fn load_denylist(
+    program_id: &Pubkey,
+    denylist_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<Denylist, ProgramError> {
+    if denylist_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let denylist = Denylist::try_from_slice(&denylist_account.data.borrow())?;
+
+    if !denylist.is_initialized || &denylist.treasury != treasury {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[DENYLIST_SEED, treasury.as_ref(), &[denylist.bump]],
+        program_id,
+    )?;
+    if denylist_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(denylist)
+}
+
+/// Extra accounts Token-2022 passes to the transfer hook: the treasury state
+/// (index 5) and the treasury's denylist PDA
+fn transfer_hook_extra_account_metas(treasury: &Pubkey) -> Result<Vec<ExtraAccountMeta>, ProgramError> {
+    Ok(vec![
+        ExtraAccountMeta::new_with_pubkey(treasury, false, false)?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: DENYLIST_SEED.to_vec() },
+                Seed::AccountKey { index: 5 },
+            ],
+            false,
+            false,
+        )?,
+    ])
+}
+
+/// Check whether a transfer authority is one of this program's PDAs that sign for ECE
+/// vaults. Each is loaded with its PDA check, since any wallet can create an account
+/// owned by this program but none can sign for a PDA.
+fn is_program_authority(program_id: &Pubkey, authority: &AccountInfo, treasury: &Pubkey) -> bool {
+    if authority.owner != program_id {
+        return false;
+    }
+
+    load_staking_pool(program_id, authority, treasury).is_ok()
+        || load_redemption_queue(program_id, authority, treasury).is_ok()
+        || load_referral_config(program_id, authority, treasury).is_ok()
+        || load_trade_offer(program_id, authority, treasury).is_ok()
+        || load_distributor(program_id, authority).is_ok_and(|distributor| &distributor.treasury == treasury)
+        || load_vesting_schedule(program_id, authority).is_ok_and(|vesting| &vesting.treasury == treasury)
+}
+
+/// Load the collateral registry and verify it belongs to the given treasury
+fn load_collateral_registry(
+    program_id: &Pubkey,
+    collateral_registry_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<CollateralRegistry, ProgramError> {
+    if collateral_registry_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let collateral_registry = CollateralRegistry::try_from_slice(&collateral_registry_account.data.borrow())?;
+
+    if !collateral_registry.is_initialized || &collateral_registry.treasury != treasury {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[COLLATERAL_REGISTRY_SEED, treasury.as_ref(), &[collateral_registry.bump]],
+        program_id,
+    )?;
+    if collateral_registry_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(collateral_registry)
+}
+
+/// Load a KYC record and verify it belongs to the given treasury and wallet
+fn load_kyc_record(
+    program_id: &Pubkey,
+    kyc_record_account: &AccountInfo,
+    treasury: &Pubkey,
+    wallet: &Pubkey,
+) -> Result<KycRecord, ProgramError> {
+    if kyc_record_account.owner != program_id {
+        return Err(EceTokenError::KycNotVerified.into());
+    }
+
+    let kyc_record = KycRecord::try_from_slice(&kyc_record_account.data.borrow())?;
+
+    if !kyc_record.is_initialized || &kyc_record.treasury != treasury || &kyc_record.wallet != wallet {
+        return Err(EceTokenError::KycNotVerified.into());
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[KYC_RECORD_SEED, treasury.as_ref(), wallet.as_ref(), &[kyc_record.bump]],
+        program_id,
+    )?;
+    if kyc_record_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(kyc_record)
+}
+
+/// Load a merkle distributor and verify its PDA
+fn load_distributor(
+    program_id: &Pubkey,
+    distributor_account: &AccountInfo,
+) -> Result<MerkleDistributor, ProgramError> {
+    if distributor_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let distributor = MerkleDistributor::try_from_slice(&distributor_account.data.borrow())?;
+
+    if !distributor.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[
+            DISTRIBUTOR_SEED,
+            distributor.treasury.as_ref(),
+            &distributor.distributor_id.to_le_bytes(),
+            &[distributor.bump],
+        ],
+        program_id,
+    )?;
+    if distributor_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(distributor)
+}
+
+/// Load a vesting schedule and verify its PDA
+fn load_vesting_schedule(
+    program_id: &Pubkey,
+    vesting_account: &AccountInfo,
+) -> Result<VestingSchedule, ProgramError> {
+    if vesting_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let vesting = VestingSchedule::try_from_slice(&vesting_account.data.borrow())?;
+
+    if !vesting.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[
+            VESTING_SEED,
+            vesting.treasury.as_ref(),
+            vesting.beneficiary.as_ref(),
+            &vesting.vesting_id.to_le_bytes(),
+            &[vesting.bump],
+        ],
+        program_id,
+    )?;
+    if vesting_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(vesting)
+}
+
+/// Transfer ECE out of a vesting vault, signed by the vesting schedule PDA
+#[allow(clippy::too_many_arguments)]
+fn vesting_transfer<'a>(
+    vesting_account: &AccountInfo<'a>,
+    vesting: &VestingSchedule,
+    vault: &AccountInfo<'a>,
+    ece_mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    additional_accounts: &[AccountInfo<'a>],
+    amount: u64,
+) -> ProgramResult {
+    if ece_mint.key != &vesting.ece_mint {
+        return Err(EceTokenError::InvalidTokenMint.into());
+    }
+
+    transfer_checked(
+        token_program,
+        vault,
+        ece_mint,
+        destination,
+        vesting_account,
+        additional_accounts,
+        amount,
+        &[&[
+            VESTING_SEED,
+            vesting.treasury.as_ref(),
+            vesting.beneficiary.as_ref(),
+            &vesting.vesting_id.to_le_bytes(),
+            &[vesting.bump],
+        ]],
+    )?;
+    Ok(())
+}
+
+/// Accrue the referrer's reward for a referred user's mint. The referral accounts are
+/// optional trailing accounts found by address; without the user's referral record
+/// nothing accrues. Rewards only accrue up to what the vault holds beyond unclaimed rewards.
+fn accrue_referral_reward(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    treasury: &Pubkey,
+    user: &Pubkey,
+    ece_amount: u64,
+) -> ProgramResult {
+    let (referral_address, _) =
+        Pubkey::find_program_address(&[REFERRAL_SEED, treasury.as_ref(), user.as_ref()], program_id);
+    let referral_account = match accounts.iter().find(|a| a.key == &referral_address) {
+        Some(referral_account) if referral_account.owner == program_id => referral_account,
+        _ => return Ok(()),
+    };
+
+    let referral = ReferralRecord::try_from_slice(&referral_account.data.borrow())?;
+    if !referral.is_initialized || &referral.treasury != treasury || &referral.user != user {
+        return Err(EceTokenError::InvalidReferral.into());
+    }
+
+    let (config_address, _) = Pubkey::find_program_address(&[REFERRAL_CONFIG_SEED, treasury.as_ref()], program_id);
+    let (referrer_address, _) =
+        Pubkey::find_program_address(&[REFERRER_SEED, treasury.as_ref(), referral.referrer.as_ref()], program_id);
+
+    let referral_config_account = accounts
+        .iter()
+        .find(|a| a.key == &config_address)
+        .ok_or(EceTokenError::InvalidReferral)?;
+    let referrer_account_info = accounts
+        .iter()
+        .find(|a| a.key == &referrer_address)
+        .ok_or(EceTokenError::InvalidReferral)?;
+
+    let mut referral_config = load_referral_config(program_id, referral_config_account, treasury)?;
+    let mut referrer_account =
+        load_referrer_account(program_id, referrer_account_info, treasury, &referral.referrer)?;
+
+    let vault = accounts
+        .iter()
+        .find(|a| a.key == &referral_config.vault)
+        .ok_or(EceTokenError::InvalidReferral)?;
+    let unallocated = unpack_token_account(vault)?.amount.saturating_sub(referral_config.outstanding());
+
+    let reward = referral_config.reward_for(ece_amount).min(unallocated);
+    let accrued = referrer_account.accrue(reward, referral_config.max_reward_per_referrer);
+    if accrued == 0 {
+        return Ok(());
+    }
+
+    referral_config.total_accrued = referral_config
+        .total_accrued
+        .checked_add(accrued)
+        .ok_or(EceTokenError::NumericalOverflow)?;
+
+    referral_config.serialize(&mut &mut referral_config_account.data.borrow_mut()[..])?;
+    referrer_account.serialize(&mut &mut referrer_account_info.data.borrow_mut()[..])?;
+
+    msg!("Accrued {} ECE referral reward to {}", accrued, referral.referrer);
+    Ok(())
+}
+
+/// Load the referral config and verify it belongs to the given treasury
+fn load_referral_config(
+    program_id: &Pubkey,
+    referral_config_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<ReferralConfig, ProgramError> {
+    if referral_config_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let referral_config = ReferralConfig::try_from_slice(&referral_config_account.data.borrow())?;
+
+    if !referral_config.is_initialized || &referral_config.treasury != treasury {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[REFERRAL_CONFIG_SEED, treasury.as_ref(), &[referral_config.bump]],
+        program_id,
+    )?;
+    if referral_config_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(referral_config)
+}
+
+/// Load a referrer account and verify it belongs to the given treasury and referrer
+fn load_referrer_account(
+    program_id: &Pubkey,
+    referrer_account_info: &AccountInfo,
+    treasury: &Pubkey,
+    referrer: &Pubkey,
+) -> Result<ReferrerAccount, ProgramError> {
+    if referrer_account_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let referrer_account = ReferrerAccount::try_from_slice(&referrer_account_info.data.borrow())?;
+
+    if !referrer_account.is_initialized
+        || &referrer_account.treasury != treasury
+        || &referrer_account.referrer != referrer
+    {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[REFERRER_SEED, treasury.as_ref(), referrer.as_ref(), &[referrer_account.bump]],
+        program_id,
+    )?;
+    if referrer_account_info.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(referrer_account)
+}
+
+/// Load a trade offer and verify it belongs to the given treasury
+fn load_trade_offer(
+    program_id: &Pubkey,
+    offer_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<TradeOffer, ProgramError> {
+    if offer_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let offer = TradeOffer::try_from_slice(&offer_account.data.borrow())?;
+
+    if !offer.is_initialized || &offer.treasury != treasury {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[
+            TRADE_OFFER_SEED,
+            offer.treasury.as_ref(),
+            offer.maker.as_ref(),
+            &offer.offer_id.to_le_bytes(),
+            &[offer.bump],
+        ],
+        program_id,
+    )?;
+    if offer_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(offer)
+}
+
+/// Empty a trade offer's vault into the given accounts and close it, returning its rent
+/// to the maker. The vault is checked against its PDA, so a taker can't substitute one.
+#[allow(clippy::too_many_arguments)]
+fn release_trade_vault<'a>(
+    offer_account: &AccountInfo<'a>,
+    offer: &TradeOffer,
+    vault_seed: &[u8],
+    vault: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    maker: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    additional_accounts: &[AccountInfo<'a>],
+    payments: &[(&AccountInfo<'a>, u64)],
+) -> ProgramResult {
+    let (vault_address, _) =
+        Pubkey::find_program_address(&[vault_seed, offer_account.key.as_ref()], offer_account.owner);
+    if vault.key != &vault_address {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    let id_bytes = offer.offer_id.to_le_bytes();
+    let bump = [offer.bump];
+    let offer_seeds: &[&[u8]] = &[TRADE_OFFER_SEED, offer.treasury.as_ref(), offer.maker.as_ref(), &id_bytes, &bump];
+
+    for (destination, amount) in payments {
+        if *amount == 0 {
+            continue;
+        }
+
+        transfer_checked(
+            token_program,
+            vault,
+            mint,
+            destination,
+            offer_account,
+            additional_accounts,
+            *amount,
+            &[offer_seeds],
+        )?;
+    }
+
+    invoke_signed(
+        &token_instruction::close_account(token_program.key, vault.key, maker.key, offer_account.key, &[])?,
+        &[
+            vault.clone(),
+            maker.clone(),
+            offer_account.clone(),
+            token_program.clone(),
+        ],
+        &[offer_seeds],
+    )
+}
+
+/// Check that a token account belongs to `owner` and holds `mint`
+fn check_token_account(token_account: &AccountInfo, owner: &Pubkey, mint: &Pubkey) -> ProgramResult {
+    let account = unpack_token_account(token_account)?;
+    if &account.owner != owner || &account.mint != mint {
+        return Err(EceTokenError::InvalidTokenAccount.into());
+    }
+    Ok(())
+}
+
+/// Load a timelocked withdrawal and verify it belongs to the given treasury
+fn load_pending_withdrawal(
+    program_id: &Pubkey,
+    pending_withdrawal_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<PendingWithdrawal, ProgramError> {
+    if pending_withdrawal_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let pending_withdrawal = PendingWithdrawal::try_from_slice(&pending_withdrawal_account.data.borrow())?;
+
+    if !pending_withdrawal.is_initialized || &pending_withdrawal.treasury != treasury {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[
+            PENDING_WITHDRAWAL_SEED,
+            treasury.as_ref(),
+            &pending_withdrawal.withdrawal_id.to_le_bytes(),
+            &[pending_withdrawal.bump],
+        ],
+        program_id,
+    )?;
+    if pending_withdrawal_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(pending_withdrawal)
+}
+
+/// Load the redemption queue and verify it belongs to the given treasury
+fn load_redemption_queue(
+    program_id: &Pubkey,
+    queue_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<RedemptionQueue, ProgramError> {
+    if queue_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let queue = RedemptionQueue::try_from_slice(&queue_account.data.borrow())?;
+
+    if !queue.is_initialized || &queue.treasury != treasury {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[REDEMPTION_QUEUE_SEED, treasury.as_ref(), &[queue.bump]],
+        program_id,
+    )?;
+    if queue_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(queue)
+}
+
+/// Load a redemption ticket and verify it belongs to the given queue
+fn load_redemption_ticket(
+    program_id: &Pubkey,
+    ticket_account: &AccountInfo,
+    queue: &Pubkey,
+) -> Result<RedemptionTicket, ProgramError> {
+    if ticket_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let ticket = RedemptionTicket::try_from_slice(&ticket_account.data.borrow())?;
+
+    if !ticket.is_initialized || &ticket.queue != queue {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[
+            REDEMPTION_TICKET_SEED,
+            queue.as_ref(),
+            &ticket.ticket_id.to_le_bytes(),
+            &[ticket.bump],
+        ],
+        program_id,
+    )?;
+    if ticket_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(ticket)
+}
+
+/// Load a subscription plan and verify it lives at its canonical PDA
+fn load_subscription_plan(
+    program_id: &Pubkey,
+    plan_account: &AccountInfo,
+) -> Result<SubscriptionPlan, ProgramError> {
+    if plan_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let plan = SubscriptionPlan::try_from_slice(&plan_account.data.borrow())?;
+
+    if !plan.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[SUBSCRIPTION_PLAN_SEED, &plan.plan_id.to_le_bytes(), &[plan.bump]],
+        program_id,
+    )?;
+    if plan_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(plan)
+}
+
+/// Load a subscription and verify it lives at its canonical PDA
+fn load_subscription(
+    program_id: &Pubkey,
+    subscription_account: &AccountInfo,
+) -> Result<Subscription, ProgramError> {
+    if subscription_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let subscription = Subscription::try_from_slice(&subscription_account.data.borrow())?;
+
+    if !subscription.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[
+            SUBSCRIPTION_SEED,
+            subscription.plan.as_ref(),
+            subscription.subscriber.as_ref(),
+            &[subscription.bump],
+        ],
+        program_id,
+    )?;
+    if subscription_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(subscription)
+}
+
+/// Load the card collection and verify it belongs to the given treasury
+fn load_card_collection(
+    program_id: &Pubkey,
+    collection_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<CardCollection, ProgramError> {
+    if collection_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let collection = CardCollection::try_from_slice(&collection_account.data.borrow())?;
+
+    if !collection.is_initialized || &collection.treasury != treasury {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[CARD_COLLECTION_SEED, treasury.as_ref(), &[collection.bump]],
+        program_id,
+    )?;
+    if collection_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(collection)
+}
+
+/// Load a card record and verify it belongs to the given treasury's collection
+fn load_card_record(
+    program_id: &Pubkey,
+    card_record_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<CardRecord, ProgramError> {
+    if card_record_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let card_record = CardRecord::try_from_slice(&card_record_account.data.borrow())?;
+
+    if !card_record.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let (collection_address, _) = Pubkey::find_program_address(
+        &[CARD_COLLECTION_SEED, treasury.as_ref()],
+        program_id,
+    );
+    let (expected, _) = Pubkey::find_program_address(
+        &[CARD_RECORD_SEED, card_record.mint.as_ref()],
+        program_id,
+    );
+    if card_record.collection != collection_address || card_record_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(card_record)
+}
+
+/// Load a royalty registry and verify it lives at its canonical PDA
+fn load_royalty_registry(
+    program_id: &Pubkey,
+    royalty_registry_account: &AccountInfo,
+) -> Result<RoyaltyRegistry, ProgramError> {
+    if royalty_registry_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let royalty_registry = RoyaltyRegistry::try_from_slice(&royalty_registry_account.data.borrow())?;
+
+    if !royalty_registry.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[ROYALTY_SEED, royalty_registry.collection.as_ref(), &[royalty_registry.bump]],
+        program_id,
+    )?;
+    if royalty_registry_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(royalty_registry)
+}
+
+/// Load the fee split table and verify it belongs to the given treasury
+fn load_fee_split(
+    program_id: &Pubkey,
+    fee_split_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<FeeSplitConfig, ProgramError> {
+    if fee_split_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let fee_split = FeeSplitConfig::try_from_slice(&fee_split_account.data.borrow())?;
+
+    if !fee_split.is_initialized || &fee_split.treasury != treasury {
+        return Err(EceTokenError::InvalidFeeSplit.into());
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[FEE_SPLIT_SEED, treasury.as_ref(), &[fee_split.bump]],
+        program_id,
+    )?;
+    if fee_split_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(fee_split)
+}
+
+/// Load the governance config and verify it lives at its canonical PDA
+fn load_governance(
+    program_id: &Pubkey,
+    governance_account: &AccountInfo,
+) -> Result<GovernanceConfig, ProgramError> {
+    if governance_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let governance = GovernanceConfig::try_from_slice(&governance_account.data.borrow())?;
+
+    if !governance.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[GOVERNANCE_SEED, governance.treasury.as_ref(), &[governance.bump]],
+        program_id,
+    )?;
+    if governance_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(governance)
+}
+
+/// Load a proposal and verify it belongs to the given governance config
+fn load_proposal(
+    program_id: &Pubkey,
+    proposal_account: &AccountInfo,
+    governance: &Pubkey,
+) -> Result<Proposal, ProgramError> {
+    if proposal_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let proposal = Proposal::try_from_slice(&proposal_account.data.borrow())?;
+
+    if !proposal.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[
+            PROPOSAL_SEED,
+            governance.as_ref(),
+            &proposal.proposal_id.to_le_bytes(),
+            &[proposal.bump],
+        ],
+        program_id,
+    )?;
+    if proposal_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(proposal)
+}
+
+/// Load a staking pool and verify it belongs to the given treasury
+fn load_staking_pool(
+    program_id: &Pubkey,
+    staking_pool_account: &AccountInfo,
+    treasury: &Pubkey,
+) -> Result<StakingPool, ProgramError> {
+    if staking_pool_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let staking_pool = StakingPool::try_from_slice(&staking_pool_account.data.borrow())?;
+
+    if !staking_pool.is_initialized || &staking_pool.treasury != treasury {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[STAKING_POOL_SEED, treasury.as_ref(), &[staking_pool.bump]],
+        program_id,
+    )?;
+    if staking_pool_account.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(staking_pool)
+}
+
+/// Load a stake account and verify it belongs to the given pool and owner
+fn load_stake_account(
+    program_id: &Pubkey,
+    stake_account_info: &AccountInfo,
+    pool: &Pubkey,
+    owner: &Pubkey,
+) -> Result<StakeAccount, ProgramError> {
+    if stake_account_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let stake_account = StakeAccount::try_from_slice(&stake_account_info.data.borrow())?;
+
+    if !stake_account.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if &stake_account.pool != pool || &stake_account.owner != owner {
+        return Err(EceTokenError::UnauthorizedSigner.into());
+    }
+
+    let expected = Pubkey::create_program_address(
+        &[STAKE_ACCOUNT_SEED, pool.as_ref(), owner.as_ref(), &[stake_account.bump]],
+        program_id,
+    )?;
+    if stake_account_info.key != &expected {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+
+    Ok(stake_account)
+}
+
+/// Create a program-owned account at a PDA
+fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &Rent,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            new_account.key,
+            rent.minimum_balance(space),
+            space as u64,
+            owner,
+        ),
+        &[payer.clone(), new_account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )
+}
+
+/// Create and initialize a token account at a PDA
+#[allow(clippy::too_many_arguments)]
+fn create_pda_token_account<'a>(
+    payer: &AccountInfo<'a>,
+    token_account: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    owner: &Pubkey,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &Rent,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    // Token-2022 accounts need room for any extensions their mint requires
+    let space = if token_program.key == &spl_token_2022::id() {
+        let mint_data = mint.data.borrow();
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        let required_extensions =
+            ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?);
+        ExtensionType::try_calculate_account_len::<TokenAccount>(&required_extensions)?
+    } else {
+        TokenAccount::LEN
+    };
+
+    create_pda_account(
+        payer,
+        token_account,
+        system_program,
+        rent,
+        space,
+        token_program.key,
+        signer_seeds,
+    )?;
+
+    invoke(
+        &token_instruction::initialize_account3(
+            token_program.key,
+            token_account.key,
+            mint.key,
+            owner,
+        )?,
+        &[token_account.clone(), mint.clone(), token_program.clone()],
+    )
+}
+
+/// Build a plain token transfer for the classic USDC reserve, whose mint has no transfer
+/// extensions. ECE and card transfers go through `transfer_checked`.
+#[allow(deprecated)]
+fn transfer_instruction(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    token_instruction::transfer(token_program_id, source, destination, authority, signers, amount)
+}
+
+/// Move tokens with `transfer_checked`, resolving any transfer-hook extra accounts from
+/// `additional_accounts`. Returns what the destination actually received, which is less
+/// than `amount` when the mint withholds a transfer fee.
+#[allow(clippy::too_many_arguments)]
+fn transfer_checked<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    additional_accounts: &[AccountInfo<'a>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64, ProgramError> {
+    let decimals = unpack_mint(mint)?.decimals;
+    let balance_before = unpack_token_account(destination)?.amount;
+
+    onchain::invoke_transfer_checked(
+        token_program.key,
+        source.clone(),
+        mint.clone(),
+        destination.clone(),
+        authority.clone(),
+        additional_accounts,
+        amount,
+        decimals,
+        signer_seeds,
+    )?;
+
+    let balance_after = unpack_token_account(destination)?.amount;
+    balance_after
+        .checked_sub(balance_before)
+        .ok_or_else(|| EceTokenError::NumericalOverflow.into())
+}
+
+/// Amount to send so that `net_amount` arrives after the mint's transfer fee for `epoch`
+fn gross_up_transfer_fee(mint: &AccountInfo, net_amount: u64, epoch: u64) -> Result<u64, ProgramError> {
+    let data = mint.data.borrow();
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+    let fee = match mint.get_extension::<transfer_fee::TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_inverse_epoch_fee(epoch, net_amount)
+            .ok_or(EceTokenError::NumericalOverflow)?,
+        Err(_) => 0,
+    };
+    net_amount
+        .checked_add(fee)
+        .ok_or_else(|| EceTokenError::NumericalOverflow.into())
+}
+
+/// Unpack a classic or Token-2022 token account, ignoring any extensions
+fn unpack_token_account(token_account: &AccountInfo) -> Result<TokenAccount, ProgramError> {
+    let data = token_account.data.borrow();
+    Ok(StateWithExtensions::<TokenAccount>::unpack(&data)?.base)
+}
+
+/// Check that an account is the treasury's ECE mint and still has the recorded decimals
+fn check_ece_mint(ece_mint: &AccountInfo, treasury_state: &TreasuryState) -> ProgramResult {
+    if ece_mint.key != &treasury_state.ece_mint {
+        return Err(EceTokenError::InvalidTokenMint.into());
+    }
+
+    if unpack_mint(ece_mint)?.decimals != treasury_state.ece_decimals {
+        return Err(EceTokenError::DecimalsMismatch.into());
+    }
+    Ok(())
+}
+
+/// Unpack a classic or Token-2022 mint, ignoring any extensions
+fn unpack_mint(mint: &AccountInfo) -> Result<Mint, ProgramError> {
+    let data = mint.data.borrow();
+    Ok(StateWithExtensions::<Mint>::unpack(&data)?.base)
+}
+
+/// Check that an account is the classic SPL Token or the Token-2022 program
+fn check_token_program(token_program: &AccountInfo) -> ProgramResult {
+    if token_program.key != &spl_token::id() && token_program.key != &spl_token_2022::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Check that an account is the token program owning the treasury's ECE mint
+fn check_ece_token_program(token_program: &AccountInfo, treasury_state: &TreasuryState) -> ProgramResult {
+    check_token_program(token_program)?;
+
+    if token_program.key != &treasury_state.ece_token_program {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Check that an account is the treasury's USDC reserve and the token program owns it
+fn check_usdc_reserve(
+    treasury_usdc_account: &AccountInfo,
+    token_program: &AccountInfo,
+    treasury_state: &TreasuryState,
+) -> ProgramResult {
+    check_token_program(token_program)?;
+
+    if treasury_usdc_account.key != &treasury_state.usdc_reserve {
+        return Err(EceTokenError::InvalidTokenAccount.into());
+    }
+
+    if treasury_usdc_account.owner != token_program.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Check that an account is the treasury authority PDA that owns the USDC reserve and
+/// return its bump
+fn check_treasury_authority(program_id: &Pubkey, treasury_authority: &AccountInfo) -> Result<u8, ProgramError> {
+    let (treasury_authority_address, bump) = Pubkey::find_program_address(&[TREASURY_AUTHORITY_SEED], program_id);
+    if treasury_authority.key != &treasury_authority_address {
+        return Err(EceTokenError::InvalidProgramAddress.into());
+    }
+    Ok(bump)
+}
+
+/// Pay USDC out of the reserve, signed by the treasury authority PDA that owns it
+fn transfer_from_reserve<'a>(
+    token_program: &AccountInfo<'a>,
+    treasury_usdc_account: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    treasury_authority: &AccountInfo<'a>,
+    authority_bump: u8,
+    amount: u64,
+) -> ProgramResult {
+    invoke_signed(
+        &transfer_instruction(
+            token_program.key,
+            treasury_usdc_account.key,
+            destination.key,
+            treasury_authority.key,
+            &[],
+            amount,
+        )?,
+        &[
+            treasury_usdc_account.clone(),
+            destination.clone(),
+            treasury_authority.clone(),
+            token_program.clone(),
+        ],
+        &[&[TREASURY_AUTHORITY_SEED, &[authority_bump]]],
+    )
+}
+
+/// Resolve the token program that owns the ECE mint. It is the instruction's token
+/// program when they match, otherwise it must be appended to the instruction's accounts.
+fn find_ece_token_program<'a, 'b>(
+    accounts: &'a [AccountInfo<'b>],
+    treasury_state: &TreasuryState,
+    token_program: &'a AccountInfo<'b>,
+) -> Result<&'a AccountInfo<'b>, ProgramError> {
+    let ece_token_program = if token_program.key == &treasury_state.ece_token_program {
+        token_program
+    } else {
+        accounts
+            .iter()
+            .find(|a| a.key == &treasury_state.ece_token_program)
+            .ok_or(ProgramError::IncorrectProgramId)?
+    };
+
+    check_token_program(ece_token_program)?;
+    Ok(ece_token_program)
 }
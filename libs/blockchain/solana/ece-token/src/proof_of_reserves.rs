@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{hash::hashv, pubkey::Pubkey};
+use spl_token_2022::{extension::StateWithExtensions, state::Account as TokenAccount};
+
+use crate::error::EceTokenError;
+
+/// Domain separators so a leaf can never be passed off as an inner node
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Node of the Merkle sum tree: a hash committing to the balances below it and their sum
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SumNode {
+    pub hash: [u8; 32],
+    pub sum: u64,
+}
+
+impl SumNode {
+    /// Leaf for a holder's total ECE balance
+    pub fn leaf(owner: &Pubkey, balance: u64) -> Self {
+        Self {
+            hash: hashv(&[LEAF_PREFIX, owner.as_ref(), &balance.to_le_bytes()]).to_bytes(),
+            sum: balance,
+        }
+    }
+
+    /// Parent of two nodes, or `None` if their sums overflow
+    pub fn parent(left: &SumNode, right: &SumNode) -> Option<Self> {
+        let sum = left.sum.checked_add(right.sum)?;
+        Some(Self {
+            hash: hashv(&[
+                NODE_PREFIX,
+                &left.hash,
+                &left.sum.to_le_bytes(),
+                &right.hash,
+                &right.sum.to_le_bytes(),
+            ])
+            .to_bytes(),
+            sum,
+        })
+    }
+}
+
+/// One step of an inclusion proof: the sibling of the node being proven at that level
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofStep {
+    pub sibling: SumNode,
+    pub sibling_is_left: bool,
+}
+
+/// Check that `owner` holding `balance` is counted in a published root and total
+pub fn verify_inclusion(
+    root: &[u8; 32],
+    total: u64,
+    owner: &Pubkey,
+    balance: u64,
+    proof: &[ProofStep],
+) -> bool {
+    let mut node = SumNode::leaf(owner, balance);
+
+    for step in proof {
+        let parent = if step.sibling_is_left {
+            SumNode::parent(&step.sibling, &node)
+        } else {
+            SumNode::parent(&node, &step.sibling)
+        };
+
+        node = match parent {
+            Some(parent) => parent,
+            None => return false,
+        };
+    }
+
+    &node.hash == root && node.sum == total
+}
+
+/// Merkle sum tree over every ECE holder's balance at a slot, built off-chain
+/// from the program accounts of the ECE mint
+#[derive(Debug, Clone)]
+pub struct HolderSnapshot {
+    /// Slot the token accounts were read at
+    pub slot: u64,
+
+    /// Holders and their balances, sorted by owner
+    pub holders: Vec<(Pubkey, u64)>,
+
+    /// Tree levels from the leaves up to the root
+    levels: Vec<Vec<SumNode>>,
+}
+
+impl HolderSnapshot {
+    /// Build the tree from per-owner balances; zero balances are left out and
+    /// repeated owners are summed
+    pub fn new(slot: u64, balances: &[(Pubkey, u64)]) -> Result<Self, EceTokenError> {
+        let mut by_owner: BTreeMap<Pubkey, u64> = BTreeMap::new();
+        for (owner, amount) in balances.iter().filter(|(_, amount)| *amount > 0) {
+            let balance = by_owner.entry(*owner).or_insert(0);
+            *balance = balance.checked_add(*amount).ok_or(EceTokenError::NumericalOverflow)?;
+        }
+
+        let holders: Vec<(Pubkey, u64)> = by_owner.into_iter().collect();
+        let mut levels = vec![holders
+            .iter()
+            .map(|(owner, balance)| SumNode::leaf(owner, *balance))
+            .collect::<Vec<_>>()];
+
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let level = levels.last().unwrap();
+            let mut parents = Vec::with_capacity(level.len().div_ceil(2));
+
+            for pair in level.chunks(2) {
+                // An unpaired node moves up unchanged
+                let parent = match pair {
+                    [left, right] => SumNode::parent(left, right).ok_or(EceTokenError::NumericalOverflow)?,
+                    _ => pair[0],
+                };
+                parents.push(parent);
+            }
+
+            levels.push(parents);
+        }
+
+        Ok(Self { slot, holders, levels })
+    }
+
+    /// Build the tree from raw token account data (`getProgramAccounts` results),
+    /// skipping accounts of other mints. Works for classic and Token-2022 accounts.
+    pub fn from_token_accounts(
+        slot: u64,
+        ece_mint: &Pubkey,
+        accounts: &[(Pubkey, Vec<u8>)],
+    ) -> Result<Self, EceTokenError> {
+        let mut balances = Vec::with_capacity(accounts.len());
+
+        for (_, data) in accounts {
+            let account = StateWithExtensions::<TokenAccount>::unpack(data)
+                .map_err(|_| EceTokenError::InvalidTokenAccount)?
+                .base;
+
+            if &account.mint == ece_mint {
+                balances.push((account.owner, account.amount));
+            }
+        }
+
+        Self::new(slot, &balances)
+    }
+
+    /// Merkle root to publish on-chain
+    pub fn root(&self) -> [u8; 32] {
+        self.top().map_or([0; 32], |node| node.hash)
+    }
+
+    /// Sum of all holder balances
+    pub fn total(&self) -> u64 {
+        self.top().map_or(0, |node| node.sum)
+    }
+
+    /// Number of holders with a non-zero balance
+    pub fn holder_count(&self) -> u32 {
+        self.holders.len() as u32
+    }
+
+    /// A holder's counted balance and the proof of its inclusion
+    pub fn proof(&self, owner: &Pubkey) -> Option<(u64, Vec<ProofStep>)> {
+        let mut index = self.holders.binary_search_by(|(holder, _)| holder.cmp(owner)).ok()?;
+        let balance = self.holders[index].1;
+        let mut proof = Vec::new();
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling_index = index ^ 1;
+            if sibling_index < level.len() {
+                proof.push(ProofStep {
+                    sibling: level[sibling_index],
+                    sibling_is_left: sibling_index < index,
+                });
+            }
+            index /= 2;
+        }
+
+        Some((balance, proof))
+    }
+
+    fn top(&self) -> Option<&SumNode> {
+        self.levels.last().and_then(|level| level.first())
+    }
+}
@@ -69,12 +69,123 @@ pub struct TreasuryState {
     
     /// Last volume reset timestamp
     pub last_volume_reset: i64,
+    
+    /// Platform fee on secondary card sales (basis points)
+    pub marketplace_fee_bps: u16,
+    
+    /// ECE locked in the redemption queue awaiting USDC
+    pub queued_redemptions: u64,
+    
+    /// Withdrawals above this amount are timelocked
+    pub withdrawal_timelock_threshold: u64,
+    
+    /// Delay before a timelocked withdrawal can execute (in seconds)
+    pub withdrawal_timelock_delay: i64,
+    
+    /// Number of timelocked withdrawals created
+    pub withdrawal_count: u64,
+    
+    /// Length of the circuit breaker outflow window (in seconds)
+    pub outflow_window: i64,
+    
+    /// Maximum outflow within the window as a share of reserves (basis points, 0 disables)
+    pub max_window_outflow_bps: u16,
+    
+    /// Hard reserve ratio floor that trips the breaker (basis points)
+    pub reserve_floor_bps: u16,
+    
+    /// USDC outflow recorded in the current window
+    pub window_outflow: u64,
+    
+    /// USDC outflow recorded in the previous window
+    pub previous_window_outflow: u64,
+    
+    /// Start timestamp of the current outflow window
+    pub outflow_window_start: i64,
+    
+    /// Why the circuit breaker last paused the treasury
+    pub breaker_trip_reason: CircuitBreakerReason,
+    
+    /// Timestamp of the last circuit breaker trip
+    pub breaker_tripped_at: i64,
+    
+    /// Per-operation pause bitmask (see `PAUSE_*`), applied on top of `is_paused`
+    pub pause_flags: u16,
+    
+    /// Proposed emergency authority awaiting acceptance (default key when none)
+    pub pending_emergency_authority: Pubkey,
+    
+    /// Proposed compliance authority awaiting acceptance (default key when none)
+    pub pending_compliance_authority: Pubkey,
+    
+    /// Lowest threshold a signer-set update may set
+    pub min_signer_threshold: u8,
+    
+    /// Incremented whenever the signer set changes, invalidating in-flight withdrawals
+    pub signer_epoch: u64,
+    
+    /// Pyth-format USDC/USD price account (default key when no oracle is configured)
+    pub usdc_oracle: Pubkey,
+    
+    /// Maximum USDC deviation from $1.00 before mint and burn are rejected (basis points)
+    pub max_peg_deviation_bps: u16,
+    
+    /// Maximum age of the oracle price (in seconds)
+    pub max_oracle_staleness: i64,
+    
+    /// Fee on mints, taken from the deposited USDC (basis points)
+    pub mint_fee_bps: u16,
+    
+    /// Fee on redemptions, withheld from the released USDC (basis points)
+    pub redeem_fee_bps: u16,
+    
+    /// USDC account collecting mint and redeem fees
+    pub fee_usdc_account: Pubkey,
+    
+    /// Total mint and redeem fees collected (in USDC)
+    pub total_fees_collected: u64,
+    
+    /// Token program owning the ECE mint (classic SPL Token or Token-2022)
+    pub ece_token_program: Pubkey,
+    
+    /// Haircut-adjusted value of non-USDC collateral, in ECE base units
+    pub collateral_reserves: u64,
+    
+    /// ECE mint decimals
+    pub ece_decimals: u8,
+    
+    /// USDC mint decimals
+    pub usdc_decimals: u8,
+    
+    /// Maximum USDC per self-service mint
+    pub user_mint_limit: u64,
+    
+    /// Maximum USDC each KYC-verified wallet may mint per day
+    pub user_daily_mint_limit: u64,
+    
+    /// Maximum ECE per admin mint from surplus reserves (in USDC terms)
+    pub reserve_mint_limit: u64,
+    
+    /// ECE account collecting marketplace, card mint and subscription fees
+    pub fee_ece_account: Pubkey,
+    
+    /// Untimelocked withdrawals in the current outflow window, counted against the threshold
+    pub window_withdrawals: u64,
+    
+    /// Staged withdrawal timelock threshold awaiting the current delay
+    pub pending_timelock_threshold: u64,
+    
+    /// Staged withdrawal timelock delay awaiting the current delay
+    pub pending_timelock_delay: i64,
+    
+    /// When the staged timelock change can be applied (0 when none is staged)
+    pub pending_timelock_at: i64,
 }
 
 impl TreasuryState {
-    pub const LEN: usize = 8 + // discriminator
-        1 + // is_initialized
-        4 + (32 * 5) + // signers (max 5 signers)
+    /// Space for the largest signer set; smaller sets leave zero padding at the end
+    pub const LEN: usize = 1 + // is_initialized
+        4 + (32 * MAX_TREASURY_SIGNERS) + // signers
         1 + // threshold
         32 + // ece_mint
         32 + // usdc_mint
@@ -94,23 +205,284 @@ impl TreasuryState {
         8 + // max_transaction_amount
         8 + // daily_volume_limit
         8 + // current_daily_volume
-        8; // last_volume_reset
+        8 + // last_volume_reset
+        2 + // marketplace_fee_bps
+        8 + // queued_redemptions
+        8 + // withdrawal_timelock_threshold
+        8 + // withdrawal_timelock_delay
+        8 + // withdrawal_count
+        8 + // outflow_window
+        2 + // max_window_outflow_bps
+        2 + // reserve_floor_bps
+        8 + // window_outflow
+        8 + // previous_window_outflow
+        8 + // outflow_window_start
+        1 + // breaker_trip_reason
+        8 + // breaker_tripped_at
+        2 + // pause_flags
+        32 + // pending_emergency_authority
+        32 + // pending_compliance_authority
+        1 + // min_signer_threshold
+        8 + // signer_epoch
+        32 + // usdc_oracle
+        2 + // max_peg_deviation_bps
+        8 + // max_oracle_staleness
+        2 + // mint_fee_bps
+        2 + // redeem_fee_bps
+        32 + // fee_usdc_account
+        8 + // total_fees_collected
+        32 + // ece_token_program
+        8 + // collateral_reserves
+        1 + // ece_decimals
+        1 + // usdc_decimals
+        8 + // user_mint_limit
+        8 + // user_daily_mint_limit
+        8 + // reserve_mint_limit
+        32 + // fee_ece_account
+        8 + // window_withdrawals
+        8 + // pending_timelock_threshold
+        8 + // pending_timelock_delay
+        8; // pending_timelock_at
+    
+    /// Convert ECE base units to USDC base units, rounding down
+    pub fn ece_to_usdc(&self, ece_amount: u64) -> Option<u64> {
+        convert_decimals(ece_amount, self.ece_decimals, self.usdc_decimals, false)
+    }
+    
+    /// Convert ECE base units to USDC base units, rounding up
+    pub fn ece_to_usdc_ceil(&self, ece_amount: u64) -> Option<u64> {
+        convert_decimals(ece_amount, self.ece_decimals, self.usdc_decimals, true)
+    }
+    
+    /// Convert USDC base units to ECE base units, rounding down
+    pub fn usdc_to_ece(&self, usdc_amount: u64) -> Option<u64> {
+        convert_decimals(usdc_amount, self.usdc_decimals, self.ece_decimals, false)
+    }
+    
+    /// Convert USDC base units to ECE base units, rounding up
+    pub fn usdc_to_ece_ceil(&self, usdc_amount: u64) -> Option<u64> {
+        convert_decimals(usdc_amount, self.usdc_decimals, self.ece_decimals, true)
+    }
+    
+    /// USDC reserves not already earmarked for queued redemptions
+    pub fn available_reserves(&self) -> u64 {
+        let earmarked = self.ece_to_usdc_ceil(self.queued_redemptions).unwrap_or(u64::MAX);
+        self.usdc_reserves.saturating_sub(earmarked)
+    }
+    
+    /// Check if the treasury can pay out `usdc_amount` without jumping ahead of the redemption queue
+    pub fn has_available_usdc(&self, usdc_amount: u64) -> bool {
+        self.available_reserves() >= usdc_amount
+    }
     
-    /// Check if the treasury has sufficient reserves for a given ECE amount
+    /// Check if the treasury has sufficient reserves to redeem a given ECE amount
+    /// without jumping ahead of the redemption queue
     pub fn has_sufficient_reserves(&self, ece_amount: u64) -> bool {
-        self.usdc_reserves >= ece_amount
+        self.ece_to_usdc(ece_amount)
+            .is_some_and(|usdc_amount| self.has_available_usdc(usdc_amount))
+    }
+    
+    /// USDC reserves plus the haircut-adjusted value of the collateral basket, in ECE base units
+    pub fn basket_reserves(&self) -> u64 {
+        self.usdc_to_ece(self.usdc_reserves)
+            .unwrap_or(u64::MAX)
+            .saturating_add(self.collateral_reserves)
+    }
+    
+    /// ECE that can still be redeemed for USDC: circulation scaled by the USDC share of the
+    /// basket, so ECE minted against other collateral can't drain the USDC reserve
+    pub fn usdc_redeemable_supply(&self) -> u64 {
+        let basket = self.basket_reserves();
+        if basket == 0 {
+            return 0;
+        }
+        
+        let usdc = self.usdc_to_ece(self.usdc_reserves).unwrap_or(u64::MAX).min(basket);
+        (self.ece_circulation as u128 * usdc as u128 / basket as u128) as u64
+    }
+    
+    /// Check if `ece_amount` more ECE can be redeemed for USDC, counting the redemption queue
+    pub fn within_usdc_share(&self, ece_amount: u64) -> bool {
+        self.queued_redemptions
+            .checked_add(ece_amount)
+            .is_some_and(|total| total <= self.usdc_redeemable_supply())
     }
     
-    /// Calculate current reserve ratio in basis points
+    /// Calculate current reserve ratio across the collateral basket in basis points
     pub fn reserve_ratio(&self) -> u16 {
         if self.ece_circulation == 0 {
             return 10000; // 100% if no tokens in circulation
         }
         
-        let ratio = (self.usdc_reserves as u128 * 10000) / self.ece_circulation as u128;
+        let ratio = (self.basket_reserves() as u128 * 10000) / self.ece_circulation as u128;
         ratio.min(10000) as u16
     }
     
+    /// Check if withdrawing the given USDC amount would push the reserve ratio below minimum
+    pub fn would_breach_min_reserve_ratio(&self, usdc_amount: u64) -> bool {
+        if self.ece_circulation == 0 {
+            return false;
+        }
+        
+        let outflow = self.usdc_to_ece_ceil(usdc_amount).unwrap_or(u64::MAX);
+        let new_reserves = self.basket_reserves().saturating_sub(outflow);
+        let new_ratio = (new_reserves as u128 * 10000) / self.ece_circulation as u128;
+        new_ratio < self.min_reserve_ratio as u128
+    }
+    
+    /// Advance the outflow window, carrying the current bucket into the previous one
+    pub fn roll_outflow_window(&mut self, current_timestamp: i64) {
+        if self.outflow_window <= 0 {
+            return;
+        }
+        
+        let elapsed = current_timestamp.saturating_sub(self.outflow_window_start);
+        if elapsed >= self.outflow_window.saturating_mul(2) {
+            self.previous_window_outflow = 0;
+            self.window_outflow = 0;
+            self.window_withdrawals = 0;
+            self.outflow_window_start = current_timestamp;
+        } else if elapsed >= self.outflow_window {
+            self.previous_window_outflow = self.window_outflow;
+            self.window_outflow = 0;
+            self.window_withdrawals = 0;
+            self.outflow_window_start += self.outflow_window;
+        }
+    }
+    
+    /// Estimated outflow over the sliding window ending now, weighting the previous
+    /// bucket by the share of it still inside the window
+    pub fn sliding_window_outflow(&self, current_timestamp: i64) -> u64 {
+        if self.outflow_window <= 0 {
+            return self.window_outflow;
+        }
+        
+        let elapsed = current_timestamp
+            .saturating_sub(self.outflow_window_start)
+            .clamp(0, self.outflow_window);
+        let carried = self.previous_window_outflow as u128
+            * (self.outflow_window - elapsed) as u128
+            / self.outflow_window as u128;
+        self.window_outflow.saturating_add(carried as u64)
+    }
+    
+    /// Check whether an outflow of `usdc_amount` (burning `ece_amount`) should trip the breaker
+    pub fn circuit_breaker_check(
+        &mut self,
+        usdc_amount: u64,
+        ece_amount: u64,
+        current_timestamp: i64,
+    ) -> Option<CircuitBreakerReason> {
+        self.roll_outflow_window(current_timestamp);
+        
+        if self.max_window_outflow_bps > 0 {
+            let limit = self.usdc_reserves as u128 * self.max_window_outflow_bps as u128 / 10000;
+            let outflow = self.sliding_window_outflow(current_timestamp) as u128 + usdc_amount as u128;
+            if outflow > limit {
+                return Some(CircuitBreakerReason::OutflowLimit);
+            }
+        }
+        
+        let new_circulation = self.ece_circulation.saturating_sub(ece_amount);
+        if new_circulation > 0 {
+            let outflow = self.usdc_to_ece_ceil(usdc_amount).unwrap_or(u64::MAX);
+            let new_reserves = self.basket_reserves().saturating_sub(outflow);
+            let new_ratio = (new_reserves as u128 * 10000) / new_circulation as u128;
+            if new_ratio < self.reserve_floor_bps as u128 {
+                return Some(CircuitBreakerReason::ReserveFloor);
+            }
+        }
+        
+        None
+    }
+    
+    /// Count a completed outflow towards the current window
+    pub fn record_outflow(&mut self, usdc_amount: u64) {
+        self.window_outflow = self.window_outflow.saturating_add(usdc_amount);
+    }
+    
+    /// Whether a withdrawal must wait out the timelock, counting earlier
+    /// untimelocked withdrawals in the same window so splitting doesn't avoid it
+    pub fn requires_withdrawal_timelock(&self, amount: u64) -> bool {
+        self.window_withdrawals.saturating_add(amount) > self.withdrawal_timelock_threshold
+    }
+    
+    /// Whether a timelock change only makes withdrawals stricter
+    pub fn is_timelock_tightening(&self, threshold: u64, delay: i64) -> bool {
+        threshold <= self.withdrawal_timelock_threshold && delay >= self.withdrawal_timelock_delay
+    }
+    
+    /// Check if an operation type is paused, either globally or by its flag
+    pub fn is_operation_paused(&self, flag: u16) -> bool {
+        self.is_paused || self.pause_flags & flag != 0
+    }
+    
+    /// Current holder of a transferable authority
+    pub fn authority(&self, kind: AuthorityKind) -> &Pubkey {
+        match kind {
+            AuthorityKind::Emergency => &self.emergency_authority,
+            AuthorityKind::Compliance => &self.compliance_authority,
+        }
+    }
+    
+    /// Pending successor for a transferable authority (default key when none)
+    pub fn pending_authority(&self, kind: AuthorityKind) -> &Pubkey {
+        match kind {
+            AuthorityKind::Emergency => &self.pending_emergency_authority,
+            AuthorityKind::Compliance => &self.pending_compliance_authority,
+        }
+    }
+    
+    /// Pending successor slot for a transferable authority
+    pub fn pending_authority_mut(&mut self, kind: AuthorityKind) -> &mut Pubkey {
+        match kind {
+            AuthorityKind::Emergency => &mut self.pending_emergency_authority,
+            AuthorityKind::Compliance => &mut self.pending_compliance_authority,
+        }
+    }
+    
+    /// Pause the treasury and record why
+    pub fn trip_circuit_breaker(&mut self, reason: CircuitBreakerReason, current_timestamp: i64) {
+        self.is_paused = true;
+        self.breaker_trip_reason = reason;
+        self.breaker_tripped_at = current_timestamp;
+    }
+    
+    /// Check that a proposed signer set is well formed and its threshold is within bounds
+    pub fn is_valid_signer_set(&self, signers: &[Pubkey], threshold: u8) -> bool {
+        if signers.is_empty() || signers.len() > MAX_TREASURY_SIGNERS {
+            return false;
+        }
+        
+        if signers.iter().enumerate().any(|(i, signer)| signers[..i].contains(signer)) {
+            return false;
+        }
+        
+        threshold >= self.min_signer_threshold.max(1) && threshold as usize <= signers.len()
+    }
+    
+    /// USDC fee on a mint of `usdc_amount`, rounded up in the treasury's favour
+    pub fn mint_fee(&self, usdc_amount: u64) -> Option<u64> {
+        let fee = (usdc_amount as u128).checked_mul(self.mint_fee_bps as u128)?.checked_add(9999)? / 10000;
+        u64::try_from(fee).ok()
+    }
+    
+    /// USDC fee on a redemption releasing `usdc_amount`, rounded up in the treasury's favour
+    pub fn redeem_fee(&self, usdc_amount: u64) -> Option<u64> {
+        let fee = (usdc_amount as u128).checked_mul(self.redeem_fee_bps as u128)?.checked_add(9999)? / 10000;
+        u64::try_from(fee).ok()
+    }
+    
+    /// Check if `ece_amount` can be minted without new deposits while keeping
+    /// the basket above the minimum reserve ratio
+    pub fn can_mint_from_reserves(&self, ece_amount: u64) -> bool {
+        self.ece_circulation.checked_add(ece_amount).is_some_and(|new_circulation| {
+            // Round the required backing up so the ratio is never undershot
+            let required = (new_circulation as u128 * self.min_reserve_ratio as u128).div_ceil(10000);
+            self.basket_reserves() as u128 >= required
+        })
+    }
+    
     /// Check if reserve ratio is above minimum threshold
     pub fn is_reserve_ratio_healthy(&self) -> bool {
         self.reserve_ratio() >= self.min_reserve_ratio
@@ -312,3 +684,2035 @@ impl ReserveAuditRecord {
         32 + // auditor
         4 + 512; // notes (max 512 chars)
 }
+
+/// Maximum number of treasury multisig signers
+pub const MAX_TREASURY_SIGNERS: usize = 5;
+
+/// Maximum mint or redeem fee (basis points)
+pub const MAX_MINT_REDEEM_FEE_BPS: u16 = 500;
+
+/// Lowest reserve ratio governance can set (basis points)
+pub const MIN_GOVERNED_RESERVE_RATIO_BPS: u16 = 5000;
+
+/// Seed for the program's mint authority PDA
+pub const MINT_AUTHORITY_SEED: &[u8] = b"ece-mint-authority";
+
+/// Seed for the program's treasury authority PDA
+pub const TREASURY_AUTHORITY_SEED: &[u8] = b"ece-treasury-authority";
+
+/// Maximum lengths of the ECE token metadata fields
+pub const MAX_TOKEN_NAME_LEN: usize = 32;
+pub const MAX_TOKEN_SYMBOL_LEN: usize = 10;
+pub const MAX_TOKEN_URI_LEN: usize = 200;
+
+/// Pause flag for ECE minting
+pub const PAUSE_MINT: u16 = 1 << 0;
+
+/// Pause flag for ECE burns and redemptions
+pub const PAUSE_BURN: u16 = 1 << 1;
+
+/// Pause flag for USDC deposits into reserves
+pub const PAUSE_DEPOSIT: u16 = 1 << 2;
+
+/// Pause flag for USDC withdrawals from reserves
+pub const PAUSE_WITHDRAW: u16 = 1 << 3;
+
+/// Pause flag for weekly payouts
+pub const PAUSE_PAYOUT: u16 = 1 << 4;
+
+/// Pause flag for marketplace escrow, sale settlement and trade offers
+pub const PAUSE_ESCROW: u16 = 1 << 5;
+
+/// Pause flag for staking and unstaking
+pub const PAUSE_STAKING: u16 = 1 << 6;
+
+/// Pause flag for distributor claims, vesting releases and referral and staking reward claims
+pub const PAUSE_REWARDS: u16 = 1 << 7;
+
+/// Pause flag for trading card mints
+pub const PAUSE_CARDS: u16 = 1 << 8;
+
+/// Pause flag for new subscriptions and subscription charges
+pub const PAUSE_SUBSCRIPTIONS: u16 = 1 << 9;
+
+/// All defined pause flags; SetPauseFlags rejects any other bit
+pub const PAUSE_ALL: u16 = PAUSE_MINT
+    | PAUSE_BURN
+    | PAUSE_DEPOSIT
+    | PAUSE_WITHDRAW
+    | PAUSE_PAYOUT
+    | PAUSE_ESCROW
+    | PAUSE_STAKING
+    | PAUSE_REWARDS
+    | PAUSE_CARDS
+    | PAUSE_SUBSCRIPTIONS;
+
+/// Seed for the role registry PDA (followed by the treasury)
+pub const ROLE_REGISTRY_SEED: &[u8] = b"ece-role-registry";
+
+/// Maximum number of keys holding roles in the registry
+pub const MAX_ROLE_HOLDERS: usize = 16;
+
+/// Largest supported gap between ECE and USDC decimals
+pub const MAX_DECIMALS_DIFFERENCE: u8 = 12;
+
+/// Seed for the collateral registry PDA (followed by the treasury)
+pub const COLLATERAL_REGISTRY_SEED: &[u8] = b"ece-collateral-registry";
+
+/// Seed for a collateral reserve token account PDA (followed by the treasury and collateral mint)
+pub const COLLATERAL_RESERVE_SEED: &[u8] = b"ece-collateral-reserve";
+
+/// Maximum number of non-USDC collateral assets
+pub const MAX_COLLATERAL_ASSETS: usize = 8;
+
+/// Maximum haircut on a collateral asset (basis points)
+pub const MAX_COLLATERAL_HAIRCUT_BPS: u16 = 5000;
+
+/// Seed for the treasury's proof-of-reserves attestation PDA (followed by the treasury)
+pub const RESERVE_ATTESTATION_SEED: &[u8] = b"ece-reserve-attestation";
+
+/// Seed for a merkle distributor PDA (followed by the treasury and distributor id)
+pub const DISTRIBUTOR_SEED: &[u8] = b"ece-distributor";
+
+/// Seed for a merkle distributor's ECE vault PDA (followed by the distributor)
+pub const DISTRIBUTOR_VAULT_SEED: &[u8] = b"ece-distributor-vault";
+
+/// Maximum number of claims in one distributor, bounding its claimed bitmap
+pub const MAX_DISTRIBUTOR_CLAIMS: u32 = 10_000;
+
+/// Seed for a vesting schedule PDA (followed by the treasury, beneficiary and vesting id)
+pub const VESTING_SEED: &[u8] = b"ece-vesting";
+
+/// Seed for a vesting schedule's ECE vault PDA (followed by the vesting schedule)
+pub const VESTING_VAULT_SEED: &[u8] = b"ece-vesting-vault";
+
+/// Seed for the treasury's referral program config PDA (followed by the treasury)
+pub const REFERRAL_CONFIG_SEED: &[u8] = b"ece-referral-config";
+
+/// Seed for the referral reward vault PDA (followed by the referral config)
+pub const REFERRAL_VAULT_SEED: &[u8] = b"ece-referral-vault";
+
+/// Seed for a user's referral record PDA (followed by the treasury and user)
+pub const REFERRAL_SEED: &[u8] = b"ece-referral";
+
+/// Seed for a referrer's reward account PDA (followed by the treasury and referrer)
+pub const REFERRER_SEED: &[u8] = b"ece-referrer";
+
+/// Maximum referral reward: 10% of the minted ECE
+pub const MAX_REFERRAL_REWARD_BPS: u16 = 1000;
+
+/// Seed for a card trade offer PDA (followed by the treasury, maker and offer id)
+pub const TRADE_OFFER_SEED: &[u8] = b"ece-trade-offer";
+
+/// Seed for a trade offer's escrowed card vault PDA (followed by the offer)
+pub const TRADE_CARD_VAULT_SEED: &[u8] = b"ece-trade-card-vault";
+
+/// Seed for a trade offer's escrowed ECE vault PDA (followed by the offer)
+pub const TRADE_ECE_VAULT_SEED: &[u8] = b"ece-trade-ece-vault";
+
+/// Seed for a wallet's KYC record PDA (followed by the treasury and wallet)
+pub const KYC_RECORD_SEED: &[u8] = b"ece-kyc";
+
+/// Seed for the transfer denylist PDA (followed by the treasury)
+pub const DENYLIST_SEED: &[u8] = b"ece-denylist";
+
+/// Maximum number of wallets on the transfer denylist
+pub const MAX_DENYLISTED_WALLETS: usize = 32;
+
+/// Seed for the staking pool PDA (followed by the treasury state account)
+pub const STAKING_POOL_SEED: &[u8] = b"ece-staking-pool";
+
+/// Seed for the staked ECE vault PDA (followed by the staking pool)
+pub const STAKE_VAULT_SEED: &[u8] = b"ece-stake-vault";
+
+/// Seed for the staking reward vault PDA (followed by the staking pool)
+pub const REWARD_VAULT_SEED: &[u8] = b"ece-reward-vault";
+
+/// Seed for per-user stake account PDAs (followed by the pool and owner)
+pub const STAKE_ACCOUNT_SEED: &[u8] = b"ece-stake";
+
+/// Seed for the governance config PDA
+pub const GOVERNANCE_SEED: &[u8] = b"ece-governance";
+
+/// Seed for governance proposal PDAs (followed by the proposal id)
+pub const PROPOSAL_SEED: &[u8] = b"ece-proposal";
+
+/// Seed for vote record PDAs (followed by the proposal and voter)
+pub const VOTE_RECORD_SEED: &[u8] = b"ece-vote";
+
+/// Seed for the payout fee split PDA (followed by the treasury state account)
+pub const FEE_SPLIT_SEED: &[u8] = b"ece-fee-split";
+
+/// Maximum number of recipients in a payout fee split
+pub const MAX_FEE_RECIPIENTS: usize = 5;
+
+/// Seed for royalty registry PDAs (followed by the card collection key)
+pub const ROYALTY_SEED: &[u8] = b"ece-royalty";
+
+/// Maximum number of creators sharing a royalty
+pub const MAX_ROYALTY_CREATORS: usize = 5;
+
+/// Maximum royalty on a sale (basis points)
+pub const MAX_ROYALTY_BPS: u16 = 2500;
+
+/// Seed for the card collection PDA (followed by the treasury state account)
+pub const CARD_COLLECTION_SEED: &[u8] = b"ece-card-collection";
+
+/// Seed for card mint PDAs (followed by the collection and card id)
+pub const CARD_MINT_SEED: &[u8] = b"ece-card-mint";
+
+/// Seed for card record PDAs (followed by the card mint)
+pub const CARD_RECORD_SEED: &[u8] = b"ece-card";
+
+/// Seed for subscription plan PDAs (followed by the plan id)
+pub const SUBSCRIPTION_PLAN_SEED: &[u8] = b"ece-subscription-plan";
+
+/// Seed for subscription PDAs (followed by the plan and subscriber)
+pub const SUBSCRIPTION_SEED: &[u8] = b"ece-subscription";
+
+/// Seed for the redemption queue PDA (followed by the treasury state account)
+pub const REDEMPTION_QUEUE_SEED: &[u8] = b"ece-redemption-queue";
+
+/// Seed for the redemption escrow vault PDA (followed by the queue)
+pub const REDEMPTION_ESCROW_SEED: &[u8] = b"ece-redemption-escrow";
+
+/// Seed for redemption ticket PDAs (followed by the queue and ticket id)
+pub const REDEMPTION_TICKET_SEED: &[u8] = b"ece-redemption-ticket";
+
+/// Seed for timelocked withdrawal PDAs (followed by the treasury and withdrawal id)
+pub const PENDING_WITHDRAWAL_SEED: &[u8] = b"ece-pending-withdrawal";
+
+/// Fixed-point scale applied to the staking reward-per-token accumulator
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Staking pool that distributes ECE rewards to stakers per second
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct StakingPool {
+    /// Whether the pool is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this pool belongs to
+    pub treasury: Pubkey,
+    
+    /// ECE token mint address
+    pub ece_mint: Pubkey,
+    
+    /// Vault holding staked ECE
+    pub stake_vault: Pubkey,
+    
+    /// Vault holding ECE rewards funded by the treasury
+    pub reward_vault: Pubkey,
+    
+    /// Total ECE staked (raw, without lockup boost)
+    pub total_staked: u64,
+    
+    /// Total boosted stake used as the reward denominator
+    pub total_weighted_stake: u64,
+    
+    /// Rewards emitted per second across the whole pool
+    pub reward_rate: u64,
+    
+    /// Timestamp at which the current reward period ends
+    pub reward_period_end: i64,
+    
+    /// Accumulated rewards per unit of weighted stake (scaled by REWARD_PRECISION)
+    pub reward_per_token_stored: u128,
+    
+    /// Last time the accumulator was updated
+    pub last_update_timestamp: i64,
+    
+    /// Rewards accrued to stakers but not yet claimed, still owed from the reward vault
+    pub accrued_rewards: u64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl StakingPool {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // ece_mint
+        32 + // stake_vault
+        32 + // reward_vault
+        8 + // total_staked
+        8 + // total_weighted_stake
+        8 + // reward_rate
+        8 + // reward_period_end
+        16 + // reward_per_token_stored
+        8 + // last_update_timestamp
+        8 + // accrued_rewards
+        1; // bump
+    
+    /// Latest timestamp at which rewards are still accruing
+    pub fn last_time_reward_applicable(&self, current_timestamp: i64) -> i64 {
+        current_timestamp.min(self.reward_period_end)
+    }
+    
+    /// Calculate the reward-per-token accumulator at the given timestamp
+    pub fn reward_per_token(&self, current_timestamp: i64) -> Option<u128> {
+        if self.total_weighted_stake == 0 {
+            return Some(self.reward_per_token_stored);
+        }
+        
+        let last_applicable = self.last_time_reward_applicable(current_timestamp);
+        let elapsed = last_applicable.saturating_sub(self.last_update_timestamp).max(0) as u128;
+        
+        let accrued = elapsed
+            .checked_mul(self.reward_rate as u128)?
+            .checked_mul(REWARD_PRECISION)?
+            .checked_div(self.total_weighted_stake as u128)?;
+        
+        self.reward_per_token_stored.checked_add(accrued)
+    }
+    
+    /// Checkpoint the accumulator up to the given timestamp, adding what stakers
+    /// earned since the last checkpoint to the rewards owed
+    pub fn update_reward_per_token(&mut self, current_timestamp: i64) -> Option<()> {
+        let last_applicable = self.last_time_reward_applicable(current_timestamp);
+        if self.total_weighted_stake > 0 {
+            let elapsed = last_applicable.saturating_sub(self.last_update_timestamp).max(0) as u64;
+            self.accrued_rewards = self
+                .accrued_rewards
+                .checked_add(elapsed.checked_mul(self.reward_rate)?)?;
+        }
+        
+        self.reward_per_token_stored = self.reward_per_token(current_timestamp)?;
+        self.last_update_timestamp = last_applicable;
+        Some(())
+    }
+    
+    /// Start a new reward period, rolling any undistributed rewards into it
+    pub fn notify_reward_amount(
+        &mut self,
+        amount: u64,
+        duration: i64,
+        current_timestamp: i64,
+    ) -> Option<()> {
+        if duration <= 0 {
+            return None;
+        }
+        
+        self.update_reward_per_token(current_timestamp)?;
+        
+        let mut total = amount as u128;
+        if current_timestamp < self.reward_period_end {
+            let remaining = (self.reward_period_end - current_timestamp) as u128;
+            total = total.checked_add(remaining.checked_mul(self.reward_rate as u128)?)?;
+        }
+        
+        self.reward_rate = u64::try_from(total / duration as u128).ok()?;
+        self.last_update_timestamp = current_timestamp;
+        self.reward_period_end = current_timestamp.checked_add(duration)?;
+        Some(())
+    }
+}
+
+/// Lockup tier chosen at stake time; longer lockups earn boosted rewards
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LockupTier {
+    Flexible,
+    ThirtyDays,
+    NinetyDays,
+    OneYear,
+}
+
+impl LockupTier {
+    /// Lockup duration in seconds
+    pub fn duration(&self) -> i64 {
+        match self {
+            LockupTier::Flexible => 0,
+            LockupTier::ThirtyDays => 30 * 86400,
+            LockupTier::NinetyDays => 90 * 86400,
+            LockupTier::OneYear => 365 * 86400,
+        }
+    }
+    
+    /// Reward weight multiplier in basis points (10000 = 1x)
+    pub fn boost_bps(&self) -> u16 {
+        match self {
+            LockupTier::Flexible => 10000,
+            LockupTier::ThirtyDays => 11000,
+            LockupTier::NinetyDays => 12500,
+            LockupTier::OneYear => 15000,
+        }
+    }
+    
+    /// Apply the tier boost to a raw stake amount
+    pub fn weighted_amount(&self, amount: u64) -> Option<u64> {
+        let weighted = (amount as u128).checked_mul(self.boost_bps() as u128)? / 10000;
+        u64::try_from(weighted).ok()
+    }
+}
+
+/// Per-user stake position in a staking pool
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct StakeAccount {
+    /// Whether the stake account is initialized
+    pub is_initialized: bool,
+    
+    /// Staking pool this position belongs to
+    pub pool: Pubkey,
+    
+    /// Owner of the staked ECE
+    pub owner: Pubkey,
+    
+    /// Raw ECE staked
+    pub amount: u64,
+    
+    /// Boosted stake counted towards rewards
+    pub weighted_amount: u64,
+    
+    /// Selected lockup tier
+    pub lockup_tier: LockupTier,
+    
+    /// Timestamp until which the stake cannot be withdrawn
+    pub lockup_end: i64,
+    
+    /// Timestamp of the most recent deposit (used for governance snapshots)
+    pub last_stake_timestamp: i64,
+    
+    /// Pool accumulator value at the last checkpoint
+    pub reward_per_token_paid: u128,
+    
+    /// Rewards earned but not yet claimed
+    pub pending_rewards: u64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl StakeAccount {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // pool
+        32 + // owner
+        8 + // amount
+        8 + // weighted_amount
+        1 + // lockup_tier
+        8 + // lockup_end
+        8 + // last_stake_timestamp
+        16 + // reward_per_token_paid
+        8 + // pending_rewards
+        1; // bump
+    
+    /// Rewards earned given the current pool accumulator
+    pub fn earned(&self, reward_per_token: u128) -> Option<u64> {
+        let delta = reward_per_token.checked_sub(self.reward_per_token_paid)?;
+        let accrued = (self.weighted_amount as u128)
+            .checked_mul(delta)?
+            .checked_div(REWARD_PRECISION)?;
+        let total = (self.pending_rewards as u128).checked_add(accrued)?;
+        u64::try_from(total).ok()
+    }
+    
+    /// Checkpoint earned rewards against the pool accumulator
+    pub fn update_rewards(&mut self, reward_per_token: u128) -> Option<()> {
+        self.pending_rewards = self.earned(reward_per_token)?;
+        self.reward_per_token_paid = reward_per_token;
+        Some(())
+    }
+    
+    /// Whether the stake is still locked at the given timestamp
+    pub fn is_locked(&self, current_timestamp: i64) -> bool {
+        current_timestamp < self.lockup_end
+    }
+    
+    /// Voting weight for a proposal created at the given timestamp.
+    /// Stake added at or after proposal creation does not count.
+    pub fn voting_weight_at(&self, snapshot_timestamp: i64) -> u64 {
+        if self.last_stake_timestamp < snapshot_timestamp {
+            self.amount
+        } else {
+            0
+        }
+    }
+}
+
+/// Governance configuration for staker-voted treasury changes
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct GovernanceConfig {
+    /// Whether governance is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account controlled by governance
+    pub treasury: Pubkey,
+    
+    /// Staking pool whose positions carry voting weight
+    pub staking_pool: Pubkey,
+    
+    /// Minimum participation (basis points of total staked at creation)
+    pub quorum_bps: u16,
+    
+    /// Voting period in seconds
+    pub voting_period: i64,
+    
+    /// Minimum stake required to create a proposal
+    pub proposal_threshold: u64,
+    
+    /// Number of proposals created
+    pub proposal_count: u64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl GovernanceConfig {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // staking_pool
+        2 + // quorum_bps
+        8 + // voting_period
+        8 + // proposal_threshold
+        8 + // proposal_count
+        1; // bump
+}
+
+/// Treasury parameters that governance is allowed to change
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreasuryParameter {
+    MinReserveRatio,
+    DailyVolumeLimit,
+    MaxTransactionAmount,
+    PayoutWindow,
+    MarketplaceFeeBps,
+}
+
+/// Whitelisted treasury parameter change executable by governance
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreasuryParameterChange {
+    /// Parameter to change
+    pub parameter: TreasuryParameter,
+    
+    /// New value (fixed width so proposals have a constant size)
+    pub value: u64,
+}
+
+impl TreasuryParameterChange {
+    pub const LEN: usize = 1 + // parameter
+        8; // value
+    
+    /// Check that the new value is within sane bounds
+    pub fn is_valid(&self) -> bool {
+        match self.parameter {
+            TreasuryParameter::MinReserveRatio => (MIN_GOVERNED_RESERVE_RATIO_BPS as u64..=10000).contains(&self.value),
+            TreasuryParameter::DailyVolumeLimit => self.value > 0,
+            TreasuryParameter::MaxTransactionAmount => self.value > 0,
+            TreasuryParameter::PayoutWindow => self.value > 0 && self.value <= i64::MAX as u64,
+            TreasuryParameter::MarketplaceFeeBps => self.value <= (10000 - MAX_ROYALTY_BPS) as u64,
+        }
+    }
+    
+    /// Apply the change to the treasury state; callers must check `is_valid` first
+    pub fn apply(&self, treasury_state: &mut TreasuryState) {
+        match self.parameter {
+            TreasuryParameter::MinReserveRatio => {
+                treasury_state.min_reserve_ratio = self.value as u16;
+            }
+            TreasuryParameter::DailyVolumeLimit => {
+                treasury_state.daily_volume_limit = self.value;
+            }
+            TreasuryParameter::MaxTransactionAmount => {
+                treasury_state.max_transaction_amount = self.value;
+            }
+            TreasuryParameter::PayoutWindow => {
+                treasury_state.payout_window = self.value as i64;
+            }
+            TreasuryParameter::MarketplaceFeeBps => {
+                treasury_state.marketplace_fee_bps = self.value as u16;
+            }
+        }
+    }
+}
+
+/// Governance proposal voted on by stakers
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Proposal {
+    /// Whether the proposal is initialized
+    pub is_initialized: bool,
+    
+    /// Sequential proposal id
+    pub proposal_id: u64,
+    
+    /// Proposal creator
+    pub proposer: Pubkey,
+    
+    /// Treasury change to execute if the proposal passes
+    pub change: TreasuryParameterChange,
+    
+    /// Creation timestamp; stake added afterwards carries no weight
+    pub created_at: i64,
+    
+    /// Timestamp when voting closes
+    pub voting_ends_at: i64,
+    
+    /// Total staked at creation, used as the quorum base
+    pub snapshot_total_staked: u64,
+    
+    /// Weight voting in favour
+    pub votes_for: u64,
+    
+    /// Weight voting against
+    pub votes_against: u64,
+    
+    /// Whether the change has been executed
+    pub executed: bool,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const LEN: usize = 1 + // is_initialized
+        8 + // proposal_id
+        32 + // proposer
+        TreasuryParameterChange::LEN + // change
+        8 + // created_at
+        8 + // voting_ends_at
+        8 + // snapshot_total_staked
+        8 + // votes_for
+        8 + // votes_against
+        1 + // executed
+        1; // bump
+    
+    /// Whether voting is still open
+    pub fn is_voting_active(&self, current_timestamp: i64) -> bool {
+        current_timestamp < self.voting_ends_at
+    }
+    
+    /// Whether participation reached quorum
+    pub fn has_quorum(&self, quorum_bps: u16) -> bool {
+        let participation = self.votes_for as u128 + self.votes_against as u128;
+        participation * 10000 >= self.snapshot_total_staked as u128 * quorum_bps as u128
+    }
+    
+    /// Whether the proposal passed: quorum met and a strict majority in favour
+    pub fn has_passed(&self, quorum_bps: u16) -> bool {
+        self.has_quorum(quorum_bps) && self.votes_for > self.votes_against
+    }
+}
+
+/// Record of a single staker's vote, preventing double voting
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct VoteRecord {
+    /// Whether the record is initialized
+    pub is_initialized: bool,
+    
+    /// Proposal voted on
+    pub proposal: Pubkey,
+    
+    /// Voter wallet
+    pub voter: Pubkey,
+    
+    /// Whether the vote was in favour
+    pub support: bool,
+    
+    /// Weight cast
+    pub weight: u64,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // proposal
+        32 + // voter
+        1 + // support
+        8; // weight
+}
+
+/// A single payout recipient and its share
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeRecipient {
+    /// USDC token account receiving the share
+    pub usdc_account: Pubkey,
+    
+    /// Share of each payout in basis points
+    pub share_bps: u16,
+}
+
+/// Fee split table used to route weekly payouts across recipients
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct FeeSplitConfig {
+    /// Whether the fee split is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this split belongs to
+    pub treasury: Pubkey,
+    
+    /// Number of active entries in `recipients`
+    pub recipient_count: u8,
+    
+    /// Recipients (only the first `recipient_count` are active)
+    pub recipients: [FeeRecipient; MAX_FEE_RECIPIENTS],
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl FeeSplitConfig {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        1 + // recipient_count
+        (32 + 2) * MAX_FEE_RECIPIENTS + // recipients
+        1; // bump
+    
+    /// Check that a recipient table is non-empty, unique and sums to 100%
+    pub fn validate_recipients(recipients: &[FeeRecipient]) -> bool {
+        if recipients.is_empty() || recipients.len() > MAX_FEE_RECIPIENTS {
+            return false;
+        }
+        
+        let mut total_bps: u32 = 0;
+        for (i, recipient) in recipients.iter().enumerate() {
+            if recipient.share_bps == 0 {
+                return false;
+            }
+            if recipients[..i].iter().any(|r| r.usdc_account == recipient.usdc_account) {
+                return false;
+            }
+            total_bps += recipient.share_bps as u32;
+        }
+        
+        total_bps == 10000
+    }
+    
+    /// Replace the recipient table; callers must validate first
+    pub fn set_recipients(&mut self, recipients: &[FeeRecipient]) {
+        self.recipients = [FeeRecipient::default(); MAX_FEE_RECIPIENTS];
+        self.recipients[..recipients.len()].copy_from_slice(recipients);
+        self.recipient_count = recipients.len() as u8;
+    }
+    
+    /// Active recipients
+    pub fn active_recipients(&self) -> &[FeeRecipient] {
+        &self.recipients[..(self.recipient_count as usize).min(MAX_FEE_RECIPIENTS)]
+    }
+    
+    /// Split an amount across recipients. Each share is rounded down and the
+    /// rounding dust goes to the first recipient, so the parts always sum to `amount`.
+    pub fn split_amount(&self, amount: u64) -> Option<Vec<u64>> {
+        let mut shares = Vec::with_capacity(self.recipient_count as usize);
+        let mut distributed: u64 = 0;
+        
+        for recipient in self.active_recipients() {
+            let share = (amount as u128).checked_mul(recipient.share_bps as u128)? / 10000;
+            let share = u64::try_from(share).ok()?;
+            distributed = distributed.checked_add(share)?;
+            shares.push(share);
+        }
+        
+        let dust = amount.checked_sub(distributed)?;
+        let first = shares.first_mut()?;
+        *first = first.checked_add(dust)?;
+        
+        Some(shares)
+    }
+}
+
+/// A creator entitled to part of a card's royalty
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RoyaltyCreator {
+    /// Creator wallet (owner of the ECE account receiving royalties)
+    pub wallet: Pubkey,
+    
+    /// Share of the royalty in basis points
+    pub share_bps: u16,
+}
+
+/// Royalty configuration for a card collection
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RoyaltyRegistry {
+    /// Whether the registry is initialized
+    pub is_initialized: bool,
+    
+    /// Card collection this registry applies to
+    pub collection: Pubkey,
+    
+    /// Total royalty on each sale (basis points of the price)
+    pub royalty_bps: u16,
+    
+    /// Number of active entries in `creators`
+    pub creator_count: u8,
+    
+    /// Creators (only the first `creator_count` are active)
+    pub creators: [RoyaltyCreator; MAX_ROYALTY_CREATORS],
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Breakdown of a card sale paid in ECE
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaleSettlement {
+    /// Amount received by the seller
+    pub seller_amount: u64,
+    
+    /// Platform fee routed to company revenue
+    pub platform_fee: u64,
+    
+    /// Royalty per creator, in registry order
+    pub creator_amounts: Vec<u64>,
+}
+
+impl RoyaltyRegistry {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // collection
+        2 + // royalty_bps
+        1 + // creator_count
+        (32 + 2) * MAX_ROYALTY_CREATORS + // creators
+        1; // bump
+    
+    /// Check that a creator table is non-empty, unique and sums to 100%
+    pub fn validate_creators(creators: &[RoyaltyCreator]) -> bool {
+        if creators.is_empty() || creators.len() > MAX_ROYALTY_CREATORS {
+            return false;
+        }
+        
+        let mut total_bps: u32 = 0;
+        for (i, creator) in creators.iter().enumerate() {
+            if creator.share_bps == 0 {
+                return false;
+            }
+            if creators[..i].iter().any(|c| c.wallet == creator.wallet) {
+                return false;
+            }
+            total_bps += creator.share_bps as u32;
+        }
+        
+        total_bps == 10000
+    }
+    
+    /// Replace the creator table; callers must validate first
+    pub fn set_creators(&mut self, creators: &[RoyaltyCreator]) {
+        self.creators = [RoyaltyCreator::default(); MAX_ROYALTY_CREATORS];
+        self.creators[..creators.len()].copy_from_slice(creators);
+        self.creator_count = creators.len() as u8;
+    }
+    
+    /// Active creators
+    pub fn active_creators(&self) -> &[RoyaltyCreator] {
+        &self.creators[..(self.creator_count as usize).min(MAX_ROYALTY_CREATORS)]
+    }
+    
+    /// Split a sale price into seller proceeds, platform fee and creator
+    /// royalties. Creator shares round down with the dust going to the first
+    /// creator; the seller receives whatever remains.
+    pub fn settle(&self, price: u64, platform_fee_bps: u16) -> Option<SaleSettlement> {
+        if self.royalty_bps as u32 + platform_fee_bps as u32 > 10000 {
+            return None;
+        }
+        
+        let platform_fee = u64::try_from((price as u128) * platform_fee_bps as u128 / 10000).ok()?;
+        let royalty_total = u64::try_from((price as u128) * self.royalty_bps as u128 / 10000).ok()?;
+        
+        let mut creator_amounts = Vec::with_capacity(self.creator_count as usize);
+        let mut distributed: u64 = 0;
+        for creator in self.active_creators() {
+            let amount = u64::try_from((royalty_total as u128) * creator.share_bps as u128 / 10000).ok()?;
+            distributed = distributed.checked_add(amount)?;
+            creator_amounts.push(amount);
+        }
+        
+        let dust = royalty_total.checked_sub(distributed)?;
+        let first = creator_amounts.first_mut()?;
+        *first = first.checked_add(dust)?;
+        
+        let seller_amount = price.checked_sub(platform_fee)?.checked_sub(royalty_total)?;
+        
+        Some(SaleSettlement {
+            seller_amount,
+            platform_fee,
+            creator_amounts,
+        })
+    }
+}
+
+/// Collection of trading card NFTs minted under program authority
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CardCollection {
+    /// Whether the collection is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account receiving mint fees
+    pub treasury: Pubkey,
+    
+    /// Mint fee charged in ECE per card
+    pub mint_fee: u64,
+    
+    /// Number of cards minted
+    pub cards_minted: u64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl CardCollection {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        8 + // mint_fee
+        8 + // cards_minted
+        1; // bump
+}
+
+/// On-chain record linking a card NFT mint to its off-chain card
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CardRecord {
+    /// Whether the record is initialized
+    pub is_initialized: bool,
+    
+    /// Collection the card belongs to
+    pub collection: Pubkey,
+    
+    /// Card NFT mint
+    pub mint: Pubkey,
+    
+    /// Off-chain card identifier (hash of the database id)
+    pub card_id: [u8; 32],
+    
+    /// Wallet the card was minted to
+    pub minted_to: Pubkey,
+    
+    /// Mint timestamp
+    pub minted_at: i64,
+    
+    /// Sequence number within the collection
+    pub serial_number: u64,
+}
+
+impl CardRecord {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // collection
+        32 + // mint
+        32 + // card_id
+        32 + // minted_to
+        8 + // minted_at
+        8; // serial_number
+}
+
+/// Recurring ECE billing plan offered by the platform
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SubscriptionPlan {
+    /// Whether the plan is initialized
+    pub is_initialized: bool,
+    
+    /// Plan identifier
+    pub plan_id: u64,
+    
+    /// ECE charged per period
+    pub price: u64,
+    
+    /// Billing period in seconds
+    pub period: i64,
+    
+    /// Time after a missed charge before the subscription expires
+    pub grace_period: i64,
+    
+    /// Whether new subscriptions are accepted
+    pub is_active: bool,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl SubscriptionPlan {
+    pub const LEN: usize = 1 + // is_initialized
+        8 + // plan_id
+        8 + // price
+        8 + // period
+        8 + // grace_period
+        1 + // is_active
+        1; // bump
+}
+
+/// Billing state of a subscription; cancelled subscriptions keep access until `next_charge_at`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionStatus {
+    Active,
+    PastDue,
+    Cancelled,
+    Expired,
+}
+
+/// A user's subscription, collected by a permissionless crank
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Subscription {
+    /// Whether the subscription is initialized
+    pub is_initialized: bool,
+    
+    /// Plan subscribed to
+    pub plan: Pubkey,
+    
+    /// Subscriber wallet
+    pub subscriber: Pubkey,
+    
+    /// ECE account the fee is pulled from (delegated to this PDA)
+    pub subscriber_ece_account: Pubkey,
+    
+    /// ECE charged per period, fixed at subscribe time
+    pub price: u64,
+    
+    /// Billing period in seconds, fixed at subscribe time
+    pub period: i64,
+    
+    /// Grace period in seconds, fixed at subscribe time
+    pub grace_period: i64,
+    
+    /// Timestamp the next period becomes collectable
+    pub next_charge_at: i64,
+    
+    /// Number of periods paid
+    pub periods_paid: u64,
+    
+    /// Total ECE collected
+    pub total_collected: u64,
+    
+    /// Current status
+    pub status: SubscriptionStatus,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Subscription {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // plan
+        32 + // subscriber
+        32 + // subscriber_ece_account
+        8 + // price
+        8 + // period
+        8 + // grace_period
+        8 + // next_charge_at
+        8 + // periods_paid
+        8 + // total_collected
+        1 + // status
+        1; // bump
+    
+    /// Whether the current period can be collected
+    pub fn is_due(&self, current_timestamp: i64) -> bool {
+        current_timestamp >= self.next_charge_at
+    }
+    
+    /// Whether a missed charge is still within the grace period
+    pub fn is_within_grace(&self, current_timestamp: i64) -> bool {
+        current_timestamp < self.next_charge_at.saturating_add(self.grace_period)
+    }
+    
+    /// Whether the subscriber currently has paid-up access
+    pub fn has_access(&self, current_timestamp: i64) -> bool {
+        match self.status {
+            SubscriptionStatus::Expired => false,
+            SubscriptionStatus::PastDue => self.is_within_grace(current_timestamp),
+            _ => current_timestamp < self.next_charge_at,
+        }
+    }
+}
+
+/// FIFO queue of ECE redemptions waiting for USDC reserves
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RedemptionQueue {
+    /// Whether the queue is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account the queue redeems against
+    pub treasury: Pubkey,
+    
+    /// Vault holding ECE locked in queued tickets
+    pub escrow_vault: Pubkey,
+    
+    /// Id of the next ticket to settle
+    pub head: u64,
+    
+    /// Id assigned to the next queued ticket
+    pub tail: u64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl RedemptionQueue {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // escrow_vault
+        8 + // head
+        8 + // tail
+        1; // bump
+    
+    /// Number of tickets waiting to be settled
+    pub fn pending_tickets(&self) -> u64 {
+        self.tail.saturating_sub(self.head)
+    }
+}
+
+/// A user's queued redemption
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RedemptionTicket {
+    /// Whether the ticket is initialized
+    pub is_initialized: bool,
+    
+    /// Queue the ticket belongs to
+    pub queue: Pubkey,
+    
+    /// Position in the queue
+    pub ticket_id: u64,
+    
+    /// Wallet that queued the redemption
+    pub owner: Pubkey,
+    
+    /// USDC account receiving the redemption
+    pub destination_usdc_account: Pubkey,
+    
+    /// ECE locked in the ticket
+    pub amount: u64,
+    
+    /// ECE already redeemed for USDC
+    pub settled_amount: u64,
+    
+    /// Timestamp the ticket was queued
+    pub created_at: i64,
+    
+    /// Whether the owner cancelled the ticket and took back the unsettled ECE
+    pub cancelled: bool,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl RedemptionTicket {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // queue
+        8 + // ticket_id
+        32 + // owner
+        32 + // destination_usdc_account
+        8 + // amount
+        8 + // settled_amount
+        8 + // created_at
+        1 + // cancelled
+        1; // bump
+    
+    /// ECE still waiting to be redeemed
+    pub fn remaining(&self) -> u64 {
+        if self.cancelled {
+            return 0;
+        }
+        self.amount.saturating_sub(self.settled_amount)
+    }
+}
+
+/// Treasury authorities that can be handed over with ProposeAuthority / AcceptAuthority
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityKind {
+    Emergency,
+    Compliance,
+}
+
+/// Why the circuit breaker paused the treasury
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitBreakerReason {
+    None,
+    OutflowLimit,
+    ReserveFloor,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WithdrawalStatus {
+    Pending,
+    Executed,
+    Cancelled,
+}
+
+/// Large USDC withdrawal waiting out the treasury timelock
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PendingWithdrawal {
+    /// Whether the withdrawal is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account the withdrawal draws from
+    pub treasury: Pubkey,
+    
+    /// Sequential withdrawal id
+    pub withdrawal_id: u64,
+    
+    /// Signer that requested the withdrawal
+    pub proposer: Pubkey,
+    
+    /// USDC account receiving the funds
+    pub destination_usdc_account: Pubkey,
+    
+    /// USDC amount
+    pub amount: u64,
+    
+    /// Timestamp the withdrawal was requested
+    pub created_at: i64,
+    
+    /// Timestamp after which the withdrawal can execute
+    pub unlock_at: i64,
+    
+    /// Current status
+    pub status: WithdrawalStatus,
+    
+    /// PDA bump seed
+    pub bump: u8,
+    
+    /// Treasury signer epoch the withdrawal was requested under
+    pub signer_epoch: u64,
+}
+
+impl PendingWithdrawal {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        8 + // withdrawal_id
+        32 + // proposer
+        32 + // destination_usdc_account
+        8 + // amount
+        8 + // created_at
+        8 + // unlock_at
+        1 + // status
+        1 + // bump
+        8; // signer_epoch
+    
+    /// Whether the timelock has elapsed
+    pub fn is_unlocked(&self, current_timestamp: i64) -> bool {
+        current_timestamp >= self.unlock_at
+    }
+}
+
+/// Program roles that can be granted to keys in the role registry
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    Minter,
+    Pauser,
+    ComplianceOfficer,
+    Auditor,
+    Resolver,
+}
+
+impl Role {
+    /// Bit for this role in a `RoleAssignment::roles` mask
+    pub fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+/// Roles held by a single key
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RoleAssignment {
+    /// Key holding the roles
+    pub holder: Pubkey,
+    
+    /// Bitmask of granted roles
+    pub roles: u8,
+}
+
+/// Registry mapping keys to program roles for a treasury
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RoleRegistry {
+    /// Whether the registry is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this registry belongs to
+    pub treasury: Pubkey,
+    
+    /// Number of active assignments
+    pub assignment_count: u8,
+    
+    /// Role assignments (only the first `assignment_count` are used)
+    pub assignments: [RoleAssignment; MAX_ROLE_HOLDERS],
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl RoleRegistry {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        1 + // assignment_count
+        (32 + 1) * MAX_ROLE_HOLDERS + // assignments
+        1; // bump
+    
+    /// Active assignments
+    pub fn active_assignments(&self) -> &[RoleAssignment] {
+        &self.assignments[..self.assignment_count as usize]
+    }
+    
+    /// Check if a key holds a role
+    pub fn has_role(&self, holder: &Pubkey, role: Role) -> bool {
+        self.active_assignments()
+            .iter()
+            .any(|assignment| &assignment.holder == holder && assignment.roles & role.bit() != 0)
+    }
+    
+    /// Grant a role, returning false if the registry is full
+    pub fn grant(&mut self, holder: &Pubkey, role: Role) -> bool {
+        let count = self.assignment_count as usize;
+        if let Some(assignment) = self.assignments[..count].iter_mut().find(|a| &a.holder == holder) {
+            assignment.roles |= role.bit();
+            return true;
+        }
+        
+        if count >= MAX_ROLE_HOLDERS {
+            return false;
+        }
+        
+        self.assignments[count] = RoleAssignment {
+            holder: *holder,
+            roles: role.bit(),
+        };
+        self.assignment_count += 1;
+        true
+    }
+    
+    /// Revoke a role, dropping the holder once no roles remain
+    pub fn revoke(&mut self, holder: &Pubkey, role: Role) {
+        let count = self.assignment_count as usize;
+        if let Some(index) = self.assignments[..count].iter().position(|a| &a.holder == holder) {
+            self.assignments[index].roles &= !role.bit();
+            if self.assignments[index].roles == 0 {
+                self.assignments[index] = self.assignments[count - 1];
+                self.assignments[count - 1] = RoleAssignment::default();
+                self.assignment_count -= 1;
+            }
+        }
+    }
+}
+
+/// Wallets barred from sending or receiving ECE, enforced by the transfer hook
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Denylist {
+    /// Whether the denylist is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this denylist belongs to
+    pub treasury: Pubkey,
+    
+    /// Number of denylisted wallets
+    pub wallet_count: u8,
+    
+    /// Denylisted wallets (only the first `wallet_count` are used)
+    pub wallets: [Pubkey; MAX_DENYLISTED_WALLETS],
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Denylist {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        1 + // wallet_count
+        32 * MAX_DENYLISTED_WALLETS + // wallets
+        1; // bump
+    
+    /// Denylisted wallets
+    pub fn active_wallets(&self) -> &[Pubkey] {
+        &self.wallets[..self.wallet_count as usize]
+    }
+    
+    /// Check if a wallet is denylisted
+    pub fn contains(&self, wallet: &Pubkey) -> bool {
+        self.active_wallets().contains(wallet)
+    }
+    
+    /// Add a wallet, returning false if the denylist is full
+    pub fn add(&mut self, wallet: &Pubkey) -> bool {
+        if self.contains(wallet) {
+            return true;
+        }
+        
+        let count = self.wallet_count as usize;
+        if count >= MAX_DENYLISTED_WALLETS {
+            return false;
+        }
+        
+        self.wallets[count] = *wallet;
+        self.wallet_count += 1;
+        true
+    }
+    
+    /// Remove a wallet if present
+    pub fn remove(&mut self, wallet: &Pubkey) {
+        let count = self.wallet_count as usize;
+        if let Some(index) = self.wallets[..count].iter().position(|w| w == wallet) {
+            self.wallets[index] = self.wallets[count - 1];
+            self.wallets[count - 1] = Pubkey::default();
+            self.wallet_count -= 1;
+        }
+    }
+}
+
+/// Rescale an amount between mints with different decimals, rounding up or down
+pub fn convert_decimals(amount: u64, from_decimals: u8, to_decimals: u8, round_up: bool) -> Option<u64> {
+    let value = if to_decimals >= from_decimals {
+        (amount as u128).checked_mul(10u128.checked_pow((to_decimals - from_decimals) as u32)?)?
+    } else {
+        let divisor = 10u128.checked_pow((from_decimals - to_decimals) as u32)?;
+        let quotient = amount as u128 / divisor;
+        if round_up && quotient * divisor != amount as u128 {
+            quotient + 1
+        } else {
+            quotient
+        }
+    };
+    u64::try_from(value).ok()
+}
+
+/// A non-USDC stablecoin accepted as ECE collateral
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct CollateralAsset {
+    /// Collateral token mint
+    pub mint: Pubkey,
+    
+    /// Reserve token account PDA holding this collateral
+    pub reserve: Pubkey,
+    
+    /// Collateral mint decimals
+    pub decimals: u8,
+    
+    /// Maximum amount held in reserve (in collateral base units)
+    pub cap: u64,
+    
+    /// Discount applied when valuing this collateral (basis points)
+    pub haircut_bps: u16,
+    
+    /// Amount currently held in reserve (in collateral base units)
+    pub deposited: u64,
+    
+    /// Whether new mints against this collateral are accepted
+    pub is_active: bool,
+}
+
+impl CollateralAsset {
+    /// Convert a collateral amount to ECE base units, rounding down
+    pub fn normalize(&self, amount: u64, ece_decimals: u8) -> Option<u64> {
+        convert_decimals(amount, self.decimals, ece_decimals, false)
+    }
+    
+    /// Haircut-adjusted value of a collateral amount in ECE base units, rounding down
+    pub fn collateral_value(&self, amount: u64, ece_decimals: u8) -> Option<u64> {
+        let normalized = self.normalize(amount, ece_decimals)? as u128;
+        let value = normalized * (10000 - self.haircut_bps.min(10000)) as u128 / 10000;
+        u64::try_from(value).ok()
+    }
+    
+    /// Check if the reserve can take another `amount` without exceeding the cap
+    pub fn has_capacity(&self, amount: u64) -> bool {
+        self.deposited
+            .checked_add(amount)
+            .is_some_and(|total| total <= self.cap)
+    }
+}
+
+/// Registry of approved non-USDC collateral for a treasury
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CollateralRegistry {
+    /// Whether the registry is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this registry belongs to
+    pub treasury: Pubkey,
+    
+    /// Number of registered assets
+    pub asset_count: u8,
+    
+    /// Registered assets (only the first `asset_count` are used)
+    pub assets: [CollateralAsset; MAX_COLLATERAL_ASSETS],
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl CollateralRegistry {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        1 + // asset_count
+        (32 + 32 + 1 + 8 + 2 + 8 + 1) * MAX_COLLATERAL_ASSETS + // assets
+        1; // bump
+    
+    /// Registered assets
+    pub fn active_assets(&self) -> &[CollateralAsset] {
+        &self.assets[..self.asset_count as usize]
+    }
+    
+    /// Look up a registered asset by mint
+    pub fn find(&self, mint: &Pubkey) -> Option<&CollateralAsset> {
+        self.active_assets().iter().find(|asset| &asset.mint == mint)
+    }
+    
+    /// Look up a registered asset by mint for updating
+    pub fn find_mut(&mut self, mint: &Pubkey) -> Option<&mut CollateralAsset> {
+        let count = self.asset_count as usize;
+        self.assets[..count].iter_mut().find(|asset| &asset.mint == mint)
+    }
+    
+    /// Register an asset, returning false if it is already listed or the registry is full
+    pub fn add(&mut self, asset: CollateralAsset) -> bool {
+        let count = self.asset_count as usize;
+        if count >= MAX_COLLATERAL_ASSETS || self.find(&asset.mint).is_some() {
+            return false;
+        }
+        
+        self.assets[count] = asset;
+        self.asset_count += 1;
+        true
+    }
+    
+    /// Total haircut-adjusted value of all collateral held, in ECE base units
+    pub fn total_value(&self, ece_decimals: u8) -> Option<u64> {
+        self.active_assets()
+            .iter()
+            .try_fold(0u64, |total, asset| total.checked_add(asset.collateral_value(asset.deposited, ece_decimals)?))
+    }
+}
+
+/// KYC status of a wallet, gating self-service minting
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct KycRecord {
+    /// Whether the record is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this record belongs to
+    pub treasury: Pubkey,
+    
+    /// Verified wallet
+    pub wallet: Pubkey,
+    
+    /// Whether the wallet currently passes KYC
+    pub verified: bool,
+    
+    /// Timestamp of the last status change
+    pub updated_at: i64,
+    
+    /// Verification expiry timestamp (0 for no expiry)
+    pub expires_at: i64,
+    
+    /// USDC minted during `mint_day`
+    pub daily_minted: u64,
+    
+    /// Day index (unix time / 86400) of `daily_minted`
+    pub mint_day: i64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl KycRecord {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // wallet
+        1 + // verified
+        8 + // updated_at
+        8 + // expires_at
+        8 + // daily_minted
+        8 + // mint_day
+        1; // bump
+    
+    /// Check if the wallet may mint at the given time
+    pub fn is_eligible(&self, current_timestamp: i64) -> bool {
+        self.verified && (self.expires_at == 0 || current_timestamp < self.expires_at)
+    }
+    
+    /// USDC minted so far today
+    pub fn minted_today(&self, current_timestamp: i64) -> u64 {
+        if current_timestamp.div_euclid(86400) == self.mint_day {
+            self.daily_minted
+        } else {
+            0
+        }
+    }
+    
+    /// Check if minting `usdc_amount` more today would exceed `daily_limit`
+    pub fn would_exceed_daily_mint(&self, usdc_amount: u64, daily_limit: u64, current_timestamp: i64) -> bool {
+        match self.minted_today(current_timestamp).checked_add(usdc_amount) {
+            Some(total) => total > daily_limit,
+            None => true,
+        }
+    }
+    
+    /// Record a mint towards today's total
+    pub fn record_mint(&mut self, usdc_amount: u64, current_timestamp: i64) {
+        self.daily_minted = self.minted_today(current_timestamp).saturating_add(usdc_amount);
+        self.mint_day = current_timestamp.div_euclid(86400);
+    }
+}
+
+/// Latest published proof-of-reserves snapshot: a Merkle sum tree root over all
+/// ECE holder balances next to the reserves recorded at publication
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ReserveAttestation {
+    /// Whether the attestation is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this attestation belongs to
+    pub treasury: Pubkey,
+    
+    /// Root of the holder balance Merkle sum tree
+    pub merkle_root: [u8; 32],
+    
+    /// Sum of all holder balances in the tree (ECE base units)
+    pub total_balances: u64,
+    
+    /// Number of holders in the tree
+    pub holder_count: u32,
+    
+    /// Slot the holder balances were read at
+    pub snapshot_slot: u64,
+    
+    /// USDC reserves when the snapshot was published
+    pub usdc_reserves: u64,
+    
+    /// Non-USDC collateral reserves (ECE units) when the snapshot was published
+    pub collateral_reserves: u64,
+    
+    /// Recorded ECE circulation when the snapshot was published
+    pub ece_circulation: u64,
+    
+    /// Publication timestamp
+    pub published_at: i64,
+    
+    /// Auditor that published the snapshot
+    pub auditor: Pubkey,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ReserveAttestation {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // merkle_root
+        8 + // total_balances
+        4 + // holder_count
+        8 + // snapshot_slot
+        8 + // usdc_reserves
+        8 + // collateral_reserves
+        8 + // ece_circulation
+        8 + // published_at
+        32 + // auditor
+        1; // bump
+}
+
+/// ECE airdrop or prize campaign paid out against a Merkle root of claims
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MerkleDistributor {
+    /// Whether the distributor is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this distributor belongs to
+    pub treasury: Pubkey,
+    
+    /// Campaign id, part of the PDA seeds
+    pub distributor_id: u64,
+    
+    /// Root of the claim Merkle tree
+    pub merkle_root: [u8; 32],
+    
+    /// ECE mint paid out
+    pub ece_mint: Pubkey,
+    
+    /// Vault token account holding the unclaimed ECE
+    pub vault: Pubkey,
+    
+    /// ECE funded into the vault
+    pub total_amount: u64,
+    
+    /// ECE claimed so far
+    pub total_claimed: u64,
+    
+    /// Number of claims in the tree
+    pub num_claims: u32,
+    
+    /// Number of claims paid out
+    pub num_claimed: u32,
+    
+    /// Claims close and the remainder can be clawed back from this timestamp
+    pub expires_at: i64,
+    
+    /// Whether the unclaimed remainder has been clawed back
+    pub is_clawed_back: bool,
+    
+    /// PDA bump seed
+    pub bump: u8,
+    
+    /// One bit per claim index, set once claimed
+    pub claimed_bitmap: Vec<u8>,
+}
+
+impl MerkleDistributor {
+    pub const BASE_LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        8 + // distributor_id
+        32 + // merkle_root
+        32 + // ece_mint
+        32 + // vault
+        8 + // total_amount
+        8 + // total_claimed
+        4 + // num_claims
+        4 + // num_claimed
+        8 + // expires_at
+        1 + // is_clawed_back
+        1 + // bump
+        4; // claimed_bitmap length
+    
+    /// Bitmap bytes needed for `num_claims` claims
+    pub fn bitmap_len(num_claims: u32) -> usize {
+        (num_claims as usize).div_ceil(8)
+    }
+    
+    /// Account size for a distributor with `num_claims` claims
+    pub fn space(num_claims: u32) -> usize {
+        Self::BASE_LEN + Self::bitmap_len(num_claims)
+    }
+    
+    /// Check if claims are closed
+    pub fn is_expired(&self, current_timestamp: i64) -> bool {
+        current_timestamp >= self.expires_at
+    }
+    
+    /// Check if the claim at `index` has been paid out
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed_bitmap
+            .get(index as usize / 8)
+            .is_some_and(|byte| byte & (1 << (index % 8)) != 0)
+    }
+    
+    /// Mark the claim at `index` as paid out; false if it is out of range or already claimed
+    pub fn set_claimed(&mut self, index: u32) -> bool {
+        if index >= self.num_claims || self.is_claimed(index) {
+            return false;
+        }
+        
+        self.claimed_bitmap[index as usize / 8] |= 1 << (index % 8);
+        self.num_claimed += 1;
+        true
+    }
+}
+
+/// Team or partner ECE allocation released linearly after a cliff
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct VestingSchedule {
+    /// Whether the schedule is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this schedule belongs to
+    pub treasury: Pubkey,
+    
+    /// Wallet the ECE vests to
+    pub beneficiary: Pubkey,
+    
+    /// Schedule id, part of the PDA seeds
+    pub vesting_id: u64,
+    
+    /// ECE mint held in the vault
+    pub ece_mint: Pubkey,
+    
+    /// Vault token account holding the unreleased ECE
+    pub vault: Pubkey,
+    
+    /// ECE allocated; reduced to the vested amount if revoked
+    pub total_amount: u64,
+    
+    /// ECE released to the beneficiary so far
+    pub released_amount: u64,
+    
+    /// Linear vesting starts from this timestamp
+    pub start_timestamp: i64,
+    
+    /// Nothing is releasable before this timestamp
+    pub cliff_timestamp: i64,
+    
+    /// Everything is vested from this timestamp
+    pub end_timestamp: i64,
+    
+    /// Whether the treasury multisig can revoke the unvested remainder
+    pub revocable: bool,
+    
+    /// Revocation timestamp (0 if not revoked)
+    pub revoked_at: i64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl VestingSchedule {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // beneficiary
+        8 + // vesting_id
+        32 + // ece_mint
+        32 + // vault
+        8 + // total_amount
+        8 + // released_amount
+        8 + // start_timestamp
+        8 + // cliff_timestamp
+        8 + // end_timestamp
+        1 + // revocable
+        8 + // revoked_at
+        1; // bump
+    
+    /// Check that the timestamps describe a cliff within a non-empty vesting period
+    pub fn is_valid_schedule(start_timestamp: i64, cliff_timestamp: i64, end_timestamp: i64) -> bool {
+        start_timestamp <= cliff_timestamp && cliff_timestamp <= end_timestamp && start_timestamp < end_timestamp
+    }
+    
+    /// Check if the schedule has been revoked
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at != 0
+    }
+    
+    /// ECE vested at the given time, rounded down
+    pub fn vested_amount(&self, current_timestamp: i64) -> u64 {
+        if self.is_revoked() || current_timestamp >= self.end_timestamp {
+            return self.total_amount;
+        }
+        
+        if current_timestamp < self.cliff_timestamp {
+            return 0;
+        }
+        
+        let elapsed = (current_timestamp - self.start_timestamp) as u128;
+        let duration = (self.end_timestamp - self.start_timestamp) as u128;
+        (self.total_amount as u128 * elapsed / duration) as u64
+    }
+    
+    /// ECE not yet vested at the given time
+    pub fn unvested_amount(&self, current_timestamp: i64) -> u64 {
+        self.total_amount - self.vested_amount(current_timestamp)
+    }
+    
+    /// Vested ECE not yet released at the given time
+    pub fn releasable_amount(&self, current_timestamp: i64) -> u64 {
+        self.vested_amount(current_timestamp).saturating_sub(self.released_amount)
+    }
+}
+
+/// Referral program settings and the vault rewards are paid from
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ReferralConfig {
+    /// Whether the config is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this config belongs to
+    pub treasury: Pubkey,
+    
+    /// ECE vault funding the rewards
+    pub vault: Pubkey,
+    
+    /// Reward in basis points of the ECE minted by a referred user
+    pub reward_bps: u16,
+    
+    /// Lifetime reward cap per referrer
+    pub max_reward_per_referrer: u64,
+    
+    /// Rewards accrued to referrers
+    pub total_accrued: u64,
+    
+    /// Rewards claimed by referrers
+    pub total_claimed: u64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ReferralConfig {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // vault
+        2 + // reward_bps
+        8 + // max_reward_per_referrer
+        8 + // total_accrued
+        8 + // total_claimed
+        1; // bump
+    
+    /// Reward for a referred mint of `ece_amount`, rounded down
+    pub fn reward_for(&self, ece_amount: u64) -> u64 {
+        (ece_amount as u128 * self.reward_bps as u128 / 10000) as u64
+    }
+    
+    /// Rewards accrued but not yet claimed, which the vault must keep back
+    pub fn outstanding(&self) -> u64 {
+        self.total_accrued.saturating_sub(self.total_claimed)
+    }
+}
+
+/// Link from a referred user to their referrer, set once at registration
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ReferralRecord {
+    /// Whether the record is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this record belongs to
+    pub treasury: Pubkey,
+    
+    /// Referred user
+    pub user: Pubkey,
+    
+    /// User who referred them
+    pub referrer: Pubkey,
+    
+    /// Registration timestamp
+    pub registered_at: i64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ReferralRecord {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // user
+        32 + // referrer
+        8 + // registered_at
+        1; // bump
+}
+
+/// A referrer's referral count and reward balance
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ReferrerAccount {
+    /// Whether the account is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this account belongs to
+    pub treasury: Pubkey,
+    
+    /// Referrer wallet
+    pub referrer: Pubkey,
+    
+    /// Number of users registered with this referrer
+    pub referral_count: u32,
+    
+    /// Lifetime rewards accrued
+    pub total_earned: u64,
+    
+    /// Rewards accrued but not yet claimed
+    pub claimable: u64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ReferrerAccount {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // referrer
+        4 + // referral_count
+        8 + // total_earned
+        8 + // claimable
+        1; // bump
+    
+    /// Accrue up to `reward` without passing the lifetime `cap`, returning the amount accrued
+    pub fn accrue(&mut self, reward: u64, cap: u64) -> u64 {
+        let amount = reward.min(cap.saturating_sub(self.total_earned));
+        self.total_earned += amount;
+        self.claimable += amount;
+        amount
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeOfferStatus {
+    Open,
+    Filled,
+    Cancelled,
+}
+
+/// Peer-to-peer card trade: the maker escrows a card and/or ECE and names what
+/// they want back. Unset card mints are `Pubkey::default()`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TradeOffer {
+    /// Whether the offer is initialized
+    pub is_initialized: bool,
+    
+    /// Treasury state account this offer belongs to
+    pub treasury: Pubkey,
+    
+    /// Wallet that made the offer
+    pub maker: Pubkey,
+    
+    /// Offer id, part of the PDA seeds
+    pub offer_id: u64,
+    
+    /// ECE mint of both ECE legs
+    pub ece_mint: Pubkey,
+    
+    /// Token program of both card legs
+    pub card_token_program: Pubkey,
+    
+    /// Card escrowed by the maker
+    pub offered_card_mint: Pubkey,
+    
+    /// ECE escrowed by the maker
+    pub offered_ece_amount: u64,
+    
+    /// Card the maker wants
+    pub requested_card_mint: Pubkey,
+    
+    /// ECE the maker wants
+    pub requested_ece_amount: u64,
+    
+    /// Only wallet allowed to fill (default for anyone)
+    pub taker: Pubkey,
+    
+    /// Offer can't be filled from this timestamp (0 for no expiry)
+    pub expires_at: i64,
+    
+    /// Offer status
+    pub status: TradeOfferStatus,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl TradeOffer {
+    pub const LEN: usize = 1 + // is_initialized
+        32 + // treasury
+        32 + // maker
+        8 + // offer_id
+        32 + // ece_mint
+        32 + // card_token_program
+        32 + // offered_card_mint
+        8 + // offered_ece_amount
+        32 + // requested_card_mint
+        8 + // requested_ece_amount
+        32 + // taker
+        8 + // expires_at
+        1 + // status
+        8 + // created_at
+        1; // bump
+    
+    /// Check that both sides are non-empty, at least one card changes hands and
+    /// the maker isn't asking for the card they escrow
+    pub fn is_valid_terms(
+        offered_card_mint: &Pubkey,
+        offered_ece_amount: u64,
+        requested_card_mint: &Pubkey,
+        requested_ece_amount: u64,
+    ) -> bool {
+        let offers_card = offered_card_mint != &Pubkey::default();
+        let requests_card = requested_card_mint != &Pubkey::default();
+        
+        (offers_card || offered_ece_amount > 0)
+            && (requests_card || requested_ece_amount > 0)
+            && (offers_card || requests_card)
+            && !(offers_card && offered_card_mint == requested_card_mint)
+    }
+    
+    pub fn offers_card(&self) -> bool {
+        self.offered_card_mint != Pubkey::default()
+    }
+    
+    pub fn requests_card(&self) -> bool {
+        self.requested_card_mint != Pubkey::default()
+    }
+    
+    /// Check if the offer can be filled by `taker` at the given time
+    pub fn is_fillable_by(&self, taker: &Pubkey, current_timestamp: i64) -> bool {
+        self.status == TradeOfferStatus::Open
+            && (self.taker == Pubkey::default() || &self.taker == taker)
+            && (self.expires_at == 0 || current_timestamp < self.expires_at)
+            && taker != &self.maker
+    }
+    
+    /// Platform fee withheld from an ECE leg, rounded down
+    pub fn fee_for(ece_amount: u64, fee_bps: u16) -> u64 {
+        (ece_amount as u128 * fee_bps.min(10000) as u128 / 10000) as u64
+    }
+}
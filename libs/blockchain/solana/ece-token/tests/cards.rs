@@ -0,0 +1,73 @@
+use borsh::BorshSerialize;
+use ece_token::client::EceTokenClient;
+use ece_token::state::{CardCollection, CardRecord};
+use solana_program::pubkey::Pubkey;
+
+fn card_record(collection: Pubkey, card_id: [u8; 32]) -> CardRecord {
+    CardRecord {
+        is_initialized: true,
+        collection,
+        mint: Pubkey::new_unique(),
+        card_id,
+        minted_to: Pubkey::new_unique(),
+        minted_at: 1_700_000_000,
+        serial_number: 1,
+    }
+}
+
+#[test]
+fn card_accounts_serialize_to_their_declared_length() {
+    let record = card_record(Pubkey::new_unique(), [7; 32]);
+    let collection = CardCollection {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        mint_fee: 1_000_000,
+        cards_minted: 42,
+        bump: 255,
+    };
+
+    assert_eq!(record.try_to_vec().unwrap().len(), CardRecord::LEN);
+    assert_eq!(collection.try_to_vec().unwrap().len(), CardCollection::LEN);
+}
+
+#[test]
+fn each_card_id_gets_its_own_mint_and_record() {
+    let program_id = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let (collection, _) = EceTokenClient::find_card_collection_address(&program_id, &treasury);
+
+    let (first_mint, _) = EceTokenClient::find_card_mint_address(&program_id, &collection, &[1; 32]);
+    let (second_mint, _) = EceTokenClient::find_card_mint_address(&program_id, &collection, &[2; 32]);
+    assert_ne!(first_mint, second_mint);
+
+    let (first_record, _) = EceTokenClient::find_card_record_address(&program_id, &first_mint);
+    let (second_record, _) = EceTokenClient::find_card_record_address(&program_id, &second_mint);
+    assert_ne!(first_record, second_record);
+}
+
+#[test]
+fn mint_card_needs_a_minter_signature_and_the_ece_token_program() {
+    let program_id = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let minter = Pubkey::new_unique();
+    let ece_mint = Pubkey::new_unique();
+
+    let instruction = EceTokenClient::mint_card(
+        &program_id,
+        &Pubkey::new_unique(),
+        &minter,
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &treasury,
+        &ece_mint,
+        &spl_token_2022::id(),
+        [9; 32],
+    )
+    .unwrap();
+
+    let minter_meta = instruction.accounts.iter().find(|meta| meta.pubkey == minter).unwrap();
+    assert!(minter_meta.is_signer);
+    assert_eq!(instruction.accounts[8].pubkey, spl_token_2022::id());
+    // The fee is moved with transfer_checked, which needs the ECE mint
+    assert_eq!(instruction.accounts[14].pubkey, ece_mint);
+}
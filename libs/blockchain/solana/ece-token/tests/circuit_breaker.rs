@@ -0,0 +1,327 @@
+mod common;
+
+use common::{add_backed_treasury, add_token_account, add_wallet, send, token_balance};
+use ece_token::client::EceTokenClient;
+use ece_token::error::EceTokenError;
+use ece_token::state::{CircuitBreakerReason, TreasuryState};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+const WINDOW: i64 = 86400;
+
+fn treasury(usdc_reserves: u64, ece_circulation: u64) -> TreasuryState {
+    TreasuryState {
+        is_initialized: true,
+        signers: vec![Pubkey::new_unique()],
+        threshold: 1,
+        ece_mint: Pubkey::new_unique(),
+        usdc_mint: Pubkey::new_unique(),
+        usdc_reserve: Pubkey::new_unique(),
+        ece_circulation,
+        usdc_reserves,
+        is_paused: false,
+        emergency_authority: Pubkey::new_unique(),
+        last_payout_timestamp: 0,
+        payout_window: 604800,
+        min_reserve_ratio: 10000,
+        company_revenue_account: Pubkey::new_unique(),
+        company_usdc_account: Pubkey::new_unique(),
+        total_revenue_processed: 0,
+        payout_count: 0,
+        compliance_authority: Pubkey::new_unique(),
+        max_transaction_amount: u64::MAX,
+        daily_volume_limit: u64::MAX,
+        current_daily_volume: 0,
+        last_volume_reset: 0,
+        marketplace_fee_bps: 250,
+        queued_redemptions: 0,
+        withdrawal_timelock_threshold: u64::MAX,
+        withdrawal_timelock_delay: 172800,
+        withdrawal_count: 0,
+        outflow_window: WINDOW,
+        max_window_outflow_bps: 2500,
+        reserve_floor_bps: 9500,
+        window_outflow: 0,
+        previous_window_outflow: 0,
+        outflow_window_start: 0,
+        breaker_trip_reason: CircuitBreakerReason::None,
+        breaker_tripped_at: 0,
+        pause_flags: 0,
+        pending_emergency_authority: Pubkey::default(),
+        pending_compliance_authority: Pubkey::default(),
+        min_signer_threshold: 1,
+        signer_epoch: 0,
+        usdc_oracle: Pubkey::default(),
+        max_peg_deviation_bps: 50,
+        max_oracle_staleness: 60,
+        mint_fee_bps: 0,
+        redeem_fee_bps: 0,
+        fee_usdc_account: Pubkey::default(),
+        total_fees_collected: 0,
+        ece_token_program: spl_token::id(),
+        collateral_reserves: 0,
+        ece_decimals: 6,
+        usdc_decimals: 6,
+        user_mint_limit: 10_000_000_000,
+        user_daily_mint_limit: 25_000_000_000,
+        reserve_mint_limit: 100_000_000_000,
+        fee_ece_account: Pubkey::default(),
+        window_withdrawals: 0,
+        pending_timelock_threshold: 0,
+        pending_timelock_delay: 0,
+        pending_timelock_at: 0,
+    }
+}
+
+#[test]
+fn trips_when_window_outflow_exceeds_share_of_reserves() {
+    let mut state = treasury(1_000_000, 1_000_000);
+
+    assert_eq!(state.circuit_breaker_check(200_000, 200_000, 10), None);
+    state.record_outflow(200_000);
+
+    // 200k already out plus 60k more exceeds 25% of the 1M reserves
+    assert_eq!(
+        state.circuit_breaker_check(60_000, 60_000, 20),
+        Some(CircuitBreakerReason::OutflowLimit)
+    );
+    assert_eq!(state.circuit_breaker_check(50_000, 50_000, 20), None);
+}
+
+#[test]
+fn previous_window_decays_across_the_sliding_window() {
+    let mut state = treasury(1_000_000, 1_000_000);
+    state.record_outflow(200_000);
+
+    // Halfway through the next window, half of the previous bucket still counts
+    state.roll_outflow_window(WINDOW + WINDOW / 2);
+    assert_eq!(state.previous_window_outflow, 200_000);
+    assert_eq!(state.window_outflow, 0);
+    assert_eq!(state.sliding_window_outflow(WINDOW + WINDOW / 2), 100_000);
+
+    // Two full windows later nothing is carried over
+    state.roll_outflow_window(3 * WINDOW);
+    assert_eq!(state.sliding_window_outflow(3 * WINDOW), 0);
+}
+
+#[test]
+fn trips_when_reserve_ratio_falls_below_floor() {
+    let mut state = treasury(1_000_000, 1_000_000);
+    state.max_window_outflow_bps = 0;
+
+    // Burns release reserves 1:1, keeping the ratio intact
+    assert_eq!(state.circuit_breaker_check(900_000, 900_000, 10), None);
+
+    // A bare withdrawal of 60k leaves 94% backing, under the 95% floor
+    assert_eq!(
+        state.circuit_breaker_check(60_000, 0, 10),
+        Some(CircuitBreakerReason::ReserveFloor)
+    );
+}
+
+#[test]
+fn tripping_pauses_and_records_reason() {
+    let mut state = treasury(1_000_000, 1_000_000);
+
+    state.trip_circuit_breaker(CircuitBreakerReason::OutflowLimit, 1234);
+
+    assert!(state.is_paused);
+    assert_eq!(state.breaker_trip_reason, CircuitBreakerReason::OutflowLimit);
+    assert_eq!(state.breaker_tripped_at, 1234);
+}
+
+#[test]
+fn reserve_ratio_counts_collateral_basket() {
+    let mut state = treasury(500_000, 1_000_000);
+    assert_eq!(state.reserve_ratio(), 5000);
+
+    state.collateral_reserves = 500_000;
+    assert_eq!(state.reserve_ratio(), 10000);
+    assert!(!state.would_breach_min_reserve_ratio(0));
+    assert!(state.would_breach_min_reserve_ratio(1));
+}
+
+#[test]
+fn usdc_redemptions_are_capped_at_the_usdc_share() {
+    let mut state = treasury(1_000_000, 1_000_000);
+    assert_eq!(state.usdc_redeemable_supply(), 1_000_000);
+
+    // Half the basket is other collateral, so only half the ECE redeems for USDC
+    state.ece_circulation = 2_000_000;
+    state.collateral_reserves = 1_000_000;
+    assert_eq!(state.usdc_redeemable_supply(), 1_000_000);
+    assert!(state.within_usdc_share(1_000_000));
+    assert!(!state.within_usdc_share(1_000_001));
+
+    // Queued tickets use up the share first
+    state.queued_redemptions = 400_000;
+    assert!(state.within_usdc_share(600_000));
+    assert!(!state.within_usdc_share(600_001));
+
+    state.usdc_reserves = 0;
+    assert_eq!(state.usdc_redeemable_supply(), 0);
+}
+
+#[test]
+fn reserves_convert_between_mint_decimals() {
+    // 9-decimal ECE backed by 6-decimal USDC
+    let mut state = treasury(1_000_000, 1_000_000_000);
+    state.ece_decimals = 9;
+    assert_eq!(state.reserve_ratio(), 10000);
+
+    // Redeeming 1.0000000009 ECE pays 1 USDC, rounded down
+    assert_eq!(state.ece_to_usdc(1_000_000_999), Some(1_000_000));
+    assert!(state.has_sufficient_reserves(1_000_000_999));
+
+    // Queued ECE earmarks USDC rounded up
+    state.queued_redemptions = 1;
+    assert_eq!(state.available_reserves(), 999_999);
+}
+
+#[test]
+fn minting_from_reserves_keeps_min_ratio() {
+    let mut state = treasury(1_000_000, 800_000);
+    state.min_reserve_ratio = 8000;
+
+    // 1,000,000 reserves back up to 1,250,000 ECE at 80%
+    assert!(state.can_mint_from_reserves(450_000));
+    assert!(!state.can_mint_from_reserves(450_001));
+    assert!(!state.can_mint_from_reserves(u64::MAX));
+}
+
+#[test]
+fn crank_check_only_trips_on_the_current_state() {
+    let mut state = treasury(1_000_000, 1_000_000);
+
+    // Within limits, nothing to trip without a new outflow
+    assert_eq!(state.circuit_breaker_check(0, 0, 10), None);
+
+    // Reserves fell to 94% of circulation, under the 95% floor
+    state.usdc_reserves = 940_000;
+    assert_eq!(
+        state.circuit_breaker_check(0, 0, 10),
+        Some(CircuitBreakerReason::ReserveFloor)
+    );
+}
+
+#[tokio::test]
+async fn outflow_past_the_limit_trips_the_breaker_and_unpausing_keeps_the_window() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let holder = add_wallet(&mut program_test);
+    let pauser = add_wallet(&mut program_test);
+
+    // At most 1 USDC may leave per window
+    let mut state = common::treasury_state(vec![Pubkey::new_unique()], 1);
+    state.ece_circulation = 10_000_000;
+    state.usdc_reserves = 10_000_000;
+    state.max_window_outflow_bps = 1000;
+    state.emergency_authority = pauser.pubkey();
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+    let source = add_token_account(&mut program_test, &treasury.ece_mint, &holder.pubkey(), 2_000_000);
+    let destination = add_token_account(&mut program_test, &treasury.usdc_mint, &holder.pubkey(), 0);
+
+    let mut context = program_test.start_with_context().await;
+
+    let burn = |amount| {
+        EceTokenClient::burn_tokens(
+            &program_id,
+            &holder.pubkey(),
+            &treasury.ece_mint,
+            &source,
+            &destination,
+            &treasury.usdc_reserve,
+            &treasury.address,
+            amount,
+        )
+        .unwrap()
+    };
+
+    send(&mut context, &[burn(800_000)], &[&holder]).await.unwrap();
+
+    // The burn that would cross the limit pauses the treasury instead of paying out
+    send(&mut context, &[burn(300_000)], &[&holder]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &source).await, 1_200_000);
+    assert_eq!(token_balance(&mut context, &destination).await, 800_000);
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert!(state.is_paused);
+    assert_eq!(state.breaker_trip_reason, CircuitBreakerReason::OutflowLimit);
+    assert_eq!(state.window_outflow, 800_000);
+
+    let result = send(&mut context, &[burn(100_000)], &[&holder]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::EmergencyPauseActive as u32);
+
+    let unpause = EceTokenClient::emergency_unpause(&program_id, &pauser.pubkey(), &treasury.address).unwrap();
+    send(&mut context, &[unpause], &[&pauser]).await.unwrap();
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert!(!state.is_paused);
+    assert_eq!(state.breaker_trip_reason, CircuitBreakerReason::None);
+    assert_eq!(state.window_outflow, 800_000);
+
+    // The earlier outflow still counts, so the same burn trips the breaker again
+    send(&mut context, &[burn(300_000)], &[&holder]).await.unwrap();
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert!(state.is_paused);
+    assert_eq!(token_balance(&mut context, &source).await, 1_200_000);
+}
+
+#[tokio::test]
+async fn burns_beyond_the_holders_balance_cannot_trip_the_breaker() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let holder = add_wallet(&mut program_test);
+
+    let mut state = common::treasury_state(vec![Pubkey::new_unique()], 1);
+    state.ece_circulation = 10_000_000;
+    state.usdc_reserves = 10_000_000;
+    state.max_window_outflow_bps = 1000;
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+    let source = add_token_account(&mut program_test, &treasury.ece_mint, &holder.pubkey(), 100_000);
+    let destination = add_token_account(&mut program_test, &treasury.usdc_mint, &holder.pubkey(), 0);
+
+    let mut context = program_test.start_with_context().await;
+
+    let burn = EceTokenClient::burn_tokens(
+        &program_id,
+        &holder.pubkey(),
+        &treasury.ece_mint,
+        &source,
+        &destination,
+        &treasury.usdc_reserve,
+        &treasury.address,
+        5_000_000,
+    )
+    .unwrap();
+    let result = send(&mut context, &[burn], &[&holder]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InsufficientFunds as u32);
+
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert!(!state.is_paused);
+}
+
+#[tokio::test]
+async fn crank_pauses_a_treasury_below_its_reserve_floor() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+
+    let mut healthy = common::treasury_state(vec![Pubkey::new_unique()], 1);
+    healthy.ece_circulation = 10_000_000;
+    healthy.usdc_reserves = 10_000_000;
+    healthy.reserve_floor_bps = 9000;
+    let mut short = healthy.clone();
+    short.usdc_reserves = 8_000_000;
+    let healthy = add_backed_treasury(&mut program_test, &program_id, healthy);
+    let short = add_backed_treasury(&mut program_test, &program_id, short);
+
+    let mut context = program_test.start_with_context().await;
+
+    let crank = EceTokenClient::trip_circuit_breaker(&program_id, &healthy.address).unwrap();
+    let result = send(&mut context, &[crank], &[]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::CircuitBreakerWithinLimits as u32);
+
+    let crank = EceTokenClient::trip_circuit_breaker(&program_id, &short.address).unwrap();
+    send(&mut context, &[crank], &[]).await.unwrap();
+    let state: TreasuryState = common::load_state(&mut context, &short.address).await;
+    assert!(state.is_paused);
+    assert_eq!(state.breaker_trip_reason, CircuitBreakerReason::ReserveFloor);
+}
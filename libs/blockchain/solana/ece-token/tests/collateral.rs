@@ -0,0 +1,176 @@
+mod common;
+
+use common::{add_backed_treasury, add_kyc_record, add_mint, add_state_account, add_token_account_at, add_wallet, send};
+use ece_token::client::EceTokenClient;
+use ece_token::error::EceTokenError;
+use ece_token::state::{CollateralAsset, CollateralRegistry, KycRecord, MAX_COLLATERAL_ASSETS};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+fn asset(decimals: u8, haircut_bps: u16, cap: u64) -> CollateralAsset {
+    CollateralAsset {
+        mint: Pubkey::new_unique(),
+        reserve: Pubkey::new_unique(),
+        decimals,
+        cap,
+        haircut_bps,
+        deposited: 0,
+        is_active: true,
+    }
+}
+
+fn registry() -> CollateralRegistry {
+    CollateralRegistry {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        asset_count: 0,
+        assets: [CollateralAsset::default(); MAX_COLLATERAL_ASSETS],
+        bump: 255,
+    }
+}
+
+#[test]
+fn normalizes_to_ece_decimals_rounding_down() {
+    // 18-decimal collateral into 6-decimal ECE drops the dust
+    let wide = asset(18, 0, u64::MAX);
+    assert_eq!(wide.normalize(1_999_999_999_999_999_999, 6), Some(1_999_999));
+
+    // 2-decimal collateral scales up exactly
+    let narrow = asset(2, 0, u64::MAX);
+    assert_eq!(narrow.normalize(150, 6), Some(1_500_000));
+
+    // Scaling past u64 is rejected rather than wrapped
+    assert_eq!(narrow.normalize(u64::MAX, 6), None);
+}
+
+#[test]
+fn applies_haircut_to_value() {
+    let usdt = asset(6, 200, u64::MAX);
+
+    assert_eq!(usdt.collateral_value(1_000_000, 6), Some(980_000));
+    assert_eq!(usdt.collateral_value(1, 6), Some(0));
+}
+
+#[test]
+fn enforces_cap() {
+    let mut pyusd = asset(6, 0, 1_000);
+    pyusd.deposited = 900;
+
+    assert!(pyusd.has_capacity(100));
+    assert!(!pyusd.has_capacity(101));
+}
+
+#[test]
+fn values_basket_and_rejects_duplicates() {
+    let mut registry = registry();
+    let mut usdt = asset(6, 100, u64::MAX);
+    usdt.deposited = 1_000_000;
+    let mut pyusd = asset(9, 0, u64::MAX);
+    pyusd.deposited = 2_000_000_000;
+
+    assert!(registry.add(usdt));
+    assert!(registry.add(pyusd));
+    assert!(!registry.add(usdt));
+
+    assert_eq!(registry.total_value(6), Some(990_000 + 2_000_000));
+}
+
+struct CollateralFixture {
+    treasury: Pubkey,
+    ece_mint: Pubkey,
+    collateral_mint: Pubkey,
+}
+
+/// A treasury accepting a 6-decimal stablecoin without haircut, and a user holding
+/// 10 of it with an empty ECE account
+fn add_collateral_treasury(
+    program_test: &mut ProgramTest,
+    program_id: &Pubkey,
+    user: &Keypair,
+    daily_mint_limit: u64,
+) -> CollateralFixture {
+    let mut state = common::treasury_state(vec![Pubkey::new_unique()], 1);
+    state.user_daily_mint_limit = daily_mint_limit;
+    let treasury = add_backed_treasury(program_test, program_id, state);
+
+    let collateral_mint = add_mint(program_test, &Pubkey::new_unique(), 6, u64::MAX / 2);
+    let (collateral_reserve, _) =
+        EceTokenClient::find_collateral_reserve_address(program_id, &treasury.address, &collateral_mint);
+    add_token_account_at(program_test, collateral_reserve, &collateral_mint, &collateral_reserve, 0);
+
+    let (registry_address, bump) = EceTokenClient::find_collateral_registry_address(program_id, &treasury.address);
+    let mut collateral_registry = CollateralRegistry {
+        is_initialized: true,
+        treasury: treasury.address,
+        asset_count: 0,
+        assets: [CollateralAsset::default(); MAX_COLLATERAL_ASSETS],
+        bump,
+    };
+    assert!(collateral_registry.add(CollateralAsset {
+        mint: collateral_mint,
+        reserve: collateral_reserve,
+        ..asset(6, 0, u64::MAX)
+    }));
+    add_state_account(program_test, program_id, registry_address, &collateral_registry, CollateralRegistry::LEN);
+
+    let user_collateral = get_associated_token_address(&user.pubkey(), &collateral_mint);
+    add_token_account_at(program_test, user_collateral, &collateral_mint, &user.pubkey(), 10_000_000);
+    let user_ece = get_associated_token_address(&user.pubkey(), &treasury.ece_mint);
+    add_token_account_at(program_test, user_ece, &treasury.ece_mint, &user.pubkey(), 0);
+
+    CollateralFixture {
+        treasury: treasury.address,
+        ece_mint: treasury.ece_mint,
+        collateral_mint,
+    }
+}
+
+fn mint_with_collateral(program_id: &Pubkey, user: &Keypair, fixture: &CollateralFixture, amount: u64) -> Instruction {
+    EceTokenClient::mint_with_collateral(
+        program_id,
+        &user.pubkey(),
+        &fixture.treasury,
+        &fixture.collateral_mint,
+        &fixture.ece_mint,
+        &spl_token::id(),
+        &spl_token::id(),
+        amount,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn collateral_mints_require_kyc() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let user = add_wallet(&mut program_test);
+    let fixture = add_collateral_treasury(&mut program_test, &program_id, &user, u64::MAX);
+
+    let mut context = program_test.start_with_context().await;
+
+    let mint = mint_with_collateral(&program_id, &user, &fixture, 1_000_000);
+    let result = send(&mut context, &[mint], &[&user]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::KycNotVerified as u32);
+}
+
+#[tokio::test]
+async fn collateral_mints_count_against_the_daily_mint_limit() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let user = add_wallet(&mut program_test);
+    let fixture = add_collateral_treasury(&mut program_test, &program_id, &user, 1_500_000);
+    let kyc_record = add_kyc_record(&mut program_test, &program_id, &fixture.treasury, &user.pubkey());
+
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[mint_with_collateral(&program_id, &user, &fixture, 1_000_000)], &[&user])
+        .await
+        .unwrap();
+    let record: KycRecord = common::load_state(&mut context, &kyc_record).await;
+    assert_eq!(record.daily_minted, 1_000_000);
+
+    let result = send(&mut context, &[mint_with_collateral(&program_id, &user, &fixture, 1_000_000)], &[&user]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::ComplianceCheckFailed as u32);
+}
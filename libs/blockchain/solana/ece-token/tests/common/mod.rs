@@ -0,0 +1,337 @@
+//! Shared `solana-program-test` setup for handler-level tests. Accounts are preloaded
+//! with the state each test needs, so a test only sends the instruction it covers.
+#![allow(dead_code)]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use ece_token::state::{
+    CircuitBreakerReason, KycRecord, TreasuryState, KYC_RECORD_SEED, MINT_AUTHORITY_SEED, TREASURY_AUTHORITY_SEED,
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+/// Program test running the ECE program natively under `program_id`
+pub fn program_test(program_id: &Pubkey) -> ProgramTest {
+    let mut program_test = ProgramTest::new(
+        "ece_token",
+        *program_id,
+        processor!(ece_token::process_instruction),
+    );
+    program_test.prefer_bpf(false);
+    program_test
+}
+
+/// Treasury with 6-decimal ECE and USDC, fully backed reserves and no limits in the way
+pub fn treasury_state(signers: Vec<Pubkey>, threshold: u8) -> TreasuryState {
+    TreasuryState {
+        is_initialized: true,
+        signers,
+        threshold,
+        ece_mint: Pubkey::default(),
+        usdc_mint: Pubkey::default(),
+        usdc_reserve: Pubkey::default(),
+        ece_circulation: 0,
+        usdc_reserves: 0,
+        is_paused: false,
+        emergency_authority: Pubkey::new_unique(),
+        last_payout_timestamp: 0,
+        payout_window: 604800,
+        min_reserve_ratio: 10000,
+        company_revenue_account: Pubkey::default(),
+        company_usdc_account: Pubkey::default(),
+        total_revenue_processed: 0,
+        payout_count: 0,
+        compliance_authority: Pubkey::new_unique(),
+        max_transaction_amount: u64::MAX,
+        daily_volume_limit: u64::MAX,
+        current_daily_volume: 0,
+        last_volume_reset: 0,
+        marketplace_fee_bps: 250,
+        queued_redemptions: 0,
+        withdrawal_timelock_threshold: u64::MAX,
+        withdrawal_timelock_delay: 172800,
+        withdrawal_count: 0,
+        outflow_window: 86400,
+        max_window_outflow_bps: 0,
+        reserve_floor_bps: 0,
+        window_outflow: 0,
+        previous_window_outflow: 0,
+        outflow_window_start: 0,
+        breaker_trip_reason: CircuitBreakerReason::None,
+        breaker_tripped_at: 0,
+        pause_flags: 0,
+        pending_emergency_authority: Pubkey::default(),
+        pending_compliance_authority: Pubkey::default(),
+        min_signer_threshold: threshold,
+        signer_epoch: 0,
+        usdc_oracle: Pubkey::default(),
+        max_peg_deviation_bps: 50,
+        max_oracle_staleness: 60,
+        mint_fee_bps: 0,
+        redeem_fee_bps: 0,
+        fee_usdc_account: Pubkey::default(),
+        total_fees_collected: 0,
+        ece_token_program: spl_token::id(),
+        collateral_reserves: 0,
+        ece_decimals: 6,
+        usdc_decimals: 6,
+        user_mint_limit: u64::MAX,
+        user_daily_mint_limit: u64::MAX,
+        reserve_mint_limit: u64::MAX,
+        fee_ece_account: Pubkey::default(),
+        window_withdrawals: 0,
+        pending_timelock_threshold: 0,
+        pending_timelock_delay: 0,
+        pending_timelock_at: 0,
+    }
+}
+
+/// Preload a program-owned account holding `state`, zero-padded to `len` bytes
+pub fn add_state_account<T: BorshSerialize>(
+    program_test: &mut ProgramTest,
+    program_id: &Pubkey,
+    address: Pubkey,
+    state: &T,
+    len: usize,
+) {
+    let mut data = state.try_to_vec().unwrap();
+    assert!(data.len() <= len, "state does not fit in {} bytes", len);
+    data.resize(len, 0);
+
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(len),
+            data,
+            owner: *program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// Preload the treasury state account
+pub fn add_treasury(program_test: &mut ProgramTest, program_id: &Pubkey, state: &TreasuryState) -> Pubkey {
+    let address = Pubkey::new_unique();
+    add_state_account(program_test, program_id, address, state, TreasuryState::LEN);
+    address
+}
+
+/// Accounts of a treasury preloaded by `add_backed_treasury`
+pub struct TreasuryFixture {
+    pub address: Pubkey,
+    pub ece_mint: Pubkey,
+    pub usdc_mint: Pubkey,
+    pub usdc_reserve: Pubkey,
+}
+
+/// Preload a treasury with its ECE and USDC mints and a USDC reserve holding
+/// `state.usdc_reserves`, owned by the treasury authority PDA
+pub fn add_backed_treasury(
+    program_test: &mut ProgramTest,
+    program_id: &Pubkey,
+    mut state: TreasuryState,
+) -> TreasuryFixture {
+    let (mint_authority, _) = Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], program_id);
+    let ece_mint = add_mint(program_test, &mint_authority, state.ece_decimals, state.ece_circulation);
+    let usdc_mint = add_mint(program_test, &Pubkey::new_unique(), state.usdc_decimals, u64::MAX / 2);
+    let usdc_reserve = add_token_account(
+        program_test,
+        &usdc_mint,
+        &treasury_authority(program_id),
+        state.usdc_reserves,
+    );
+
+    state.ece_mint = ece_mint;
+    state.usdc_mint = usdc_mint;
+    state.usdc_reserve = usdc_reserve;
+    let address = add_treasury(program_test, program_id, &state);
+
+    TreasuryFixture {
+        address,
+        ece_mint,
+        usdc_mint,
+        usdc_reserve,
+    }
+}
+
+/// Preload a verified, non-expiring KYC record for `wallet`
+pub fn add_kyc_record(
+    program_test: &mut ProgramTest,
+    program_id: &Pubkey,
+    treasury: &Pubkey,
+    wallet: &Pubkey,
+) -> Pubkey {
+    let (address, bump) =
+        Pubkey::find_program_address(&[KYC_RECORD_SEED, treasury.as_ref(), wallet.as_ref()], program_id);
+    let kyc_record = KycRecord {
+        is_initialized: true,
+        treasury: *treasury,
+        wallet: *wallet,
+        verified: true,
+        updated_at: 0,
+        expires_at: 0,
+        daily_minted: 0,
+        mint_day: 0,
+        bump,
+    };
+    add_state_account(program_test, program_id, address, &kyc_record, KycRecord::LEN);
+    address
+}
+
+/// Preload a classic SPL Token mint
+pub fn add_mint(program_test: &mut ProgramTest, mint_authority: &Pubkey, decimals: u8, supply: u64) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let mut data = vec![0; Mint::LEN];
+    Mint::pack(
+        Mint {
+            mint_authority: COption::Some(*mint_authority),
+            supply,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        },
+        &mut data,
+    )
+    .unwrap();
+
+    program_test.add_account(address, token_program_account(data));
+    address
+}
+
+/// Preload a classic SPL Token account at `address`
+pub fn add_token_account_at(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) {
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount::pack(
+        TokenAccount {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        },
+        &mut data,
+    )
+    .unwrap();
+
+    program_test.add_account(address, token_program_account(data));
+}
+
+/// Preload a classic SPL Token account
+pub fn add_token_account(program_test: &mut ProgramTest, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+    let address = Pubkey::new_unique();
+    add_token_account_at(program_test, address, mint, owner, amount);
+    address
+}
+
+fn token_program_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Preload a funded wallet
+pub fn add_wallet(program_test: &mut ProgramTest) -> Keypair {
+    let wallet = Keypair::new();
+    program_test.add_account(
+        wallet.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+    wallet
+}
+
+/// The treasury authority PDA that owns the USDC reserve
+pub fn treasury_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TREASURY_AUTHORITY_SEED], program_id).0
+}
+
+/// Sign and send `instructions` with the context payer and `signers`
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+/// The instruction error a failed transaction returned
+pub fn instruction_error(result: Result<(), BanksClientError>) -> InstructionError {
+    match result.expect_err("transaction should fail").unwrap() {
+        TransactionError::InstructionError(_, error) => error,
+        other => panic!("unexpected transaction error {:?}", other),
+    }
+}
+
+/// The program error a failed transaction returned, as its custom error code
+pub fn custom_error(result: Result<(), BanksClientError>) -> u32 {
+    match instruction_error(result) {
+        InstructionError::Custom(code) => code,
+        other => panic!("unexpected instruction error {:?}", other),
+    }
+}
+
+/// Current balance of a token account
+pub async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    let account = context.banks_client.get_account(*address).await.unwrap().unwrap();
+    TokenAccount::unpack(&account.data[..TokenAccount::LEN]).unwrap().amount
+}
+
+/// Current data of a program account
+pub async fn account_data(context: &mut ProgramTestContext, address: &Pubkey) -> Vec<u8> {
+    context.banks_client.get_account(*address).await.unwrap().unwrap().data
+}
+
+/// Rewrite the state of a program account in place, keeping any padding after it
+pub async fn update_state<T: BorshDeserialize + BorshSerialize>(
+    context: &mut ProgramTestContext,
+    address: &Pubkey,
+    update: impl FnOnce(&mut T),
+) {
+    let mut account = context.banks_client.get_account(*address).await.unwrap().unwrap();
+    let mut state = T::deserialize(&mut account.data.as_slice()).unwrap();
+    update(&mut state);
+    state.serialize(&mut account.data.as_mut_slice()).unwrap();
+    context.set_account(address, &account.into());
+}
+
+/// Current state of a program account, ignoring any zero padding after it
+pub async fn load_state<T: BorshDeserialize>(context: &mut ProgramTestContext, address: &Pubkey) -> T {
+    T::deserialize(&mut account_data(context, address).await.as_slice()).unwrap()
+}
@@ -0,0 +1,22 @@
+use ece_token::state::convert_decimals;
+
+#[test]
+fn scales_up_exactly() {
+    assert_eq!(convert_decimals(1_500_000, 6, 9, false), Some(1_500_000_000));
+    assert_eq!(convert_decimals(1_500_000, 6, 9, true), Some(1_500_000_000));
+    assert_eq!(convert_decimals(42, 6, 6, false), Some(42));
+}
+
+#[test]
+fn scales_down_with_requested_rounding() {
+    assert_eq!(convert_decimals(1_999_999_999, 9, 6, false), Some(1_999_999));
+    assert_eq!(convert_decimals(1_999_999_999, 9, 6, true), Some(2_000_000));
+    assert_eq!(convert_decimals(2_000_000_000, 9, 6, true), Some(2_000_000));
+    assert_eq!(convert_decimals(999, 9, 6, false), Some(0));
+}
+
+#[test]
+fn rejects_overflow() {
+    assert_eq!(convert_decimals(u64::MAX, 6, 9, false), None);
+    assert_eq!(convert_decimals(1, 0, 40, false), None);
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use common::{add_backed_treasury, add_state_account, add_wallet, send, TreasuryFixture};
+use ece_token::client::EceTokenClient;
+use ece_token::error::EceTokenError;
+use ece_token::state::{Denylist, StakingPool, MAX_DENYLISTED_WALLETS};
+use solana_program::{instruction::Instruction, pubkey::Pubkey, rent::Rent};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+use spl_token_2022::{
+    extension::{transfer_hook::TransferHookAccount, ExtensionType, StateWithExtensionsMut},
+    state::{Account as TokenAccount, AccountState},
+};
+use spl_transfer_hook_interface::{get_extra_account_metas_address, instruction::execute};
+
+fn denylist() -> Denylist {
+    Denylist {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        wallet_count: 0,
+        wallets: [Pubkey::default(); MAX_DENYLISTED_WALLETS],
+        bump: 255,
+    }
+}
+
+#[test]
+fn adding_twice_keeps_one_entry() {
+    let mut denylist = denylist();
+    let wallet = Pubkey::new_unique();
+
+    assert!(denylist.add(&wallet));
+    assert!(denylist.add(&wallet));
+
+    assert!(denylist.contains(&wallet));
+    assert!(!denylist.contains(&Pubkey::new_unique()));
+    assert_eq!(denylist.wallet_count, 1);
+}
+
+#[test]
+fn removing_keeps_remaining_wallets() {
+    let mut denylist = denylist();
+    let first = Pubkey::new_unique();
+    let second = Pubkey::new_unique();
+    denylist.add(&first);
+    denylist.add(&second);
+
+    denylist.remove(&first);
+
+    assert!(!denylist.contains(&first));
+    assert!(denylist.contains(&second));
+    assert_eq!(denylist.wallet_count, 1);
+}
+
+#[test]
+fn rejects_wallets_once_full() {
+    let mut denylist = denylist();
+    for _ in 0..MAX_DENYLISTED_WALLETS {
+        assert!(denylist.add(&Pubkey::new_unique()));
+    }
+
+    assert!(!denylist.add(&Pubkey::new_unique()));
+    assert_eq!(denylist.wallet_count as usize, MAX_DENYLISTED_WALLETS);
+}
+
+/// Preload a Token-2022 ECE account that is mid-transfer, as Token-2022 leaves it while
+/// calling the hook
+fn add_transferring_account(program_test: &mut ProgramTest, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let len = ExtensionType::try_calculate_account_len::<TokenAccount>(&[ExtensionType::TransferHookAccount]).unwrap();
+    let mut data = vec![0; len];
+    let mut state = StateWithExtensionsMut::<TokenAccount>::unpack_uninitialized(&mut data).unwrap();
+    state.base = TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount: 10_000_000,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    state.init_extension::<TransferHookAccount>(true).unwrap().transferring = true.into();
+
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(len),
+            data,
+            owner: spl_token_2022::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    address
+}
+
+/// Treasury capping transfers at 1 ECE, with the hook's extra account metas created by `admin`
+async fn start_with_hook(
+    program_test: ProgramTest,
+    program_id: &Pubkey,
+    treasury: &TreasuryFixture,
+    admin: &Keypair,
+) -> ProgramTestContext {
+    let mut context = program_test.start_with_context().await;
+    let initialize = EceTokenClient::initialize_transfer_hook_accounts(
+        program_id,
+        &admin.pubkey(),
+        &treasury.ece_mint,
+        &treasury.address,
+    );
+    send(&mut context, &[initialize], &[admin]).await.unwrap();
+    context
+}
+
+fn hook_execute(
+    program_id: &Pubkey,
+    treasury: &TreasuryFixture,
+    source: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut instruction = execute(
+        program_id,
+        source,
+        &treasury.ece_mint,
+        &Pubkey::new_unique(),
+        authority,
+        &get_extra_account_metas_address(&treasury.ece_mint, program_id),
+        amount,
+    );
+    // Treasury state and denylist, without the accounts Token-2022 itself needs
+    let hook_accounts = EceTokenClient::transfer_hook_accounts(program_id, &treasury.ece_mint, &treasury.address);
+    instruction.accounts.extend(hook_accounts.into_iter().take(2));
+    instruction
+}
+
+fn capped_treasury(program_test: &mut ProgramTest, program_id: &Pubkey, admin: &Pubkey) -> TreasuryFixture {
+    let mut state = common::treasury_state(vec![*admin], 1);
+    state.max_transaction_amount = 1_000_000;
+    add_backed_treasury(program_test, program_id, state)
+}
+
+fn staking_pool(treasury: &Pubkey, bump: u8) -> StakingPool {
+    StakingPool {
+        is_initialized: true,
+        treasury: *treasury,
+        ece_mint: Pubkey::new_unique(),
+        stake_vault: Pubkey::new_unique(),
+        reward_vault: Pubkey::new_unique(),
+        total_staked: 0,
+        total_weighted_stake: 0,
+        reward_rate: 0,
+        reward_period_end: 0,
+        reward_per_token_stored: 0,
+        last_update_timestamp: 0,
+        accrued_rewards: 0,
+        bump,
+    }
+}
+
+#[tokio::test]
+async fn hook_limits_transfers_between_wallets() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+    let wallet = Pubkey::new_unique();
+    let treasury = capped_treasury(&mut program_test, &program_id, &admin.pubkey());
+    let source = add_transferring_account(&mut program_test, &treasury.ece_mint, &wallet);
+
+    let mut context = start_with_hook(program_test, &program_id, &treasury, &admin).await;
+
+    send(&mut context, &[hook_execute(&program_id, &treasury, &source, &wallet, 1_000_000)], &[])
+        .await
+        .unwrap();
+    let result = send(&mut context, &[hook_execute(&program_id, &treasury, &source, &wallet, 1_000_001)], &[]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::ComplianceCheckFailed as u32);
+}
+
+#[tokio::test]
+async fn hook_lets_program_vaults_move_more_than_the_limit() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+    let treasury = capped_treasury(&mut program_test, &program_id, &admin.pubkey());
+
+    let (pool, bump) = EceTokenClient::find_staking_pool_address(&program_id, &treasury.address);
+    add_state_account(&mut program_test, &program_id, pool, &staking_pool(&treasury.address, bump), StakingPool::LEN);
+    let reward_vault = add_transferring_account(&mut program_test, &treasury.ece_mint, &pool);
+
+    // Program-owned look-alikes at other addresses are still wallets to the hook
+    let impostor = Pubkey::new_unique();
+    let look_alike = staking_pool(&treasury.address, bump);
+    add_state_account(&mut program_test, &program_id, impostor, &look_alike, StakingPool::LEN);
+    let impostor_account = add_transferring_account(&mut program_test, &treasury.ece_mint, &impostor);
+
+    let mut context = start_with_hook(program_test, &program_id, &treasury, &admin).await;
+
+    send(&mut context, &[hook_execute(&program_id, &treasury, &reward_vault, &pool, 5_000_000)], &[])
+        .await
+        .unwrap();
+    let result =
+        send(&mut context, &[hook_execute(&program_id, &treasury, &impostor_account, &impostor, 5_000_000)], &[]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::ComplianceCheckFailed as u32);
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::{add_backed_treasury, add_token_account, add_wallet, send, token_balance};
+use ece_token::client::EceTokenClient;
+use ece_token::error::EceTokenError;
+use ece_token::state::{FeeRecipient, FeeSplitConfig, TreasuryState, MAX_FEE_RECIPIENTS};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+fn recipients(shares: &[u16]) -> Vec<FeeRecipient> {
+    shares
+        .iter()
+        .map(|&share_bps| FeeRecipient {
+            usdc_account: Pubkey::new_unique(),
+            share_bps,
+        })
+        .collect()
+}
+
+fn config(recipients: &[FeeRecipient]) -> FeeSplitConfig {
+    let mut config = FeeSplitConfig {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        recipient_count: 0,
+        recipients: [FeeRecipient::default(); MAX_FEE_RECIPIENTS],
+        bump: 255,
+    };
+    config.set_recipients(recipients);
+    config
+}
+
+#[test]
+fn shares_must_sum_to_one_hundred_percent() {
+    assert!(FeeSplitConfig::validate_recipients(&recipients(&[7000, 2000, 1000])));
+    assert!(!FeeSplitConfig::validate_recipients(&recipients(&[7000, 2000])));
+    assert!(!FeeSplitConfig::validate_recipients(&recipients(&[7000, 2000, 1001])));
+}
+
+#[test]
+fn rejects_empty_oversized_zero_and_duplicate_tables() {
+    assert!(!FeeSplitConfig::validate_recipients(&[]));
+    assert!(!FeeSplitConfig::validate_recipients(&recipients(&[2000; 6])));
+    assert!(!FeeSplitConfig::validate_recipients(&recipients(&[10000, 0])));
+
+    let mut duplicated = recipients(&[5000, 5000]);
+    duplicated[1].usdc_account = duplicated[0].usdc_account;
+    assert!(!FeeSplitConfig::validate_recipients(&duplicated));
+}
+
+#[test]
+fn split_is_exact_when_divisible() {
+    let config = config(&recipients(&[7000, 2000, 1000]));
+    assert_eq!(config.split_amount(1_000_000).unwrap(), vec![700_000, 200_000, 100_000]);
+}
+
+#[test]
+fn rounding_dust_goes_to_first_recipient() {
+    let config = config(&recipients(&[3333, 3333, 3334]));
+    let shares = config.split_amount(100).unwrap();
+
+    assert_eq!(shares, vec![34, 33, 33]);
+    assert_eq!(shares.iter().sum::<u64>(), 100);
+}
+
+#[test]
+fn split_handles_max_amount() {
+    let config = config(&recipients(&[5000, 5000]));
+    let shares = config.split_amount(u64::MAX).unwrap();
+
+    assert_eq!(shares.iter().map(|&s| s as u128).sum::<u128>(), u64::MAX as u128);
+}
+
+#[test]
+fn serialized_size_matches_len() {
+    use borsh::BorshSerialize;
+
+    let config = config(&recipients(&[10000]));
+    assert_eq!(config.try_to_vec().unwrap().len(), FeeSplitConfig::LEN);
+}
+
+#[tokio::test]
+async fn payout_validates_its_accounts_and_pays_usdc_units() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+    let company_usdc_account = Pubkey::new_unique();
+
+    // 9-decimal ECE against 6-decimal USDC
+    let mut state = common::treasury_state(vec![admin.pubkey()], 1);
+    state.ece_decimals = 9;
+    state.ece_circulation = 100_000_000_000;
+    state.usdc_reserves = 100_000_000;
+    state.company_usdc_account = company_usdc_account;
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+    common::add_token_account_at(&mut program_test, company_usdc_account, &treasury.usdc_mint, &admin.pubkey(), 0);
+    let company_ece_account = add_token_account(&mut program_test, &treasury.ece_mint, &admin.pubkey(), 10_000_000_000);
+    let stray_usdc_account = add_token_account(&mut program_test, &treasury.usdc_mint, &program_id, 100_000_000);
+    let stray_mint = common::add_mint(&mut program_test, &admin.pubkey(), 9, 0);
+
+    let mut context = program_test.start_with_context().await;
+
+    let payout = |treasury_usdc_account: &Pubkey, ece_mint: &Pubkey| {
+        EceTokenClient::weekly_payout(
+            &program_id,
+            &admin.pubkey(),
+            &company_ece_account,
+            &company_usdc_account,
+            treasury_usdc_account,
+            &treasury.address,
+            ece_mint,
+            10_000_000_000,
+            50,
+        )
+        .unwrap()
+    };
+
+    let result = send(&mut context, &[payout(&stray_usdc_account, &treasury.ece_mint)], &[&admin]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InvalidTokenAccount as u32);
+
+    let result = send(&mut context, &[payout(&treasury.usdc_reserve, &stray_mint)], &[&admin]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InvalidTokenMint as u32);
+
+    send(&mut context, &[payout(&treasury.usdc_reserve, &treasury.ece_mint)], &[&admin]).await.unwrap();
+
+    // 5 ECE burned pays out 5 USDC, not 5,000
+    assert_eq!(token_balance(&mut context, &company_ece_account).await, 5_000_000_000);
+    assert_eq!(token_balance(&mut context, &company_usdc_account).await, 5_000_000);
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert_eq!(state.usdc_reserves, 95_000_000);
+    assert_eq!(state.ece_circulation, 95_000_000_000);
+}
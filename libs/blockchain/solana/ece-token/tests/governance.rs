@@ -0,0 +1,85 @@
+use ece_token::state::{
+    LockupTier, Proposal, StakeAccount, TreasuryParameter, TreasuryParameterChange,
+    MIN_GOVERNED_RESERVE_RATIO_BPS,
+};
+use solana_program::pubkey::Pubkey;
+
+fn proposal(snapshot_total_staked: u64, votes_for: u64, votes_against: u64) -> Proposal {
+    Proposal {
+        is_initialized: true,
+        proposal_id: 0,
+        proposer: Pubkey::new_unique(),
+        change: TreasuryParameterChange {
+            parameter: TreasuryParameter::MinReserveRatio,
+            value: 9000,
+        },
+        created_at: 1_000,
+        voting_ends_at: 2_000,
+        snapshot_total_staked,
+        votes_for,
+        votes_against,
+        executed: false,
+        bump: 255,
+    }
+}
+
+fn stake_at(amount: u64, last_stake_timestamp: i64) -> StakeAccount {
+    StakeAccount {
+        is_initialized: true,
+        pool: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        amount,
+        weighted_amount: amount,
+        lockup_tier: LockupTier::Flexible,
+        lockup_end: 0,
+        last_stake_timestamp,
+        reward_per_token_paid: 0,
+        pending_rewards: 0,
+        bump: 255,
+    }
+}
+
+#[test]
+fn quorum_counts_votes_on_both_sides() {
+    // 20% quorum of 1M staked needs 200k participating
+    assert!(!proposal(1_000_000, 150_000, 49_999).has_quorum(2000));
+    assert!(proposal(1_000_000, 150_000, 50_000).has_quorum(2000));
+    assert!(proposal(1_000_000, 0, 200_000).has_quorum(2000));
+}
+
+#[test]
+fn passing_needs_quorum_and_a_strict_majority() {
+    assert!(proposal(1_000_000, 150_000, 100_000).has_passed(2000));
+    assert!(!proposal(1_000_000, 125_000, 125_000).has_passed(2000));
+    assert!(!proposal(1_000_000, 100_000, 50_000).has_passed(2000));
+}
+
+#[test]
+fn voting_closes_at_the_deadline() {
+    let proposal = proposal(1_000_000, 0, 0);
+
+    assert!(proposal.is_voting_active(1_999));
+    assert!(!proposal.is_voting_active(2_000));
+}
+
+#[test]
+fn stake_added_at_or_after_creation_has_no_weight() {
+    assert_eq!(stake_at(5_000, 999).voting_weight_at(1_000), 5_000);
+    assert_eq!(stake_at(5_000, 1_000).voting_weight_at(1_000), 0);
+    assert_eq!(stake_at(5_000, 1_500).voting_weight_at(1_000), 0);
+}
+
+#[test]
+fn parameter_changes_are_bounded() {
+    let change = |parameter, value| TreasuryParameterChange { parameter, value };
+
+    assert!(change(TreasuryParameter::MinReserveRatio, 10000).is_valid());
+    assert!(!change(TreasuryParameter::MinReserveRatio, 10001).is_valid());
+    assert!(change(TreasuryParameter::MinReserveRatio, MIN_GOVERNED_RESERVE_RATIO_BPS as u64).is_valid());
+    assert!(!change(TreasuryParameter::MinReserveRatio, MIN_GOVERNED_RESERVE_RATIO_BPS as u64 - 1).is_valid());
+    assert!(!change(TreasuryParameter::MinReserveRatio, 0).is_valid());
+    assert!(!change(TreasuryParameter::DailyVolumeLimit, 0).is_valid());
+    assert!(!change(TreasuryParameter::PayoutWindow, u64::MAX).is_valid());
+    assert!(change(TreasuryParameter::MarketplaceFeeBps, 7500).is_valid());
+    assert!(!change(TreasuryParameter::MarketplaceFeeBps, 7501).is_valid());
+}
@@ -0,0 +1,55 @@
+use ece_token::state::KycRecord;
+use solana_program::pubkey::Pubkey;
+
+const DAY: i64 = 86400;
+
+fn kyc_record(verified: bool, expires_at: i64) -> KycRecord {
+    KycRecord {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        wallet: Pubkey::new_unique(),
+        verified,
+        updated_at: 0,
+        expires_at,
+        daily_minted: 0,
+        mint_day: 0,
+        bump: 255,
+    }
+}
+
+#[test]
+fn eligibility_requires_unexpired_verification() {
+    assert!(!kyc_record(false, 0).is_eligible(DAY));
+    assert!(kyc_record(true, 0).is_eligible(i64::MAX));
+
+    let record = kyc_record(true, 10 * DAY);
+    assert!(record.is_eligible(10 * DAY - 1));
+    assert!(!record.is_eligible(10 * DAY));
+}
+
+#[test]
+fn daily_mint_total_resets_each_day() {
+    let mut record = kyc_record(true, 0);
+    let now = 3 * DAY + 100;
+
+    record.record_mint(600, now);
+    assert!(!record.would_exceed_daily_mint(400, 1000, now));
+    assert!(record.would_exceed_daily_mint(401, 1000, now));
+
+    record.record_mint(400, now + 1);
+    assert_eq!(record.minted_today(now + 1), 1000);
+
+    // A new day starts from zero
+    assert_eq!(record.minted_today(4 * DAY), 0);
+    assert!(!record.would_exceed_daily_mint(1000, 1000, 4 * DAY));
+    record.record_mint(1, 4 * DAY);
+    assert_eq!(record.daily_minted, 1);
+}
+
+#[test]
+fn daily_mint_overflow_counts_as_exceeded() {
+    let mut record = kyc_record(true, 0);
+    record.record_mint(1, 0);
+
+    assert!(record.would_exceed_daily_mint(u64::MAX, u64::MAX, 0));
+}
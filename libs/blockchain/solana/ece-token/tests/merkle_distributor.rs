@@ -0,0 +1,71 @@
+use ece_token::merkle_distributor::{claim_leaf, verify_proof, DistributionTree};
+use ece_token::state::MerkleDistributor;
+use solana_program::pubkey::Pubkey;
+
+fn distributor(num_claims: u32) -> MerkleDistributor {
+    MerkleDistributor {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        distributor_id: 1,
+        merkle_root: [0; 32],
+        ece_mint: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        total_amount: 0,
+        total_claimed: 0,
+        num_claims,
+        num_claimed: 0,
+        expires_at: 1_000,
+        is_clawed_back: false,
+        bump: 255,
+        claimed_bitmap: vec![0; MerkleDistributor::bitmap_len(num_claims)],
+    }
+}
+
+#[test]
+fn every_claim_proves_against_root() {
+    let claims: Vec<(Pubkey, u64)> = (1..=5).map(|i| (Pubkey::new_unique(), i * 100)).collect();
+    let tree = DistributionTree::new(&claims);
+
+    assert_eq!(tree.total_amount(), Some(1_500));
+    assert_eq!(tree.num_claims(), 5);
+
+    for (index, (claimant, amount)) in claims.iter().enumerate() {
+        let proof = tree.proof(index as u32).unwrap();
+        assert!(verify_proof(&tree.root(), claim_leaf(index as u32, claimant, *amount), &proof));
+    }
+
+    assert!(tree.proof(5).is_none());
+}
+
+#[test]
+fn wrong_amount_index_or_claimant_fails() {
+    let claims: Vec<(Pubkey, u64)> = (1..=4).map(|i| (Pubkey::new_unique(), i * 100)).collect();
+    let tree = DistributionTree::new(&claims);
+    let (claimant, amount) = claims[1];
+    let proof = tree.proof(1).unwrap();
+
+    assert!(!verify_proof(&tree.root(), claim_leaf(1, &claimant, amount + 1), &proof));
+    assert!(!verify_proof(&tree.root(), claim_leaf(2, &claimant, amount), &proof));
+    assert!(!verify_proof(&tree.root(), claim_leaf(1, &claims[0].0, amount), &proof));
+}
+
+#[test]
+fn claimed_bitmap_prevents_double_claims() {
+    let mut distributor = distributor(10);
+    assert_eq!(MerkleDistributor::space(10), MerkleDistributor::BASE_LEN + 2);
+
+    assert!(distributor.set_claimed(9));
+    assert!(distributor.is_claimed(9));
+    assert!(!distributor.is_claimed(8));
+    assert!(!distributor.set_claimed(9));
+    assert!(!distributor.set_claimed(10));
+    assert_eq!(distributor.num_claimed, 1);
+}
+
+#[test]
+fn expiry_closes_claims() {
+    let distributor = distributor(1);
+
+    assert!(!distributor.is_expired(999));
+    assert!(distributor.is_expired(1_000));
+}
@@ -0,0 +1,71 @@
+use ece_token::{
+    error::EceTokenError,
+    oracle::{OraclePrice, PYTH_STATUS_TRADING},
+};
+
+fn usdc_price(price: i64, publish_time: i64) -> OraclePrice {
+    OraclePrice {
+        price,
+        confidence: 1_000,
+        exponent: -8,
+        status: PYTH_STATUS_TRADING,
+        publish_time,
+    }
+}
+
+#[test]
+fn round_trips_pyth_account_data() {
+    let price = usdc_price(99_990_000, 1_000);
+    let data = price.to_pyth_account_data();
+
+    assert_eq!(OraclePrice::from_pyth_account_data(&data).unwrap(), price);
+    assert_eq!(price.price_bps(), Some(9999));
+}
+
+#[test]
+fn rejects_non_price_accounts() {
+    let mut data = usdc_price(100_000_000, 1_000).to_pyth_account_data();
+    data[0] = 0;
+
+    assert!(matches!(
+        OraclePrice::from_pyth_account_data(&data),
+        Err(EceTokenError::InvalidOracleAccount)
+    ));
+    assert!(matches!(
+        OraclePrice::from_pyth_account_data(&[0u8; 16]),
+        Err(EceTokenError::InvalidOracleAccount)
+    ));
+}
+
+#[test]
+fn accepts_price_within_band() {
+    assert!(usdc_price(100_400_000, 1_000).check_peg(1_030, 60, 50).is_ok());
+    assert!(usdc_price(99_500_000, 1_000).check_peg(1_030, 60, 50).is_ok());
+}
+
+#[test]
+fn rejects_depeg_beyond_band() {
+    assert!(matches!(
+        usdc_price(99_400_000, 1_000).check_peg(1_030, 60, 50),
+        Err(EceTokenError::ConversionRateOutOfBounds)
+    ));
+    assert!(matches!(
+        usdc_price(0, 1_000).check_peg(1_030, 60, 50),
+        Err(EceTokenError::ConversionRateOutOfBounds)
+    ));
+}
+
+#[test]
+fn rejects_stale_or_halted_price() {
+    assert!(matches!(
+        usdc_price(100_000_000, 1_000).check_peg(1_061, 60, 50),
+        Err(EceTokenError::OraclePriceStale)
+    ));
+
+    let mut halted = usdc_price(100_000_000, 1_000);
+    halted.status = 0;
+    assert!(matches!(
+        halted.check_peg(1_000, 60, 50),
+        Err(EceTokenError::OraclePriceStale)
+    ));
+}
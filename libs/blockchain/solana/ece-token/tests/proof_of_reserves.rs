@@ -0,0 +1,78 @@
+use ece_token::proof_of_reserves::{verify_inclusion, HolderSnapshot};
+use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::{Account, AccountState};
+
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> (Pubkey, Vec<u8>) {
+    let account = Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    let mut data = vec![0; Account::LEN];
+    Account::pack(account, &mut data).unwrap();
+    (Pubkey::new_unique(), data)
+}
+
+#[test]
+fn every_holder_proves_inclusion() {
+    let balances: Vec<(Pubkey, u64)> = (1..=7).map(|i| (Pubkey::new_unique(), i * 1_000)).collect();
+    let snapshot = HolderSnapshot::new(42, &balances).unwrap();
+
+    assert_eq!(snapshot.total(), 28_000);
+    assert_eq!(snapshot.holder_count(), 7);
+
+    for (owner, balance) in &balances {
+        let (counted, proof) = snapshot.proof(owner).unwrap();
+        assert_eq!(counted, *balance);
+        assert!(verify_inclusion(&snapshot.root(), snapshot.total(), owner, counted, &proof));
+    }
+
+    assert!(snapshot.proof(&Pubkey::new_unique()).is_none());
+}
+
+#[test]
+fn tampered_balance_or_total_fails() {
+    let balances: Vec<(Pubkey, u64)> = (1..=4).map(|i| (Pubkey::new_unique(), i * 10)).collect();
+    let snapshot = HolderSnapshot::new(1, &balances).unwrap();
+    let (owner, balance) = balances[2];
+    let (_, proof) = snapshot.proof(&owner).unwrap();
+
+    assert!(!verify_inclusion(&snapshot.root(), snapshot.total(), &owner, balance + 1, &proof));
+    assert!(!verify_inclusion(&snapshot.root(), snapshot.total() - 1, &owner, balance, &proof));
+    assert!(!verify_inclusion(&snapshot.root(), snapshot.total(), &balances[1].0, balance, &proof));
+}
+
+#[test]
+fn token_accounts_aggregate_by_owner() {
+    let ece_mint = Pubkey::new_unique();
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let accounts = vec![
+        token_account(&ece_mint, &alice, 300),
+        token_account(&ece_mint, &alice, 200),
+        token_account(&ece_mint, &bob, 0),
+        token_account(&Pubkey::new_unique(), &bob, 1_000),
+    ];
+
+    let snapshot = HolderSnapshot::from_token_accounts(9, &ece_mint, &accounts).unwrap();
+
+    assert_eq!(snapshot.holders, vec![(alice, 500)]);
+    assert_eq!(snapshot.total(), 500);
+    let (balance, proof) = snapshot.proof(&alice).unwrap();
+    assert!(proof.is_empty());
+    assert!(verify_inclusion(&snapshot.root(), 500, &alice, balance, &proof));
+}
+
+#[test]
+fn empty_snapshot_has_zero_total() {
+    let snapshot = HolderSnapshot::new(5, &[]).unwrap();
+
+    assert_eq!(snapshot.root(), [0; 32]);
+    assert_eq!(snapshot.total(), 0);
+    assert_eq!(snapshot.holder_count(), 0);
+}
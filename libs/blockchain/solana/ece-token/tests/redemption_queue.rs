@@ -0,0 +1,191 @@
+mod common;
+
+use borsh::BorshSerialize;
+use common::{add_backed_treasury, add_state_account, add_token_account_at, send, token_balance, TreasuryFixture};
+use ece_token::client::EceTokenClient;
+use ece_token::error::EceTokenError;
+use ece_token::state::{RedemptionQueue, RedemptionTicket, TreasuryState};
+use solana_program::{
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+};
+use solana_program_test::ProgramTest;
+
+fn ticket(amount: u64, settled_amount: u64) -> RedemptionTicket {
+    RedemptionTicket {
+        is_initialized: true,
+        queue: Pubkey::new_unique(),
+        ticket_id: 3,
+        owner: Pubkey::new_unique(),
+        destination_usdc_account: Pubkey::new_unique(),
+        amount,
+        settled_amount,
+        created_at: 1_700_000_000,
+        cancelled: false,
+        bump: 255,
+    }
+}
+
+#[test]
+fn ticket_serializes_to_its_declared_length() {
+    assert_eq!(ticket(1_000, 0).try_to_vec().unwrap().len(), RedemptionTicket::LEN);
+}
+
+#[test]
+fn remaining_shrinks_with_partial_settlement() {
+    assert_eq!(ticket(1_000, 0).remaining(), 1_000);
+    assert_eq!(ticket(1_000, 400).remaining(), 600);
+    assert_eq!(ticket(1_000, 1_000).remaining(), 0);
+}
+
+#[test]
+fn cancelled_ticket_has_nothing_remaining() {
+    let mut ticket = ticket(1_000, 400);
+    ticket.cancelled = true;
+
+    assert_eq!(ticket.remaining(), 0);
+}
+
+#[test]
+fn pending_tickets_count_head_to_tail() {
+    let queue = RedemptionQueue {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        escrow_vault: Pubkey::new_unique(),
+        head: 4,
+        tail: 7,
+        bump: 255,
+    };
+
+    assert_eq!(queue.pending_tickets(), 3);
+}
+
+/// Treasury holding 10 USDC with a queue whose head ticket redeems 1 ECE into `destination`
+fn add_queued_redemption(
+    program_test: &mut ProgramTest,
+    program_id: &Pubkey,
+    state: TreasuryState,
+    destination: &Pubkey,
+) -> TreasuryFixture {
+    let treasury = add_backed_treasury(program_test, program_id, state);
+    add_token_account_at(program_test, *destination, &treasury.usdc_mint, &Pubkey::new_unique(), 0);
+
+    let (queue, queue_bump) = EceTokenClient::find_redemption_queue_address(program_id, &treasury.address);
+    let (escrow_vault, _) = EceTokenClient::find_redemption_escrow_address(program_id, &queue);
+    let (ticket_address, ticket_bump) = EceTokenClient::find_redemption_ticket_address(program_id, &queue, 0);
+    add_token_account_at(program_test, escrow_vault, &treasury.ece_mint, &queue, 1_000_000);
+
+    let queue_state = RedemptionQueue {
+        is_initialized: true,
+        treasury: treasury.address,
+        escrow_vault,
+        head: 0,
+        tail: 1,
+        bump: queue_bump,
+    };
+    add_state_account(program_test, program_id, queue, &queue_state, RedemptionQueue::LEN);
+
+    let mut head = ticket(1_000_000, 0);
+    head.queue = queue;
+    head.ticket_id = 0;
+    head.destination_usdc_account = *destination;
+    head.bump = ticket_bump;
+    add_state_account(program_test, program_id, ticket_address, &head, RedemptionTicket::LEN);
+
+    treasury
+}
+
+fn backed_state() -> TreasuryState {
+    let mut state = common::treasury_state(vec![Pubkey::new_unique()], 1);
+    state.ece_circulation = 10_000_000;
+    state.usdc_reserves = 10_000_000;
+    state.queued_redemptions = 1_000_000;
+    state
+}
+
+#[tokio::test]
+async fn settlement_rejects_a_fake_usdc_token_program() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let destination = Pubkey::new_unique();
+    let treasury = add_queued_redemption(&mut program_test, &program_id, backed_state(), &destination);
+
+    let mut context = program_test.start_with_context().await;
+
+    // A fake program moves no USDC while the real ECE program, appended, burns the escrow
+    let mut settle = EceTokenClient::settle_redemption(
+        &program_id,
+        &treasury.ece_mint,
+        &destination,
+        &treasury.usdc_reserve,
+        &treasury.address,
+        0,
+    )
+    .unwrap();
+    settle.accounts[7].pubkey = Pubkey::new_unique();
+    settle.accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+
+    let result = send(&mut context, &[settle], &[]).await;
+    assert_eq!(common::instruction_error(result), InstructionError::IncorrectProgramId);
+}
+
+#[tokio::test]
+async fn settlement_checks_the_usdc_peg() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let destination = Pubkey::new_unique();
+
+    let mut state = backed_state();
+    state.usdc_oracle = Pubkey::new_unique();
+    let treasury = add_queued_redemption(&mut program_test, &program_id, state, &destination);
+
+    let mut context = program_test.start_with_context().await;
+
+    // With an oracle configured the crank can't settle without its price
+    let settle = EceTokenClient::settle_redemption(
+        &program_id,
+        &treasury.ece_mint,
+        &destination,
+        &treasury.usdc_reserve,
+        &treasury.address,
+        0,
+    )
+    .unwrap();
+    let result = send(&mut context, &[settle], &[]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InvalidOracleAccount as u32);
+}
+
+#[tokio::test]
+async fn settlement_charges_the_redeem_fee() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let destination = Pubkey::new_unique();
+    let fee_usdc_account = Pubkey::new_unique();
+
+    let mut state = backed_state();
+    state.redeem_fee_bps = 50;
+    state.fee_usdc_account = fee_usdc_account;
+    let treasury = add_queued_redemption(&mut program_test, &program_id, state, &destination);
+    add_token_account_at(&mut program_test, fee_usdc_account, &treasury.usdc_mint, &Pubkey::new_unique(), 0);
+
+    let mut context = program_test.start_with_context().await;
+
+    let settle = EceTokenClient::settle_redemption(
+        &program_id,
+        &treasury.ece_mint,
+        &destination,
+        &treasury.usdc_reserve,
+        &treasury.address,
+        0,
+    )
+    .unwrap();
+    let settle = EceTokenClient::with_fee_account(settle, &fee_usdc_account);
+    send(&mut context, &[settle], &[]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &destination).await, 995_000);
+    assert_eq!(token_balance(&mut context, &fee_usdc_account).await, 5_000);
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert_eq!(state.total_fees_collected, 5_000);
+    assert_eq!(state.usdc_reserves, 9_000_000);
+    assert_eq!(state.queued_redemptions, 0);
+}
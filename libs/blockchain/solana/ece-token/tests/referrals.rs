@@ -0,0 +1,60 @@
+use ece_token::state::{ReferralConfig, ReferrerAccount};
+use solana_program::pubkey::Pubkey;
+
+fn referral_config(reward_bps: u16) -> ReferralConfig {
+    ReferralConfig {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        reward_bps,
+        max_reward_per_referrer: 1_000,
+        total_accrued: 0,
+        total_claimed: 0,
+        bump: 255,
+    }
+}
+
+fn referrer_account() -> ReferrerAccount {
+    ReferrerAccount {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        referrer: Pubkey::new_unique(),
+        referral_count: 1,
+        total_earned: 0,
+        claimable: 0,
+        bump: 255,
+    }
+}
+
+#[test]
+fn reward_is_basis_points_of_mint_rounded_down() {
+    let config = referral_config(250);
+
+    assert_eq!(config.reward_for(10_000), 250);
+    assert_eq!(config.reward_for(39), 0);
+    assert_eq!(config.reward_for(u64::MAX), u64::MAX / 40);
+}
+
+#[test]
+fn accrual_stops_at_lifetime_cap() {
+    let mut referrer = referrer_account();
+
+    assert_eq!(referrer.accrue(600, 1_000), 600);
+    assert_eq!(referrer.accrue(600, 1_000), 400);
+    assert_eq!(referrer.accrue(600, 1_000), 0);
+    assert_eq!(referrer.total_earned, 1_000);
+    assert_eq!(referrer.claimable, 1_000);
+
+    // Claiming doesn't reset the cap
+    referrer.claimable = 0;
+    assert_eq!(referrer.accrue(1, 1_000), 0);
+}
+
+#[test]
+fn outstanding_tracks_unclaimed_rewards() {
+    let mut config = referral_config(100);
+    config.total_accrued = 900;
+    config.total_claimed = 300;
+
+    assert_eq!(config.outstanding(), 600);
+}
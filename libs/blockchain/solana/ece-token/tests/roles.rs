@@ -0,0 +1,56 @@
+use ece_token::state::{Role, RoleAssignment, RoleRegistry, MAX_ROLE_HOLDERS};
+use solana_program::pubkey::Pubkey;
+
+fn registry() -> RoleRegistry {
+    RoleRegistry {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        assignment_count: 0,
+        assignments: [RoleAssignment::default(); MAX_ROLE_HOLDERS],
+        bump: 255,
+    }
+}
+
+#[test]
+fn grants_roles_independently() {
+    let mut registry = registry();
+    let holder = Pubkey::new_unique();
+
+    assert!(registry.grant(&holder, Role::Minter));
+    assert!(registry.grant(&holder, Role::Auditor));
+
+    assert!(registry.has_role(&holder, Role::Minter));
+    assert!(registry.has_role(&holder, Role::Auditor));
+    assert!(!registry.has_role(&holder, Role::Admin));
+    assert!(!registry.has_role(&Pubkey::new_unique(), Role::Minter));
+    assert_eq!(registry.assignment_count, 1);
+}
+
+#[test]
+fn revoking_last_role_frees_the_slot() {
+    let mut registry = registry();
+    let first = Pubkey::new_unique();
+    let second = Pubkey::new_unique();
+    registry.grant(&first, Role::Pauser);
+    registry.grant(&second, Role::Resolver);
+
+    registry.revoke(&first, Role::Pauser);
+
+    assert!(!registry.has_role(&first, Role::Pauser));
+    assert!(registry.has_role(&second, Role::Resolver));
+    assert_eq!(registry.assignment_count, 1);
+}
+
+#[test]
+fn rejects_grants_once_full() {
+    let mut registry = registry();
+    for _ in 0..MAX_ROLE_HOLDERS {
+        assert!(registry.grant(&Pubkey::new_unique(), Role::ComplianceOfficer));
+    }
+
+    assert!(!registry.grant(&Pubkey::new_unique(), Role::ComplianceOfficer));
+
+    // Existing holders can still gain roles
+    let existing = registry.assignments[0].holder;
+    assert!(registry.grant(&existing, Role::Auditor));
+}
@@ -0,0 +1,65 @@
+use ece_token::state::{RoyaltyCreator, RoyaltyRegistry, MAX_ROYALTY_CREATORS};
+use solana_program::pubkey::Pubkey;
+
+fn registry(royalty_bps: u16, shares: &[u16]) -> RoyaltyRegistry {
+    let creators: Vec<RoyaltyCreator> = shares
+        .iter()
+        .map(|&share_bps| RoyaltyCreator {
+            wallet: Pubkey::new_unique(),
+            share_bps,
+        })
+        .collect();
+
+    let mut registry = RoyaltyRegistry {
+        is_initialized: true,
+        collection: Pubkey::new_unique(),
+        royalty_bps,
+        creator_count: 0,
+        creators: [RoyaltyCreator::default(); MAX_ROYALTY_CREATORS],
+        bump: 255,
+    };
+    registry.set_creators(&creators);
+    registry
+}
+
+#[test]
+fn settlement_routes_fee_royalty_and_proceeds() {
+    let registry = registry(500, &[10000]);
+    let settlement = registry.settle(1_000_000, 250).unwrap();
+
+    assert_eq!(settlement.platform_fee, 25_000);
+    assert_eq!(settlement.creator_amounts, vec![50_000]);
+    assert_eq!(settlement.seller_amount, 925_000);
+}
+
+#[test]
+fn settlement_always_sums_to_price() {
+    let registry = registry(777, &[3333, 3333, 3334]);
+    let price = 1_234_567;
+    let settlement = registry.settle(price, 250).unwrap();
+
+    let total = settlement.seller_amount
+        + settlement.platform_fee
+        + settlement.creator_amounts.iter().sum::<u64>();
+    assert_eq!(total, price);
+    assert!(settlement.creator_amounts[0] >= settlement.creator_amounts[1]);
+}
+
+#[test]
+fn fees_above_price_are_rejected() {
+    let registry = registry(2500, &[10000]);
+    assert!(registry.settle(1_000, 7501).is_none());
+}
+
+#[test]
+fn creator_table_must_be_unique_and_complete() {
+    let wallet = Pubkey::new_unique();
+    let duplicated = [
+        RoyaltyCreator { wallet, share_bps: 5000 },
+        RoyaltyCreator { wallet, share_bps: 5000 },
+    ];
+    assert!(!RoyaltyRegistry::validate_creators(&duplicated));
+
+    let partial = [RoyaltyCreator { wallet, share_bps: 9000 }];
+    assert!(!RoyaltyRegistry::validate_creators(&partial));
+}
@@ -0,0 +1,395 @@
+mod common;
+
+use common::{add_mint, add_state_account, add_token_account, add_token_account_at, add_treasury, add_wallet};
+use ece_token::client::EceTokenClient;
+use ece_token::error::EceTokenError;
+use ece_token::state::{
+    LockupTier, StakeAccount, StakingPool, TreasuryState, PAUSE_REWARDS, PAUSE_STAKING, REWARD_PRECISION,
+};
+use solana_program::{pubkey::Pubkey, sysvar::clock::Clock};
+use solana_sdk::signature::Signer;
+
+fn pool() -> StakingPool {
+    StakingPool {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        ece_mint: Pubkey::new_unique(),
+        stake_vault: Pubkey::new_unique(),
+        reward_vault: Pubkey::new_unique(),
+        total_staked: 0,
+        total_weighted_stake: 0,
+        reward_rate: 0,
+        reward_period_end: 0,
+        reward_per_token_stored: 0,
+        last_update_timestamp: 0,
+        accrued_rewards: 0,
+        bump: 255,
+    }
+}
+
+fn stake(pool: &StakingPool, amount: u64, tier: LockupTier) -> StakeAccount {
+    StakeAccount {
+        is_initialized: true,
+        pool: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        amount,
+        weighted_amount: tier.weighted_amount(amount).unwrap(),
+        lockup_tier: tier,
+        lockup_end: tier.duration(),
+        last_stake_timestamp: 0,
+        reward_per_token_paid: pool.reward_per_token_stored,
+        pending_rewards: 0,
+        bump: 255,
+    }
+}
+
+#[test]
+fn reward_rate_spreads_funding_over_duration() {
+    let mut pool = pool();
+    pool.notify_reward_amount(1_000_000, 1_000, 0).unwrap();
+
+    assert_eq!(pool.reward_rate, 1_000);
+    assert_eq!(pool.reward_period_end, 1_000);
+}
+
+#[test]
+fn refunding_rolls_over_undistributed_rewards() {
+    let mut pool = pool();
+    pool.notify_reward_amount(1_000_000, 1_000, 0).unwrap();
+    pool.notify_reward_amount(500_000, 1_000, 500).unwrap();
+
+    // 500s * 1000/s left over + 500_000 new over 1000s
+    assert_eq!(pool.reward_rate, 1_000);
+    assert_eq!(pool.reward_period_end, 1_500);
+}
+
+#[test]
+fn invalid_duration_is_rejected() {
+    let mut pool = pool();
+    assert!(pool.notify_reward_amount(1_000, 0, 0).is_none());
+    assert!(pool.notify_reward_amount(1_000, -1, 0).is_none());
+}
+
+#[test]
+fn single_staker_earns_full_emission() {
+    let mut pool = pool();
+    pool.notify_reward_amount(1_000_000, 1_000, 0).unwrap();
+
+    let staker = stake(&pool, 5_000, LockupTier::Flexible);
+    pool.total_staked = staker.amount;
+    pool.total_weighted_stake = staker.weighted_amount;
+
+    let rpt = pool.reward_per_token(100).unwrap();
+    assert_eq!(staker.earned(rpt).unwrap(), 100_000);
+}
+
+#[test]
+fn accrual_stops_at_period_end() {
+    let mut pool = pool();
+    pool.notify_reward_amount(1_000_000, 1_000, 0).unwrap();
+
+    let staker = stake(&pool, 5_000, LockupTier::Flexible);
+    pool.total_weighted_stake = staker.weighted_amount;
+
+    let rpt = pool.reward_per_token(10_000).unwrap();
+    assert_eq!(staker.earned(rpt).unwrap(), 1_000_000);
+}
+
+#[test]
+fn lockup_boost_weights_rewards() {
+    let mut pool = pool();
+    pool.notify_reward_amount(2_500_000, 1_000, 0).unwrap();
+
+    let flexible = stake(&pool, 10_000, LockupTier::Flexible);
+    let locked = stake(&pool, 10_000, LockupTier::OneYear);
+    assert_eq!(locked.weighted_amount, 15_000);
+
+    pool.total_weighted_stake = flexible.weighted_amount + locked.weighted_amount;
+
+    let rpt = pool.reward_per_token(1_000).unwrap();
+    assert_eq!(flexible.earned(rpt).unwrap(), 1_000_000);
+    assert_eq!(locked.earned(rpt).unwrap(), 1_500_000);
+}
+
+#[test]
+fn checkpoint_preserves_pending_rewards() {
+    let mut pool = pool();
+    pool.notify_reward_amount(1_000_000, 1_000, 0).unwrap();
+
+    let mut staker = stake(&pool, 1_000, LockupTier::Flexible);
+    pool.total_weighted_stake = staker.weighted_amount;
+
+    pool.update_reward_per_token(250).unwrap();
+    staker.update_rewards(pool.reward_per_token_stored).unwrap();
+    assert_eq!(staker.pending_rewards, 250_000);
+
+    let rpt = pool.reward_per_token(500).unwrap();
+    assert_eq!(staker.earned(rpt).unwrap(), 500_000);
+}
+
+#[test]
+fn empty_pool_does_not_accumulate() {
+    let mut pool = pool();
+    pool.notify_reward_amount(1_000_000, 1_000, 0).unwrap();
+
+    assert_eq!(pool.reward_per_token(500).unwrap(), 0);
+}
+
+#[test]
+fn accumulator_overflow_is_reported() {
+    let mut pool = pool();
+    pool.reward_rate = u64::MAX;
+    pool.reward_period_end = i64::MAX;
+    pool.total_weighted_stake = 1;
+    pool.reward_per_token_stored = u128::MAX - REWARD_PRECISION;
+
+    assert!(pool.reward_per_token(10).is_none());
+}
+
+#[test]
+fn lockup_tiers_are_ordered_by_duration() {
+    assert!(LockupTier::Flexible < LockupTier::ThirtyDays);
+    assert!(LockupTier::ThirtyDays.duration() < LockupTier::NinetyDays.duration());
+    assert!(LockupTier::NinetyDays.boost_bps() < LockupTier::OneYear.boost_bps());
+    assert!(!stake(&pool(), 1, LockupTier::Flexible).is_locked(0));
+    assert!(stake(&pool(), 1, LockupTier::ThirtyDays).is_locked(86_400));
+}
+
+#[test]
+fn stake_passes_the_ece_mint_after_the_fixed_accounts() {
+    let ece_mint = Pubkey::new_unique();
+
+    let instruction = EceTokenClient::stake(
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &ece_mint,
+        &spl_token_2022::id(),
+        1_000,
+        LockupTier::Flexible,
+    )
+    .unwrap();
+
+    assert_eq!(instruction.accounts.len(), 11);
+    assert_eq!(instruction.accounts[10].pubkey, ece_mint);
+}
+
+#[test]
+fn owed_rewards_track_emission_and_claims() {
+    let mut pool = pool();
+    pool.notify_reward_amount(1_000, 100, 0).unwrap();
+
+    // Nobody is staked, so nothing is owed yet
+    pool.update_reward_per_token(10).unwrap();
+    assert_eq!(pool.accrued_rewards, 0);
+
+    pool.total_weighted_stake = 1_000;
+    pool.update_reward_per_token(40).unwrap();
+    assert_eq!(pool.accrued_rewards, 300);
+
+    // Accrual stops at the end of the period
+    pool.update_reward_per_token(500).unwrap();
+    assert_eq!(pool.accrued_rewards, 900);
+}
+
+/// Staking pool preloaded for a treasury administered by `admin`, with its vaults
+/// holding the given balances
+struct PoolFixture {
+    program_id: Pubkey,
+    treasury: Pubkey,
+    ece_mint: Pubkey,
+    pool: Pubkey,
+}
+
+fn add_pool(
+    program_test: &mut solana_program_test::ProgramTest,
+    program_id: Pubkey,
+    admin: &Pubkey,
+    mut pool: StakingPool,
+    stake_vault_balance: u64,
+    reward_vault_balance: u64,
+) -> PoolFixture {
+    let ece_mint = add_mint(program_test, &Pubkey::new_unique(), 6, 0);
+    let mut state = common::treasury_state(vec![*admin], 1);
+    state.ece_mint = ece_mint;
+    let treasury = add_treasury(program_test, &program_id, &state);
+
+    let (pool_address, bump) = EceTokenClient::find_staking_pool_address(&program_id, &treasury);
+    let (stake_vault, _) = EceTokenClient::find_stake_vault_address(&program_id, &pool_address);
+    let (reward_vault, _) = EceTokenClient::find_reward_vault_address(&program_id, &pool_address);
+    add_token_account_at(program_test, stake_vault, &ece_mint, &pool_address, stake_vault_balance);
+    add_token_account_at(program_test, reward_vault, &ece_mint, &pool_address, reward_vault_balance);
+
+    pool.treasury = treasury;
+    pool.ece_mint = ece_mint;
+    pool.stake_vault = stake_vault;
+    pool.reward_vault = reward_vault;
+    pool.bump = bump;
+    add_state_account(program_test, &program_id, pool_address, &pool, StakingPool::LEN);
+
+    PoolFixture {
+        program_id,
+        treasury,
+        ece_mint,
+        pool: pool_address,
+    }
+}
+
+#[tokio::test]
+async fn funding_must_cover_rewards_already_owed() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+
+    // Stakers are owed 1,000 but the vault only holds 600
+    let mut owed = pool();
+    owed.total_weighted_stake = 1_000;
+    owed.accrued_rewards = 1_000;
+    let fixture = add_pool(&mut program_test, program_id, &admin.pubkey(), owed, 1_000, 600);
+    let source = add_token_account(&mut program_test, &fixture.ece_mint, &admin.pubkey(), 10_000);
+
+    let mut context = program_test.start_with_context().await;
+
+    let fund = |amount| {
+        EceTokenClient::fund_staking_rewards(
+            &fixture.program_id,
+            &admin.pubkey(),
+            &source,
+            &fixture.treasury,
+            &fixture.ece_mint,
+            &spl_token::id(),
+            amount,
+            100,
+        )
+        .unwrap()
+    };
+
+    // 500 more would cover its own schedule, but not the 400 already missing
+    let result = common::send(&mut context, &[fund(500)], &[&admin]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InsufficientFunds as u32);
+}
+
+#[tokio::test]
+async fn funding_starts_a_period_next_to_owed_rewards() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+
+    let mut owed = pool();
+    owed.total_weighted_stake = 1_000;
+    owed.accrued_rewards = 1_000;
+    let fixture = add_pool(&mut program_test, program_id, &admin.pubkey(), owed, 1_000, 1_000);
+    let source = add_token_account(&mut program_test, &fixture.ece_mint, &admin.pubkey(), 10_000);
+
+    let mut context = program_test.start_with_context().await;
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+
+    let fund = EceTokenClient::fund_staking_rewards(
+        &fixture.program_id,
+        &admin.pubkey(),
+        &source,
+        &fixture.treasury,
+        &fixture.ece_mint,
+        &spl_token::id(),
+        500,
+        100,
+    )
+    .unwrap();
+    common::send(&mut context, &[fund], &[&admin]).await.unwrap();
+
+    let funded: StakingPool = common::load_state(&mut context, &fixture.pool).await;
+    assert_eq!(funded.reward_rate, 5);
+    assert_eq!(funded.accrued_rewards, 1_000);
+    assert_eq!(funded.reward_period_end, clock.unix_timestamp + 100);
+}
+
+#[tokio::test]
+async fn stake_accounts_must_be_at_their_address() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+    let staker = add_wallet(&mut program_test);
+
+    let mut staked = pool();
+    staked.total_staked = 1_000;
+    staked.total_weighted_stake = 1_000;
+    let fixture = add_pool(&mut program_test, program_id, &admin.pubkey(), staked.clone(), 1_000, 0);
+    let destination = add_token_account(&mut program_test, &fixture.ece_mint, &staker.pubkey(), 0);
+
+    // A program-owned record for the right pool and owner, but not at the stake PDA
+    let (_, bump) = EceTokenClient::find_stake_account_address(&program_id, &fixture.pool, &staker.pubkey());
+    let mut position = stake(&staked, 1_000, LockupTier::Flexible);
+    position.pool = fixture.pool;
+    position.owner = staker.pubkey();
+    position.bump = bump;
+    let impostor = Pubkey::new_unique();
+    add_state_account(&mut program_test, &program_id, impostor, &position, StakeAccount::LEN);
+
+    let mut context = program_test.start_with_context().await;
+
+    let mut unstake = EceTokenClient::unstake(
+        &program_id,
+        &staker.pubkey(),
+        &destination,
+        &fixture.treasury,
+        &fixture.ece_mint,
+        &spl_token::id(),
+        1_000,
+    )
+    .unwrap();
+    unstake.accounts[3].pubkey = impostor;
+
+    let result = common::send(&mut context, &[unstake], &[&staker]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InvalidProgramAddress as u32);
+}
+
+#[tokio::test]
+async fn reward_claims_follow_the_rewards_pause() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+    let staker = add_wallet(&mut program_test);
+
+    let mut staked = pool();
+    staked.total_staked = 1_000;
+    staked.total_weighted_stake = 1_000;
+    staked.accrued_rewards = 300;
+    let fixture = add_pool(&mut program_test, program_id, &admin.pubkey(), staked.clone(), 1_000, 300);
+    let destination = add_token_account(&mut program_test, &fixture.ece_mint, &staker.pubkey(), 0);
+
+    let (stake_address, bump) = EceTokenClient::find_stake_account_address(&program_id, &fixture.pool, &staker.pubkey());
+    let mut position = stake(&staked, 1_000, LockupTier::Flexible);
+    position.pool = fixture.pool;
+    position.owner = staker.pubkey();
+    position.pending_rewards = 300;
+    position.bump = bump;
+    add_state_account(&mut program_test, &program_id, stake_address, &position, StakeAccount::LEN);
+
+    let mut context = program_test.start_with_context().await;
+
+    let claim = EceTokenClient::claim_staking_rewards(
+        &program_id,
+        &staker.pubkey(),
+        &destination,
+        &fixture.treasury,
+        &fixture.ece_mint,
+        &spl_token::id(),
+    )
+    .unwrap();
+
+    common::update_state(&mut context, &fixture.treasury, |state: &mut TreasuryState| {
+        state.pause_flags = PAUSE_REWARDS;
+    })
+    .await;
+    let result = common::send(&mut context, std::slice::from_ref(&claim), &[&staker]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::EmergencyPauseActive as u32);
+
+    // Pausing staking still lets stakers collect what they've earned
+    common::update_state(&mut context, &fixture.treasury, |state: &mut TreasuryState| {
+        state.pause_flags = PAUSE_STAKING;
+    })
+    .await;
+    common::send(&mut context, &[claim], &[&staker]).await.unwrap();
+    assert_eq!(common::token_balance(&mut context, &destination).await, 300);
+}
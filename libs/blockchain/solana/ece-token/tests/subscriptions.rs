@@ -0,0 +1,58 @@
+use borsh::BorshSerialize;
+use ece_token::state::{Subscription, SubscriptionStatus};
+use solana_program::pubkey::Pubkey;
+
+fn subscription(status: SubscriptionStatus, next_charge_at: i64) -> Subscription {
+    Subscription {
+        is_initialized: true,
+        plan: Pubkey::new_unique(),
+        subscriber: Pubkey::new_unique(),
+        subscriber_ece_account: Pubkey::new_unique(),
+        price: 10_000_000,
+        period: 30 * 86400,
+        grace_period: 3 * 86400,
+        next_charge_at,
+        periods_paid: 1,
+        total_collected: 10_000_000,
+        status,
+        bump: 255,
+    }
+}
+
+#[test]
+fn subscription_serializes_to_its_declared_length() {
+    let subscription = subscription(SubscriptionStatus::Active, 1_000);
+
+    assert_eq!(subscription.try_to_vec().unwrap().len(), Subscription::LEN);
+}
+
+#[test]
+fn period_is_due_at_the_next_charge() {
+    let subscription = subscription(SubscriptionStatus::Active, 1_000);
+
+    assert!(!subscription.is_due(999));
+    assert!(subscription.is_due(1_000));
+}
+
+#[test]
+fn past_due_keeps_access_only_during_grace() {
+    let subscription = subscription(SubscriptionStatus::PastDue, 1_000);
+
+    assert!(subscription.has_access(1_000 + 3 * 86400 - 1));
+    assert!(!subscription.has_access(1_000 + 3 * 86400));
+}
+
+#[test]
+fn cancelled_keeps_access_until_the_paid_period_ends() {
+    let subscription = subscription(SubscriptionStatus::Cancelled, 1_000);
+
+    assert!(subscription.has_access(999));
+    assert!(!subscription.has_access(1_000));
+}
+
+#[test]
+fn expired_has_no_access() {
+    let subscription = subscription(SubscriptionStatus::Expired, 1_000);
+
+    assert!(!subscription.has_access(0));
+}
@@ -0,0 +1,90 @@
+use ece_token::client::EceTokenClient;
+use ece_token::state::{TradeOffer, TradeOfferStatus};
+use solana_program::pubkey::Pubkey;
+
+fn offer(taker: Pubkey, expires_at: i64) -> TradeOffer {
+    TradeOffer {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        maker: Pubkey::new_unique(),
+        offer_id: 1,
+        ece_mint: Pubkey::new_unique(),
+        card_token_program: spl_token::id(),
+        offered_card_mint: Pubkey::new_unique(),
+        offered_ece_amount: 0,
+        requested_card_mint: Pubkey::default(),
+        requested_ece_amount: 5_000_000,
+        taker,
+        expires_at,
+        status: TradeOfferStatus::Open,
+        created_at: 0,
+        bump: 255,
+    }
+}
+
+#[test]
+fn terms_need_a_card_and_both_sides() {
+    let card = Pubkey::new_unique();
+    let other_card = Pubkey::new_unique();
+    let none = Pubkey::default();
+
+    // Card for ECE, ECE for card, card for card, card plus ECE for card
+    assert!(TradeOffer::is_valid_terms(&card, 0, &none, 100));
+    assert!(TradeOffer::is_valid_terms(&none, 100, &card, 0));
+    assert!(TradeOffer::is_valid_terms(&card, 0, &other_card, 0));
+    assert!(TradeOffer::is_valid_terms(&card, 100, &other_card, 0));
+
+    assert!(!TradeOffer::is_valid_terms(&none, 100, &none, 100));
+    assert!(!TradeOffer::is_valid_terms(&card, 0, &none, 0));
+    assert!(!TradeOffer::is_valid_terms(&none, 0, &card, 0));
+    assert!(!TradeOffer::is_valid_terms(&card, 0, &card, 0));
+}
+
+#[test]
+fn open_offer_is_fillable_by_anyone_but_the_maker() {
+    let offer = offer(Pubkey::default(), 0);
+
+    assert!(offer.is_fillable_by(&Pubkey::new_unique(), i64::MAX));
+    assert!(!offer.is_fillable_by(&offer.maker, 0));
+}
+
+#[test]
+fn private_expiring_offer_limits_taker_and_time() {
+    let taker = Pubkey::new_unique();
+    let mut offer = offer(taker, 1_000);
+
+    assert!(offer.is_fillable_by(&taker, 999));
+    assert!(!offer.is_fillable_by(&taker, 1_000));
+    assert!(!offer.is_fillable_by(&Pubkey::new_unique(), 999));
+
+    offer.status = TradeOfferStatus::Cancelled;
+    assert!(!offer.is_fillable_by(&taker, 999));
+}
+
+#[test]
+fn fee_rounds_down_and_caps_at_full_amount() {
+    assert_eq!(TradeOffer::fee_for(5_000_000, 250), 125_000);
+    assert_eq!(TradeOffer::fee_for(39, 250), 0);
+    assert_eq!(TradeOffer::fee_for(100, u16::MAX), 100);
+}
+
+#[test]
+fn fill_passes_each_legs_mint_for_checked_transfers() {
+    let taker = Pubkey::new_unique();
+    let offer = offer(taker, 0);
+
+    let instruction = EceTokenClient::fill_trade_offer(
+        &Pubkey::new_unique(),
+        &taker,
+        &offer,
+        &Pubkey::new_unique(),
+        &spl_token_2022::id(),
+    )
+    .unwrap();
+
+    // Offered card leg, then requested ECE leg, each ending with its mint
+    assert_eq!(instruction.accounts.len(), 14);
+    assert_eq!(instruction.accounts[10].pubkey, offer.offered_card_mint);
+    assert_eq!(instruction.accounts[13].pubkey, offer.ece_mint);
+    assert!(!instruction.accounts[13].is_writable);
+}
@@ -0,0 +1,605 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{add_backed_treasury, add_token_account, add_wallet, send, token_balance};
+use ece_token::client::EceTokenClient;
+use ece_token::error::EceTokenError;
+use ece_token::instruction::EceTokenInstruction;
+use ece_token::state::{
+    AuthorityKind, CircuitBreakerReason, PendingWithdrawal, TreasuryState, WithdrawalStatus, PAUSE_ALL, PAUSE_CARDS,
+    PAUSE_ESCROW, PAUSE_MINT, PAUSE_REWARDS, PAUSE_STAKING, PAUSE_SUBSCRIPTIONS, MAX_TREASURY_SIGNERS,
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+const WINDOW: i64 = 86400;
+
+fn treasury() -> TreasuryState {
+    TreasuryState {
+        is_initialized: true,
+        signers: vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()],
+        threshold: 2,
+        ece_mint: Pubkey::new_unique(),
+        usdc_mint: Pubkey::new_unique(),
+        usdc_reserve: Pubkey::new_unique(),
+        ece_circulation: 1_000_000_000,
+        usdc_reserves: 1_000_000_000,
+        is_paused: false,
+        emergency_authority: Pubkey::new_unique(),
+        last_payout_timestamp: 0,
+        payout_window: 604800,
+        min_reserve_ratio: 10000,
+        company_revenue_account: Pubkey::new_unique(),
+        company_usdc_account: Pubkey::new_unique(),
+        total_revenue_processed: 0,
+        payout_count: 0,
+        compliance_authority: Pubkey::new_unique(),
+        max_transaction_amount: u64::MAX,
+        daily_volume_limit: u64::MAX,
+        current_daily_volume: 0,
+        last_volume_reset: 0,
+        marketplace_fee_bps: 250,
+        queued_redemptions: 0,
+        withdrawal_timelock_threshold: 50_000,
+        withdrawal_timelock_delay: 172800,
+        withdrawal_count: 0,
+        outflow_window: WINDOW,
+        max_window_outflow_bps: 2500,
+        reserve_floor_bps: 9500,
+        window_outflow: 0,
+        previous_window_outflow: 0,
+        outflow_window_start: 0,
+        breaker_trip_reason: CircuitBreakerReason::None,
+        breaker_tripped_at: 0,
+        pause_flags: 0,
+        pending_emergency_authority: Pubkey::default(),
+        pending_compliance_authority: Pubkey::default(),
+        min_signer_threshold: 2,
+        signer_epoch: 0,
+        usdc_oracle: Pubkey::default(),
+        max_peg_deviation_bps: 50,
+        max_oracle_staleness: 60,
+        mint_fee_bps: 0,
+        redeem_fee_bps: 0,
+        fee_usdc_account: Pubkey::default(),
+        total_fees_collected: 0,
+        ece_token_program: spl_token::id(),
+        collateral_reserves: 0,
+        ece_decimals: 6,
+        usdc_decimals: 6,
+        user_mint_limit: 10_000_000_000,
+        user_daily_mint_limit: 25_000_000_000,
+        reserve_mint_limit: 100_000_000_000,
+        fee_ece_account: Pubkey::default(),
+        window_withdrawals: 0,
+        pending_timelock_threshold: 0,
+        pending_timelock_delay: 0,
+        pending_timelock_at: 0,
+    }
+}
+
+#[test]
+fn split_withdrawals_still_hit_the_timelock() {
+    let mut state = treasury();
+
+    assert!(!state.requires_withdrawal_timelock(30_000));
+    state.window_withdrawals += 30_000;
+
+    // A second 30k in the same window takes the total over the 50k threshold
+    assert!(state.requires_withdrawal_timelock(30_000));
+    assert!(!state.requires_withdrawal_timelock(20_000));
+}
+
+#[test]
+fn withdrawal_total_resets_with_the_outflow_window() {
+    let mut state = treasury();
+    state.window_withdrawals = 50_000;
+
+    state.roll_outflow_window(WINDOW - 1);
+    assert_eq!(state.window_withdrawals, 50_000);
+
+    state.roll_outflow_window(WINDOW);
+    assert_eq!(state.window_withdrawals, 0);
+    assert!(!state.requires_withdrawal_timelock(50_000));
+}
+
+#[test]
+fn only_stricter_timelocks_skip_the_delay() {
+    let state = treasury();
+
+    assert!(state.is_timelock_tightening(40_000, 172800));
+    assert!(state.is_timelock_tightening(50_000, 259200));
+    assert!(!state.is_timelock_tightening(60_000, 172800));
+    assert!(!state.is_timelock_tightening(40_000, 3600));
+}
+
+#[test]
+fn pending_withdrawal_unlocks_at_its_deadline() {
+    let pending = PendingWithdrawal {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        withdrawal_id: 0,
+        proposer: Pubkey::new_unique(),
+        destination_usdc_account: Pubkey::new_unique(),
+        amount: 75_000,
+        created_at: 1_000,
+        unlock_at: 1_000 + 172800,
+        status: WithdrawalStatus::Pending,
+        bump: 255,
+        signer_epoch: 0,
+    };
+
+    assert!(!pending.is_unlocked(1_000 + 172799));
+    assert!(pending.is_unlocked(1_000 + 172800));
+}
+
+#[test]
+fn pause_flags_only_stop_their_operations() {
+    let mut state = treasury();
+    state.pause_flags = PAUSE_STAKING | PAUSE_REWARDS;
+
+    assert!(state.is_operation_paused(PAUSE_STAKING));
+    assert!(state.is_operation_paused(PAUSE_REWARDS));
+    assert!(!state.is_operation_paused(PAUSE_MINT));
+    assert!(!state.is_operation_paused(PAUSE_ESCROW));
+}
+
+#[test]
+fn global_pause_stops_every_operation() {
+    let mut state = treasury();
+    state.is_paused = true;
+
+    for bit in 0..10 {
+        assert!(state.is_operation_paused(1 << bit));
+    }
+    assert_eq!(PAUSE_ALL, (1 << 10) - 1);
+}
+
+#[test]
+fn set_pause_flags_is_signed_by_the_emergency_authority() {
+    let program_id = Pubkey::new_unique();
+    let emergency_authority = Pubkey::new_unique();
+    let treasury_state_account = Pubkey::new_unique();
+
+    let instruction = EceTokenClient::set_pause_flags(
+        &program_id,
+        &emergency_authority,
+        &treasury_state_account,
+        PAUSE_ESCROW | PAUSE_REWARDS,
+    )
+    .unwrap();
+
+    assert_eq!(instruction.accounts[0].pubkey, emergency_authority);
+    assert!(instruction.accounts[0].is_signer);
+    assert!(instruction.accounts[1].is_writable);
+    match EceTokenInstruction::try_from_slice(&instruction.data).unwrap() {
+        EceTokenInstruction::SetPauseFlags { pause_flags } => {
+            assert_eq!(pause_flags, PAUSE_ESCROW | PAUSE_REWARDS)
+        }
+        other => panic!("unexpected instruction {:?}", other),
+    }
+}
+
+#[test]
+fn authority_kinds_map_to_their_own_slots() {
+    let mut state = treasury();
+
+    assert_eq!(state.authority(AuthorityKind::Emergency), &state.emergency_authority);
+    assert_eq!(state.authority(AuthorityKind::Compliance), &state.compliance_authority);
+    assert_eq!(state.pending_authority(AuthorityKind::Emergency), &Pubkey::default());
+
+    let successor = Pubkey::new_unique();
+    *state.pending_authority_mut(AuthorityKind::Compliance) = successor;
+
+    assert_eq!(state.pending_compliance_authority, successor);
+    assert_eq!(state.pending_authority(AuthorityKind::Compliance), &successor);
+    assert_eq!(state.pending_authority(AuthorityKind::Emergency), &Pubkey::default());
+}
+
+#[test]
+fn propose_authority_carries_the_approvers() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let approver = Pubkey::new_unique();
+    let successor = Pubkey::new_unique();
+
+    let instruction = EceTokenClient::propose_authority(
+        &program_id,
+        &authority,
+        &Pubkey::new_unique(),
+        &[approver],
+        AuthorityKind::Emergency,
+        &successor,
+    )
+    .unwrap();
+
+    assert_eq!(instruction.accounts.len(), 3);
+    assert_eq!(instruction.accounts[2].pubkey, approver);
+    assert!(instruction.accounts[2].is_signer);
+    match EceTokenInstruction::try_from_slice(&instruction.data).unwrap() {
+        EceTokenInstruction::ProposeAuthority { new_authority, .. } => assert_eq!(new_authority, successor),
+        other => panic!("unexpected instruction {:?}", other),
+    }
+}
+
+#[test]
+fn signer_sets_must_be_distinct_and_bounded() {
+    let state = treasury();
+    let signers: Vec<Pubkey> = (0..MAX_TREASURY_SIGNERS + 1).map(|_| Pubkey::new_unique()).collect();
+
+    assert!(state.is_valid_signer_set(&signers[..3], 2));
+    assert!(state.is_valid_signer_set(&signers[..MAX_TREASURY_SIGNERS], MAX_TREASURY_SIGNERS as u8));
+    assert!(!state.is_valid_signer_set(&[], 0));
+    assert!(!state.is_valid_signer_set(&signers, 2));
+    assert!(!state.is_valid_signer_set(&[signers[0], signers[1], signers[0]], 2));
+}
+
+#[test]
+fn signer_set_threshold_respects_the_minimum() {
+    let mut state = treasury();
+    let signers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+
+    // min_signer_threshold is 2, so a single approver is not enough
+    assert!(!state.is_valid_signer_set(&signers, 1));
+    assert!(state.is_valid_signer_set(&signers, 3));
+    assert!(!state.is_valid_signer_set(&signers, 4));
+
+    state.min_signer_threshold = 0;
+    assert!(!state.is_valid_signer_set(&signers, 0));
+    assert!(state.is_valid_signer_set(&signers, 1));
+}
+
+#[test]
+fn signer_epoch_survives_serialization() {
+    let mut state = treasury();
+    state.signer_epoch = 7;
+    let pending = PendingWithdrawal {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        withdrawal_id: 3,
+        proposer: Pubkey::new_unique(),
+        destination_usdc_account: Pubkey::new_unique(),
+        amount: 75_000,
+        created_at: 1_000,
+        unlock_at: 1_000 + 172800,
+        status: WithdrawalStatus::Pending,
+        bump: 255,
+        signer_epoch: state.signer_epoch,
+    };
+
+    let data = pending.try_to_vec().unwrap();
+    assert_eq!(data.len(), PendingWithdrawal::LEN);
+    assert_eq!(PendingWithdrawal::try_from_slice(&data).unwrap().signer_epoch, 7);
+
+    let mut data = state.try_to_vec().unwrap();
+    assert!(data.len() <= TreasuryState::LEN);
+    data.resize(TreasuryState::LEN, 0);
+    assert_eq!(TreasuryState::deserialize(&mut &data[..]).unwrap().signer_epoch, 7);
+}
+
+#[test]
+fn treasury_len_fits_the_largest_signer_set() {
+    let mut state = treasury();
+    state.signers = (0..MAX_TREASURY_SIGNERS).map(|_| Pubkey::new_unique()).collect();
+    assert_eq!(state.try_to_vec().unwrap().len(), TreasuryState::LEN);
+
+    // A smaller set leaves padding that loading reads past
+    state.signers.truncate(1);
+    let mut data = state.try_to_vec().unwrap();
+    data.resize(TreasuryState::LEN, 0);
+    assert!(TreasuryState::try_from_slice(&data).is_err());
+    assert_eq!(TreasuryState::deserialize(&mut &data[..]).unwrap().signers.len(), 1);
+}
+
+#[test]
+fn mint_and_redeem_fees_round_up() {
+    let mut state = treasury();
+    state.mint_fee_bps = 30;
+    state.redeem_fee_bps = 50;
+
+    // 0.3% of 1_000 is 3 exactly, 0.3% of 1_001 is 3.003
+    assert_eq!(state.mint_fee(1_000), Some(3));
+    assert_eq!(state.mint_fee(1_001), Some(4));
+    // Dust amounts still pay at least one base unit
+    assert_eq!(state.redeem_fee(1), Some(1));
+    assert_eq!(state.redeem_fee(200), Some(1));
+    assert_eq!(state.redeem_fee(201), Some(2));
+}
+
+#[test]
+fn zero_fee_rates_charge_nothing() {
+    let state = treasury();
+
+    assert_eq!(state.mint_fee(1_000_001), Some(0));
+    assert_eq!(state.redeem_fee(u64::MAX), Some(0));
+}
+
+#[tokio::test]
+async fn burn_pays_out_and_takes_the_fee_as_the_treasury_authority() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let holder = add_wallet(&mut program_test);
+    let fee_usdc_account = Pubkey::new_unique();
+
+    let mut state = common::treasury_state(vec![Pubkey::new_unique()], 1);
+    state.ece_circulation = 10_000_000;
+    state.usdc_reserves = 10_000_000;
+    state.redeem_fee_bps = 50;
+    state.fee_usdc_account = fee_usdc_account;
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+    common::add_token_account_at(&mut program_test, fee_usdc_account, &treasury.usdc_mint, &Pubkey::new_unique(), 0);
+    let source = add_token_account(&mut program_test, &treasury.ece_mint, &holder.pubkey(), 1_000_000);
+    let destination = add_token_account(&mut program_test, &treasury.usdc_mint, &holder.pubkey(), 0);
+
+    let mut context = program_test.start_with_context().await;
+
+    let burn = EceTokenClient::burn_tokens(
+        &program_id,
+        &holder.pubkey(),
+        &treasury.ece_mint,
+        &source,
+        &destination,
+        &treasury.usdc_reserve,
+        &treasury.address,
+        1_000_000,
+    )
+    .unwrap();
+    let burn = EceTokenClient::with_fee_account(burn, &fee_usdc_account);
+    send(&mut context, &[burn], &[&holder]).await.unwrap();
+
+    // Both the payout and the fee leave the one reserve, signed by its one owner
+    assert_eq!(token_balance(&mut context, &destination).await, 995_000);
+    assert_eq!(token_balance(&mut context, &fee_usdc_account).await, 5_000);
+    assert_eq!(token_balance(&mut context, &treasury.usdc_reserve).await, 9_000_000);
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert_eq!(state.usdc_reserves, 9_000_000);
+    assert_eq!(state.ece_circulation, 9_000_000);
+}
+
+#[tokio::test]
+async fn usdc_redemptions_stop_at_the_usdc_share_of_reserves() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let holder = add_wallet(&mut program_test);
+
+    // 4 of the 10 ECE outstanding are backed by USDC, the rest by the collateral basket
+    let mut state = common::treasury_state(vec![Pubkey::new_unique()], 1);
+    state.ece_circulation = 10_000_000;
+    state.usdc_reserves = 4_000_000;
+    state.collateral_reserves = 6_000_000;
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+    let source = add_token_account(&mut program_test, &treasury.ece_mint, &holder.pubkey(), 5_000_000);
+    let destination = add_token_account(&mut program_test, &treasury.usdc_mint, &holder.pubkey(), 0);
+
+    let mut context = program_test.start_with_context().await;
+
+    let burn = |amount| {
+        EceTokenClient::burn_tokens(
+            &program_id,
+            &holder.pubkey(),
+            &treasury.ece_mint,
+            &source,
+            &destination,
+            &treasury.usdc_reserve,
+            &treasury.address,
+            amount,
+        )
+        .unwrap()
+    };
+
+    let result = send(&mut context, &[burn(4_500_000)], &[&holder]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::UsdcReserveInsufficient as u32);
+
+    send(&mut context, &[burn(4_000_000)], &[&holder]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &destination).await, 4_000_000);
+
+    // What's left is basket-backed and can't be paid out of the now empty USDC share
+    let result = send(&mut context, &[burn(1)], &[&holder]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::UsdcReserveInsufficient as u32);
+}
+
+#[tokio::test]
+async fn withdrawals_need_the_treasury_authority() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+
+    let mut state = common::treasury_state(vec![admin.pubkey()], 1);
+    state.usdc_reserves = 10_000_000;
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+    let destination = add_token_account(&mut program_test, &treasury.usdc_mint, &admin.pubkey(), 0);
+
+    let mut context = program_test.start_with_context().await;
+
+    let withdraw = EceTokenClient::withdraw_usdc(
+        &program_id,
+        &admin.pubkey(),
+        &treasury.usdc_reserve,
+        &destination,
+        &treasury.address,
+        1_000,
+    )
+    .unwrap();
+
+    let mut impostor = withdraw.clone();
+    impostor.accounts[6].pubkey = Pubkey::new_unique();
+    let result = send(&mut context, &[impostor], &[&admin]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InvalidProgramAddress as u32);
+
+    send(&mut context, &[withdraw], &[&admin]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &destination).await, 1_000);
+    assert_eq!(token_balance(&mut context, &treasury.usdc_reserve).await, 9_999_000);
+}
+
+#[tokio::test]
+async fn timelocked_withdrawals_need_the_quorum_to_request_and_execute() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+    let second = add_wallet(&mut program_test);
+    let third = add_wallet(&mut program_test);
+
+    let mut state = common::treasury_state(vec![admin.pubkey(), second.pubkey(), third.pubkey()], 2);
+    state.usdc_reserves = 10_000_000;
+    state.withdrawal_timelock_threshold = 1_000_000;
+    state.withdrawal_timelock_delay = 0;
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+    let destination = add_token_account(&mut program_test, &treasury.usdc_mint, &admin.pubkey(), 0);
+
+    let mut context = program_test.start_with_context().await;
+
+    let request = |approvers: &[Pubkey]| {
+        EceTokenClient::withdraw_usdc_timelocked(
+            &program_id,
+            &admin.pubkey(),
+            &treasury.usdc_reserve,
+            &destination,
+            &treasury.address,
+            approvers,
+            0,
+            2_000_000,
+        )
+        .unwrap()
+    };
+    let (pending, _) = EceTokenClient::find_pending_withdrawal_address(&program_id, &treasury.address, 0);
+    let execute = |approvers: &[Pubkey]| {
+        EceTokenClient::execute_withdrawal(
+            &program_id,
+            &admin.pubkey(),
+            &pending,
+            &treasury.usdc_reserve,
+            &destination,
+            &treasury.address,
+            approvers,
+        )
+        .unwrap()
+    };
+
+    // One admin alone can't queue a large withdrawal
+    let result = send(&mut context, &[request(&[])], &[&admin]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InsufficientSigners as u32);
+
+    send(&mut context, &[request(&[second.pubkey()])], &[&admin, &second]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &destination).await, 0);
+
+    // Nor execute one the quorum requested
+    let result = send(&mut context, &[execute(&[])], &[&admin]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InsufficientSigners as u32);
+
+    send(&mut context, &[execute(&[third.pubkey()])], &[&admin, &third]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &destination).await, 2_000_000);
+    let withdrawal: PendingWithdrawal = common::load_state(&mut context, &pending).await;
+    assert_eq!(withdrawal.status, WithdrawalStatus::Executed);
+}
+
+#[tokio::test]
+async fn cancelling_a_handover_needs_the_same_approval_as_proposing_it() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let admin = add_wallet(&mut program_test);
+    let second = add_wallet(&mut program_test);
+    let pauser = add_wallet(&mut program_test);
+
+    let mut state = common::treasury_state(vec![admin.pubkey(), second.pubkey()], 2);
+    state.emergency_authority = pauser.pubkey();
+    state.pending_emergency_authority = Pubkey::new_unique();
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+
+    let mut context = program_test.start_with_context().await;
+
+    let cancel = |authority: &Pubkey, approvers: &[Pubkey]| {
+        EceTokenClient::cancel_authority_transfer(
+            &program_id,
+            authority,
+            &treasury.address,
+            approvers,
+            AuthorityKind::Emergency,
+        )
+        .unwrap()
+    };
+
+    // A single treasury signer can't block a handover the quorum would need to propose
+    let result = send(&mut context, &[cancel(&admin.pubkey(), &[])], &[&admin]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InsufficientSigners as u32);
+
+    send(&mut context, &[cancel(&admin.pubkey(), &[second.pubkey()])], &[&admin, &second]).await.unwrap();
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert_eq!(state.pending_authority(AuthorityKind::Emergency), &Pubkey::default());
+
+    // The current holder proposes and cancels alone
+    let propose = EceTokenClient::propose_authority(
+        &program_id,
+        &pauser.pubkey(),
+        &treasury.address,
+        &[],
+        AuthorityKind::Emergency,
+        &Pubkey::new_unique(),
+    )
+    .unwrap();
+    send(&mut context, &[propose], &[&pauser]).await.unwrap();
+    send(&mut context, &[cancel(&pauser.pubkey(), &[])], &[&pauser]).await.unwrap();
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert_eq!(state.pending_authority(AuthorityKind::Emergency), &Pubkey::default());
+}
+
+#[tokio::test]
+async fn pause_flags_outside_the_defined_set_are_rejected() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let pauser = add_wallet(&mut program_test);
+
+    let mut state = common::treasury_state(vec![Pubkey::new_unique()], 1);
+    state.emergency_authority = pauser.pubkey();
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+
+    let mut context = program_test.start_with_context().await;
+
+    let set_flags = |pause_flags| {
+        EceTokenClient::set_pause_flags(&program_id, &pauser.pubkey(), &treasury.address, pause_flags).unwrap()
+    };
+
+    let result = send(&mut context, &[set_flags(PAUSE_ALL + 1)], &[&pauser]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InvalidPauseFlags as u32);
+
+    send(&mut context, &[set_flags(PAUSE_CARDS | PAUSE_SUBSCRIPTIONS)], &[&pauser]).await.unwrap();
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert!(state.is_operation_paused(PAUSE_CARDS));
+    assert!(state.is_operation_paused(PAUSE_SUBSCRIPTIONS));
+    assert!(!state.is_operation_paused(PAUSE_ESCROW));
+}
+
+#[tokio::test]
+async fn deposits_credit_what_reaches_the_reserve() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = common::program_test(&program_id);
+    let depositor = add_wallet(&mut program_test);
+
+    let mut state = common::treasury_state(vec![Pubkey::new_unique()], 1);
+    state.usdc_reserves = 1_000_000;
+    let treasury = add_backed_treasury(&mut program_test, &program_id, state);
+    let source = add_token_account(&mut program_test, &treasury.usdc_mint, &depositor.pubkey(), 5_000_000);
+    let elsewhere = add_token_account(&mut program_test, &treasury.usdc_mint, &depositor.pubkey(), 0);
+
+    let mut context = program_test.start_with_context().await;
+
+    let deposit = |treasury_usdc_account: &Pubkey| {
+        EceTokenClient::deposit_usdc(
+            &program_id,
+            &depositor.pubkey(),
+            &source,
+            treasury_usdc_account,
+            &treasury.address,
+            2_000_000,
+        )
+        .unwrap()
+    };
+
+    // Moving USDC into some other account doesn't count as reserves
+    let result = send(&mut context, &[deposit(&elsewhere)], &[&depositor]).await;
+    assert_eq!(common::custom_error(result), EceTokenError::InvalidTokenAccount as u32);
+
+    send(&mut context, &[deposit(&treasury.usdc_reserve)], &[&depositor]).await.unwrap();
+    let state: TreasuryState = common::load_state(&mut context, &treasury.address).await;
+    assert_eq!(state.usdc_reserves, 3_000_000);
+    assert_eq!(token_balance(&mut context, &treasury.usdc_reserve).await, 3_000_000);
+}
@@ -0,0 +1,81 @@
+use ece_token::client::{EceTokenClient, VestingBalance};
+use ece_token::state::VestingSchedule;
+use solana_program::pubkey::Pubkey;
+
+const START: i64 = 1_000;
+const CLIFF: i64 = 1_250;
+const END: i64 = 2_000;
+
+fn schedule(total_amount: u64) -> VestingSchedule {
+    VestingSchedule {
+        is_initialized: true,
+        treasury: Pubkey::new_unique(),
+        beneficiary: Pubkey::new_unique(),
+        vesting_id: 0,
+        ece_mint: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        total_amount,
+        released_amount: 0,
+        start_timestamp: START,
+        cliff_timestamp: CLIFF,
+        end_timestamp: END,
+        revocable: true,
+        revoked_at: 0,
+        bump: 255,
+    }
+}
+
+#[test]
+fn nothing_vests_before_cliff_then_linear() {
+    let vesting = schedule(1_000_000);
+
+    assert_eq!(vesting.vested_amount(START - 1), 0);
+    assert_eq!(vesting.vested_amount(CLIFF - 1), 0);
+    // At the cliff the time since start vests at once
+    assert_eq!(vesting.vested_amount(CLIFF), 250_000);
+    assert_eq!(vesting.vested_amount(1_500), 500_000);
+    assert_eq!(vesting.vested_amount(END), 1_000_000);
+    assert_eq!(vesting.vested_amount(i64::MAX), 1_000_000);
+}
+
+#[test]
+fn vesting_rounds_down() {
+    let vesting = schedule(3);
+
+    assert_eq!(vesting.vested_amount(1_500), 1);
+    assert_eq!(vesting.unvested_amount(1_500), 2);
+}
+
+#[test]
+fn releasable_excludes_released() {
+    let mut vesting = schedule(1_000_000);
+    vesting.released_amount = 400_000;
+
+    assert_eq!(
+        EceTokenClient::vesting_balance(&vesting, 1_500),
+        VestingBalance {
+            vested: 500_000,
+            unvested: 500_000,
+            releasable: 100_000,
+        }
+    );
+}
+
+#[test]
+fn revocation_freezes_vested_amount() {
+    let mut vesting = schedule(1_000_000);
+    vesting.total_amount = vesting.vested_amount(1_500);
+    vesting.revoked_at = 1_500;
+
+    assert_eq!(vesting.vested_amount(1_900), 500_000);
+    assert_eq!(vesting.unvested_amount(1_900), 0);
+    assert_eq!(vesting.releasable_amount(1_900), 500_000);
+}
+
+#[test]
+fn schedule_validation() {
+    assert!(VestingSchedule::is_valid_schedule(START, CLIFF, END));
+    assert!(VestingSchedule::is_valid_schedule(START, START, END));
+    assert!(!VestingSchedule::is_valid_schedule(START, END + 1, END));
+    assert!(!VestingSchedule::is_valid_schedule(START, START, START));
+}